@@ -0,0 +1,62 @@
+use common_data::{formats::FormatError, currency::ParseCurrencyError};
+
+#[derive(Debug)]
+pub enum CliError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Format(FormatError),
+    Argument(String),
+    Invalid(usize)
+}
+
+impl CliError {
+    pub fn argument(message: impl Into<String>) -> Self {
+        Self::Argument(message.into())
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Argument(_) => 2,
+            Self::Invalid(_) => 3,
+            _ => 1
+        }
+    }
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{}", e),
+            Self::Json(e) => write!(f, "Invalid table file: {}", e),
+            Self::Format(e) => write!(f, "{}", e),
+            Self::Argument(message) => write!(f, "{}", message),
+            Self::Invalid(count) => write!(f, "{} file(s) failed validation", count)
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<std::io::Error> for CliError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for CliError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
+
+impl From<FormatError> for CliError {
+    fn from(value: FormatError) -> Self {
+        Self::Format(value)
+    }
+}
+
+impl From<ParseCurrencyError> for CliError {
+    fn from(value: ParseCurrencyError) -> Self {
+        Self::Argument(format!("Invalid cost: {}. Use an amount and a coin, e.g. '50 gp'", value))
+    }
+}
\ No newline at end of file
@@ -0,0 +1,326 @@
+mod error;
+mod table_file;
+
+use std::{path::PathBuf, process::ExitCode};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use common_data::{Currency, RollResult, TableData, create_seeded_rng, create_rng};
+
+use crate::{error::CliError, table_file::{InputFormat, OutputFormat, read_table, write_table}};
+
+#[derive(Debug, Parser)]
+#[command(name = "table-roller", version, about = "Roll on random tables without opening the app")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    #[command(about = "Roll random entries from a table")]
+    Roll(RollArgs),
+    #[command(about = "Check table files for problems")]
+    Validate(ValidateArgs),
+    #[command(about = "Convert a table between formats")]
+    Convert(ConvertArgs)
+}
+
+#[derive(Debug, clap::Args)]
+struct RollArgs {
+    #[arg(help = "Table file to roll on, or '-' to read from stdin")]
+    file: PathBuf,
+    #[arg(long, help = "Format of the table file, guessed from the extension by default")]
+    from: Option<InputFormat>,
+    #[arg(short, long, default_value_t = 1, conflicts_with = "cost", help = "Number of entries to roll")]
+    count: usize,
+    #[arg(long, help = "Keep rolling until this budget is spent, e.g. '50 gp'")]
+    cost: Option<String>,
+    #[arg(long, help = "Roll each entry at most once")]
+    no_duplicates: bool,
+    #[arg(long, help = "Treat every entry as equally likely")]
+    ignore_weights: bool,
+    #[arg(long, help = "Seed for repeatable rolls")]
+    seed: Option<u64>,
+    #[arg(short, long, value_enum, default_value_t = ResultFormat::Text, help = "How to print the results")]
+    format: ResultFormat
+}
+
+#[derive(Debug, clap::Args)]
+struct ValidateArgs {
+    #[arg(required = true, help = "Table files to check")]
+    files: Vec<PathBuf>,
+    #[arg(long, help = "Format of the table files, guessed from the extension by default")]
+    from: Option<InputFormat>
+}
+
+#[derive(Debug, clap::Args)]
+struct ConvertArgs {
+    #[arg(help = "Table to convert, or '-' to read from stdin")]
+    input: PathBuf,
+    #[arg(help = "File to write, or '-' to print the result")]
+    output: PathBuf,
+    #[arg(long, help = "Format of the input, guessed from the extension by default")]
+    from: Option<InputFormat>,
+    #[arg(long, help = "Format of the output, guessed from the extension by default")]
+    to: Option<OutputFormat>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ResultFormat {
+    Text,
+    Json
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Roll(args) => roll(args),
+        Command::Validate(args) => validate(args),
+        Command::Convert(args) => convert(args)
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(e.exit_code() as u8)
+        }
+    }
+}
+
+fn roll(args: RollArgs) -> Result<(), CliError> {
+    let table = read_table(&args.file, args.from)?;
+    let results = roll_table(&table, &args)?;
+
+    match args.format {
+        ResultFormat::Json => println!("{}", serde_json::to_string_pretty(&results)?),
+        ResultFormat::Text => print_results(&table, &results)
+    }
+
+    Ok(())
+}
+
+fn roll_table(table: &TableData, args: &RollArgs) -> Result<Vec<RollResult>, CliError> {
+    if table.is_empty() {
+        return Err(CliError::argument(format!("'{}' has no entries to roll", table.name())));
+    }
+
+    let use_weight = table.use_weight() && !args.ignore_weights;
+    let allow_duplicates = !args.no_duplicates;
+    let mut rng = match args.seed {
+        Some(seed) => create_seeded_rng(seed),
+        None => create_rng().map_err(|e| CliError::argument(format!("Could not seed the random number generator: {}", e)))?
+    };
+
+    let results = match &args.cost {
+        Some(cost) => {
+            let cost = cost.parse::<Currency>()?;
+            table.get_random_set_by_cost_with_rng(&mut rng, use_weight, cost, allow_duplicates)
+        },
+        None => table.get_random_set_by_count_with_rng(&mut rng, use_weight, args.count, allow_duplicates)
+    };
+
+    Ok(results)
+}
+
+fn print_results(table: &TableData, results: &[RollResult]) {
+    for result in results {
+        let entry = result.entry();
+        match (result.count(), table.use_cost()) {
+            (1, true) => println!("{} ({})", entry.name(), entry.cost()),
+            (1, false) => println!("{}", entry.name()),
+            (count, true) => println!("{} x {} ({} each)", count, entry.name(), entry.cost()),
+            (count, false) => println!("{} x {}", count, entry.name())
+        }
+    }
+}
+
+fn validate(args: ValidateArgs) -> Result<(), CliError> {
+    let mut failed = 0;
+
+    for file in &args.files {
+        let problems = match read_table(file, args.from) {
+            Ok(table) => get_problems(&table),
+            Err(e) => vec![e.to_string()]
+        };
+
+        if problems.is_empty() {
+            println!("{}: ok", file.display());
+        } else {
+            failed += 1;
+            for problem in problems {
+                println!("{}: {}", file.display(), problem);
+            }
+        }
+    }
+
+    if failed > 0 {
+        Err(CliError::Invalid(failed))
+    } else {
+        Ok(())
+    }
+}
+
+fn get_problems(table: &TableData) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    if table.name().trim().is_empty() {
+        problems.push("the table has no name".to_string());
+    }
+
+    if table.is_empty() {
+        problems.push("the table has no entries".to_string());
+    }
+
+    for (index, entry) in table.iter().enumerate() {
+        if entry.name().trim().is_empty() {
+            problems.push(format!("entry {} has no name", index + 1));
+        }
+
+        if table.use_weight() && entry.weight() == 0 {
+            problems.push(format!("entry {} has a weight of 0 and can never be rolled", index + 1));
+        }
+    }
+
+    problems
+}
+
+fn convert(args: ConvertArgs) -> Result<(), CliError> {
+    let table = read_table(&args.input, args.from)?;
+    write_table(&args.output, args.to, &table)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use common_data::TableEntry;
+
+    use super::*;
+
+    fn table() -> TableData {
+        let mut table = TableData::new(true, true, "Loot", 0);
+        for (name, weight, cost) in [("Coins", 4, Currency::Gold(10)), ("Gem", 1, Currency::Gold(50)), ("gem", 2, Currency::Gold(40)), ("Sword", 3, Currency::Gold(15))] {
+            let mut entry = TableEntry::with_weight(weight, cost);
+            entry.set_name(name);
+            table.push(entry);
+        }
+
+        table
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("table-roller-{}-{}", std::process::id(), name))
+    }
+
+    fn parse(args: &[&str]) -> Command {
+        Cli::try_parse_from(["table-roller"].iter().chain(args)).unwrap().command
+    }
+
+    fn roll_args(args: &[&str]) -> RollArgs {
+        match parse(&[&["roll", "loot.table"], args].concat()) {
+            Command::Roll(args) => args,
+            other => panic!("Unexpected command: {:?}", other)
+        }
+    }
+
+    fn summarise(results: &[RollResult]) -> Vec<(String, u64, usize)> {
+        results.iter().map(|r| (r.entry().name().to_string(), u64::from(r.entry().cost()), r.count())).collect()
+    }
+
+    #[test]
+    fn seeded_count_rolls_are_repeatable() {
+        let table = table();
+        let args = roll_args(&["--count", "20", "--seed", "42"]);
+
+        let results = roll_table(&table, &args).unwrap();
+        assert_eq!(20, results.iter().map(|r| r.count()).sum::<usize>());
+        for _ in 0..10 {
+            assert_eq!(summarise(&results), summarise(&roll_table(&table, &args).unwrap()));
+        }
+    }
+
+    #[test]
+    fn seeded_cost_rolls_are_repeatable() {
+        let table = table();
+        let args = roll_args(&["--cost", "200 gp", "--seed", "7"]);
+
+        let results = roll_table(&table, &args).unwrap();
+        let spent: u64 = results.iter().map(|r| u64::from(r.entry().cost()) * r.count() as u64).sum();
+        assert!(!results.is_empty());
+        assert!(spent <= u64::from(Currency::Gold(200)));
+        for _ in 0..10 {
+            assert_eq!(summarise(&results), summarise(&roll_table(&table, &args).unwrap()));
+        }
+    }
+
+    #[test]
+    fn no_duplicates_stops_when_entries_run_out() {
+        let table = table();
+        let results = roll_table(&table, &roll_args(&["--count", "10", "--no-duplicates", "--seed", "1"])).unwrap();
+
+        assert_eq!(table.len(), results.len());
+        assert!(results.iter().all(|r| r.count() == 1));
+    }
+
+    #[test]
+    fn validate_reports_exit_codes() {
+        let good = temp_path("good.table");
+        let bad = temp_path("bad.table");
+        write_table(&good, None, &table()).unwrap();
+        write_table(&bad, None, &TableData::new(false, true, " ", 0)).unwrap();
+
+        let validate_files = |files: &[&Path]| {
+            let mut args = vec!["validate"];
+            args.extend(files.iter().map(|f| f.to_str().unwrap()));
+            match parse(&args) {
+                Command::Validate(args) => validate(args),
+                other => panic!("Unexpected command: {:?}", other)
+            }
+        };
+
+        assert!(validate_files(&[&good]).is_ok());
+        let missing = temp_path("missing.table");
+        match validate_files(&[&good, &bad, &missing]) {
+            Err(e @ CliError::Invalid(2)) => assert_eq!(3, e.exit_code()),
+            other => panic!("Unexpected result: {:?}", other)
+        }
+
+        let error = roll_table(&TableData::new(false, true, "Empty", 0), &roll_args(&[])).unwrap_err();
+        assert_eq!(2, error.exit_code());
+        assert_eq!(2, read_table(Path::new("loot.unknown"), None).unwrap_err().exit_code());
+        assert_eq!(1, read_table(&missing, None).unwrap_err().exit_code());
+        assert!(Cli::try_parse_from(["table-roller", "roll", "loot.table", "--count", "2", "--cost", "5 gp"]).is_err());
+
+        fs::remove_file(good).unwrap();
+        fs::remove_file(bad).unwrap();
+    }
+
+    #[test]
+    fn convert_round_trips() {
+        let table = table();
+        let source = temp_path("source.table");
+        write_table(&source, None, &table).unwrap();
+
+        for (format, keeps_cost) in [(InputFormat::Table, true), (InputFormat::ShareCode, true), (InputFormat::Csv, true), (InputFormat::Tsv, true), (InputFormat::Foundry, true), (InputFormat::Roll20, false)] {
+            let to = format.to_possible_value().unwrap();
+            let output = temp_path(&format!("converted.{}", to.get_name()));
+            match parse(&["convert", source.to_str().unwrap(), output.to_str().unwrap(), "--to", to.get_name()]) {
+                Command::Convert(args) => convert(args).unwrap(),
+                other => panic!("Unexpected command: {:?}", other)
+            }
+
+            let converted = read_table(&output, Some(format)).unwrap();
+            let names = |t: &TableData| t.iter().map(|e| (e.name().to_string(), e.weight())).collect::<Vec<_>>();
+            assert_eq!(names(&table), names(&converted), "{:?}", format);
+            if keeps_cost {
+                let costs = |t: &TableData| t.iter().map(|e| u64::from(e.cost())).collect::<Vec<_>>();
+                assert_eq!(costs(&table), costs(&converted), "{:?}", format);
+            }
+
+            fs::remove_file(output).unwrap();
+        }
+
+        fs::remove_file(source).unwrap();
+    }
+}
\ No newline at end of file
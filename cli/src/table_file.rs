@@ -0,0 +1,124 @@
+use std::{fs, io::{self, Read, Write}, path::Path};
+
+use clap::ValueEnum;
+use common_data::{FileTableData, TableData, formats::{ImportFormat, ExportFormat, share_code::{encode_share_code, decode_share_code}}};
+
+use crate::error::CliError;
+
+const STDIO_PATH: &str = "-";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InputFormat {
+    Table,
+    Csv,
+    Tsv,
+    Foundry,
+    Roll20,
+    ShareCode
+}
+
+impl InputFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match get_extension(path)?.as_str() {
+            "table" => Some(Self::Table),
+            "csv" => Some(Self::Csv),
+            "tsv" | "txt" => Some(Self::Tsv),
+            _ => None
+        }
+    }
+
+    pub fn parse(&self, text: &str) -> Result<TableData, CliError> {
+        match self {
+            Self::Table => Ok(serde_json::from_str::<FileTableData>(text)?.into_table_data(0, None)),
+            Self::ShareCode => Ok(decode_share_code(text)?.into_table_data(0, None)),
+            Self::Csv => Ok(ImportFormat::Csv.import_table(text)?),
+            Self::Tsv => Ok(ImportFormat::Tsv.import_table(text)?),
+            Self::Foundry => Ok(ImportFormat::FoundryVtt.import_table(text)?),
+            Self::Roll20 => Ok(ImportFormat::Roll20.import_table(text)?)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Csv,
+    Tsv,
+    Markdown,
+    Html,
+    Foundry,
+    Roll20,
+    ShareCode
+}
+
+impl OutputFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match get_extension(path)?.as_str() {
+            "table" => Some(Self::Table),
+            "csv" => Some(Self::Csv),
+            "tsv" | "txt" => Some(Self::Tsv),
+            "md" => Some(Self::Markdown),
+            "html" | "htm" => Some(Self::Html),
+            _ => None
+        }
+    }
+
+    pub fn render(&self, table: &TableData) -> Result<String, CliError> {
+        match self {
+            Self::Table => Ok(serde_json::to_string_pretty(&table.to_file_data())?),
+            Self::ShareCode => Ok(encode_share_code(&table.to_file_data())?),
+            Self::Csv => Ok(ExportFormat::Csv.export_table(table)?),
+            Self::Tsv => Ok(ExportFormat::Tsv.export_table(table)?),
+            Self::Markdown => Ok(ExportFormat::Markdown.export_table(table)?),
+            Self::Html => Ok(ExportFormat::Html.export_table(table)?),
+            Self::Foundry => Ok(ExportFormat::FoundryVtt.export_table(table)?),
+            Self::Roll20 => Ok(ExportFormat::Roll20.export_table(table)?)
+        }
+    }
+}
+
+pub fn read_table(path: &Path, format: Option<InputFormat>) -> Result<TableData, CliError> {
+    let format = format.or_else(|| InputFormat::from_path(path))
+        .ok_or_else(|| CliError::argument(format!("Cannot tell the format of {:?}, use --from to choose one", path)))?;
+    let text = read_text(path)?;
+    let mut table = format.parse(&text)?;
+
+    if matches!(format, InputFormat::Csv | InputFormat::Tsv) {
+        if let Some(name) = path.file_stem().filter(|_| path != Path::new(STDIO_PATH)) {
+            table.set_name(name.to_string_lossy());
+        }
+    }
+
+    Ok(table)
+}
+
+pub fn write_table(path: &Path, format: Option<OutputFormat>, table: &TableData) -> Result<(), CliError> {
+    let format = format.or_else(|| OutputFormat::from_path(path))
+        .ok_or_else(|| CliError::argument(format!("Cannot tell the format of {:?}, use --to to choose one", path)))?;
+    let mut contents = format.render(table)?;
+    if !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+
+    if path == Path::new(STDIO_PATH) {
+        io::stdout().write_all(contents.as_bytes())?;
+    } else {
+        fs::write(path, contents)?;
+    }
+
+    Ok(())
+}
+
+fn read_text(path: &Path) -> Result<String, CliError> {
+    if path == Path::new(STDIO_PATH) {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        Ok(text)
+    } else {
+        Ok(fs::read_to_string(path)?)
+    }
+}
+
+fn get_extension(path: &Path) -> Option<String> {
+    path.extension().map(|extension| extension.to_string_lossy().to_lowercase())
+}
\ No newline at end of file
@@ -0,0 +1,117 @@
+use std::{ops::{Add, Sub, AddAssign, SubAssign}, iter::Sum};
+
+use serde::{Serialize, Deserialize};
+
+use crate::{BackendError, Currency};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Balance(i64);
+
+impl Balance {
+    pub fn from_copper(copper: i64) -> Self {
+        Self(copper)
+    }
+
+    pub fn copper(&self) -> i64 {
+        self.0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.0 < 0
+    }
+
+    pub fn magnitude(&self) -> Currency {
+        Currency::from(self.0.unsigned_abs())
+    }
+
+    /// Turns income into an expense or back. Fails for the most negative balance, which has no positive counterpart.
+    pub fn checked_neg(self) -> Result<Self, BackendError> {
+        self.0.checked_neg()
+            .map(Self)
+            .ok_or_else(|| BackendError::validation_error("amount", format!("{} copper is too large to negate", self.0)))
+    }
+
+    pub fn split(self, parts: usize) -> Vec<Balance> {
+        if parts == 0 {
+            return Vec::new();
+        }
+
+        let parts_i64 = parts as i64;
+        let share = self.0 / parts_i64;
+        let remainder = (self.0 % parts_i64).unsigned_abs() as usize;
+        let step = self.0.signum();
+
+        (0..parts)
+            .map(|index| if index < remainder { Self(share + step) } else { Self(share) })
+            .collect()
+    }
+}
+
+impl From<Currency> for Balance {
+    fn from(value: Currency) -> Self {
+        let copper = u64::from(value);
+        match i64::try_from(copper) {
+            Ok(copper) => Self(copper),
+            Err(_) => {
+                log::error!("Overflow occurred when converting '{}' to a balance", value);
+                panic!("Overflow");
+            }
+        }
+    }
+}
+
+impl Add<Balance> for Balance {
+    type Output = Balance;
+
+    fn add(self, rhs: Balance) -> Self::Output {
+        match self.0.checked_add(rhs.0) {
+            Some(res) => Self(res),
+            None => {
+                log::error!("Overflow occurred when adding '{}' to '{}'", rhs.0, self.0);
+                panic!("Overflow");
+            }
+        }
+    }
+}
+
+impl AddAssign<Balance> for Balance {
+    fn add_assign(&mut self, rhs: Balance) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<Balance> for Balance {
+    type Output = Balance;
+
+    fn sub(self, rhs: Balance) -> Self::Output {
+        match self.0.checked_sub(rhs.0) {
+            Some(res) => Self(res),
+            None => {
+                log::error!("Overflow occurred when subtracting '{}' from '{}'", rhs.0, self.0);
+                panic!("Overflow");
+            }
+        }
+    }
+}
+
+impl SubAssign<Balance> for Balance {
+    fn sub_assign(&mut self, rhs: Balance) {
+        *self = *self - rhs;
+    }
+}
+
+impl Sum for Balance {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |acc, item| acc + item)
+    }
+}
+
+impl std::fmt::Display for Balance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_negative() {
+            write!(f, "-{}", self.magnitude())
+        } else {
+            write!(f, "{}", self.magnitude())
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,120 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+use crate::{FileTableData, IdNamePair, TableData, ledger::Ledger};
+
+pub const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleTable {
+    id: Uuid,
+    #[serde(flatten)]
+    data: FileTableData
+}
+
+impl BundleTable {
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn data(&self) -> &FileTableData {
+        &self.data
+    }
+}
+
+/// A whole workspace in one file: every open table, in tab order, and optionally the party ledger.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CampaignBundle {
+    version: u32,
+    name: String,
+    tables: Vec<BundleTable>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ledger: Option<Ledger>
+}
+
+impl CampaignBundle {
+    pub fn new<'a>(name: impl Into<String>, tables: impl IntoIterator<Item = &'a TableData>, ledger: Option<Ledger>) -> Self {
+        let mut tables = tables.into_iter().collect::<Vec<_>>();
+        tables.sort_by_key(|t| t.order());
+
+        Self {
+            version: BUNDLE_VERSION,
+            name: name.into(),
+            tables: tables.into_iter()
+                .map(|t| BundleTable { id: t.id(), data: t.to_file_data() })
+                .collect(),
+            ledger
+        }
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn is_supported(&self) -> bool {
+        self.version <= BUNDLE_VERSION
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn tables(&self) -> &Vec<BundleTable> {
+        &self.tables
+    }
+
+    pub fn ledger(&self) -> Option<&Ledger> {
+        self.ledger.as_ref()
+    }
+
+    pub fn summaries(&self) -> Vec<IdNamePair> {
+        self.tables.iter()
+            .map(|t| IdNamePair::new(t.id, t.data.name().to_string()))
+            .collect()
+    }
+
+    pub fn extract_table(&self, id: Uuid, order: usize) -> Option<TableData> {
+        self.tables.iter()
+            .find(|t| t.id == id)
+            .map(|t| t.data.clone().into_table_data(order, None))
+    }
+
+    pub fn into_workspace(self) -> (Vec<TableData>, Option<Ledger>) {
+        let tables = self.tables.into_iter()
+            .enumerate()
+            .map(|(order, t)| t.data.into_table_data_with_id(t.id, order, None))
+            .collect();
+
+        (tables, self.ledger)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Currency, TableEntry};
+
+    use super::*;
+
+    #[test]
+    fn bundle_preserves_order_and_ids() {
+        let mut first = TableData::new(false, false, "Encounters", 1);
+        first.push(TableEntry::new(Currency::Copper(1)));
+        let second = TableData::new(true, true, "Loot", 0);
+
+        let bundle = CampaignBundle::new("Campaign", [&first, &second], None);
+        let json = serde_json::to_string(&bundle).unwrap();
+        let bundle = serde_json::from_str::<CampaignBundle>(&json).unwrap();
+
+        assert!(bundle.is_supported());
+        assert_eq!(vec!["Loot", "Encounters"], bundle.summaries().iter().map(|s| s.name()).collect::<Vec<_>>());
+
+        let extracted = bundle.extract_table(first.id(), 5).unwrap();
+        assert_ne!(first.id(), extracted.id());
+        assert_eq!(first.entries(), extracted.entries());
+
+        let (tables, ledger) = bundle.into_workspace();
+        assert!(ledger.is_none());
+        assert_eq!((second.id(), 0), (tables[0].id(), tables[0].order()));
+        assert_eq!((first.id(), 1), (tables[1].id(), tables[1].order()));
+    }
+}
\ No newline at end of file
@@ -343,6 +343,20 @@ impl Currency {
         }
     }
 
+    pub fn to_denominations(self) -> Vec<Currency> {
+        let copper = self.to_copper().amount();
+
+        vec![
+            Self::Platinum(copper / 1000),
+            Self::Gold(copper % 1000 / 100),
+            Self::Silver(copper % 100 / 10),
+            Self::Copper(copper % 10),
+        ]
+        .into_iter()
+        .filter(|c| c.amount() > 0)
+        .collect()
+    }
+
     pub fn amount(&self) -> u64 {
         match self {
             Self::Platinum(amount) => *amount,
@@ -483,6 +497,13 @@ mod tests {
         assert_eq!(Currency::Gold(19), Currency::Gold(19).to_largest_denomination());
     }
 
+    #[test]
+    fn currency_to_denominations() {
+        assert_eq!(vec![Currency::Platinum(1), Currency::Gold(2), Currency::Copper(4)], Currency::Copper(1204).to_denominations());
+        assert_eq!(vec![Currency::Silver(5)], Currency::Silver(5).to_denominations());
+        assert!(Currency::Copper(0).to_denominations().is_empty());
+    }
+
     #[test]
     fn test_currency_serialization() {
         test_currency_serialization_test_base(Currency::Copper(1));
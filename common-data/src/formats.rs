@@ -0,0 +1,174 @@
+pub mod delimited;
+pub mod markup;
+pub mod paste;
+pub mod share_code;
+pub mod vtt;
+
+use serde::{Serialize, Deserialize};
+
+use crate::TableData;
+
+use self::{delimited::{Delimiter, DelimitedData, export_delimited}, markup::Markup, vtt::{import_foundry, export_foundry, import_roll20, export_roll20}};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FormatError {
+    Parse(String),
+    MissingColumn(String),
+    InvalidValue { row: usize, column: String, message: String },
+    InvalidShareCode(String),
+    UnsupportedShareCodeVersion(u8)
+}
+
+impl std::fmt::Display for FormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(message) => write!(f, "Parse error: {}", message),
+            Self::MissingColumn(column) => write!(f, "No column was selected for '{}'", column),
+            Self::InvalidValue { row, column, message } => write!(f, "Invalid value in row {} column '{}': {}", row, column, message),
+            Self::InvalidShareCode(message) => write!(f, "Invalid share code: {}", message),
+            Self::UnsupportedShareCodeVersion(version) => write!(f, "The share code uses format version {}, which this version of the app cannot read", version)
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+/// The contents of an imported file. Delimited files still need their columns mapped before they become a table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportedFile {
+    Table(TableData),
+    Delimited(DelimitedData)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportFormat {
+    Csv,
+    Tsv,
+    FoundryVtt,
+    Roll20
+}
+
+impl ImportFormat {
+    pub fn get_values() -> Vec<Self> {
+        vec![
+            Self::Csv,
+            Self::Tsv,
+            Self::FoundryVtt,
+            Self::Roll20
+        ]
+    }
+
+    pub fn extensions(&self) -> Vec<&'static str> {
+        match self {
+            Self::Csv => vec!["csv"],
+            Self::Tsv => vec!["tsv", "txt"],
+            Self::FoundryVtt | Self::Roll20 => vec!["json"]
+        }
+    }
+
+    pub fn delimiter(&self) -> Option<Delimiter> {
+        match self {
+            Self::Csv => Some(Delimiter::Comma),
+            Self::Tsv => Some(Delimiter::Tab),
+            Self::FoundryVtt | Self::Roll20 => None
+        }
+    }
+
+    pub fn import_file(&self, text: &str) -> Result<ImportedFile, FormatError> {
+        match self.delimiter() {
+            Some(delimiter) => Ok(ImportedFile::Delimited(DelimitedData::parse(text, delimiter)?)),
+            None => Ok(ImportedFile::Table(self.import_table(text)?))
+        }
+    }
+
+    pub fn import_table(&self, text: &str) -> Result<TableData, FormatError> {
+        match self {
+            Self::FoundryVtt => import_foundry(text),
+            Self::Roll20 => import_roll20(text),
+            Self::Csv | Self::Tsv => {
+                let data = DelimitedData::parse(text, self.delimiter().unwrap_or(Delimiter::Comma))?;
+                let mapping = data.guess_mapping();
+                let mut table = TableData::new(mapping.cost().is_some(), mapping.weight().is_some(), "Imported table", 0);
+                table.set_entries(data.to_entries(&mapping)?);
+                Ok(table)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Csv => write!(f, "CSV"),
+            Self::Tsv => write!(f, "TSV"),
+            Self::FoundryVtt => write!(f, "Foundry VTT"),
+            Self::Roll20 => write!(f, "Roll20")
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExportFormat {
+    Csv,
+    Tsv,
+    Markdown,
+    Html,
+    FoundryVtt,
+    Roll20
+}
+
+impl ExportFormat {
+    pub fn get_values() -> Vec<Self> {
+        vec![
+            Self::Csv,
+            Self::Tsv,
+            Self::Markdown,
+            Self::Html,
+            Self::FoundryVtt,
+            Self::Roll20
+        ]
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Tsv => "tsv",
+            Self::Markdown => "md",
+            Self::Html => "html",
+            Self::FoundryVtt | Self::Roll20 => "json"
+        }
+    }
+
+    pub fn export_table(&self, table: &TableData) -> Result<String, FormatError> {
+        match self {
+            Self::Csv => export_delimited(table, Delimiter::Comma),
+            Self::Tsv => export_delimited(table, Delimiter::Tab),
+            Self::Markdown => Ok(Markup::Markdown.render_table(table)),
+            Self::Html => Ok(Markup::Html.render_table(table)),
+            Self::FoundryVtt => export_foundry(table),
+            Self::Roll20 => export_roll20(table)
+        }
+    }
+}
+
+impl From<Markup> for ExportFormat {
+    fn from(value: Markup) -> Self {
+        match value {
+            Markup::Markdown => Self::Markdown,
+            Markup::Html => Self::Html
+        }
+    }
+}
+
+impl std::fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Csv => write!(f, "CSV"),
+            Self::Tsv => write!(f, "TSV"),
+            Self::Markdown => write!(f, "Markdown"),
+            Self::Html => write!(f, "HTML"),
+            Self::FoundryVtt => write!(f, "Foundry VTT"),
+            Self::Roll20 => write!(f, "Roll20")
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,295 @@
+use std::str::FromStr;
+
+use serde::{Serialize, Deserialize};
+
+use crate::{Currency, TableData, TableEntry, formats::FormatError};
+
+const NAME_HEADERS: [&str; 4] = ["name", "entry", "item", "result"];
+const WEIGHT_HEADERS: [&str; 4] = ["weight", "weights", "chance", "frequency"];
+const COST_HEADERS: [&str; 4] = ["cost", "price", "value", "worth"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Delimiter {
+    Comma,
+    Tab
+}
+
+impl Delimiter {
+    fn as_byte(&self) -> u8 {
+        match self {
+            Self::Comma => b',',
+            Self::Tab => b'\t'
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnMapping {
+    name: Option<usize>,
+    weight: Option<usize>,
+    cost: Option<usize>,
+    extra: Vec<usize>
+}
+
+impl ColumnMapping {
+    pub fn name(&self) -> Option<usize> {
+        self.name
+    }
+
+    pub fn set_name(&mut self, column: Option<usize>) {
+        self.name = column;
+    }
+
+    pub fn weight(&self) -> Option<usize> {
+        self.weight
+    }
+
+    pub fn set_weight(&mut self, column: Option<usize>) {
+        self.weight = column;
+    }
+
+    pub fn cost(&self) -> Option<usize> {
+        self.cost
+    }
+
+    pub fn set_cost(&mut self, column: Option<usize>) {
+        self.cost = column;
+    }
+
+    pub fn extra(&self) -> &Vec<usize> {
+        &self.extra
+    }
+
+    pub fn set_extra(&mut self, column: usize, include: bool) {
+        self.extra.retain(|c| *c != column);
+
+        if include {
+            self.extra.push(column);
+            self.extra.sort();
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelimitedData {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>
+}
+
+impl DelimitedData {
+    pub fn parse(text: &str, delimiter: Delimiter) -> Result<Self, FormatError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter.as_byte())
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(text.as_bytes());
+
+        let headers = reader.headers()
+            .map_err(|e| FormatError::Parse(e.to_string()))?
+            .iter()
+            .map(|h| h.to_string())
+            .collect::<Vec<_>>();
+
+        let mut rows = Vec::new();
+        for record in reader.records() {
+            let record = record.map_err(|e| FormatError::Parse(e.to_string()))?;
+            rows.push(record.iter().map(|v| v.to_string()).collect());
+        }
+
+        Ok(Self { headers, rows })
+    }
+
+    pub fn headers(&self) -> &Vec<String> {
+        &self.headers
+    }
+
+    pub fn rows(&self) -> &Vec<Vec<String>> {
+        &self.rows
+    }
+
+    pub fn guess_mapping(&self) -> ColumnMapping {
+        let find = |names: &[&str]| self.headers.iter().position(|h| names.contains(&h.to_lowercase().as_str()));
+
+        let mut mapping = ColumnMapping {
+            name: find(&NAME_HEADERS).or(if self.headers.is_empty() { None } else { Some(0) }),
+            weight: find(&WEIGHT_HEADERS),
+            cost: find(&COST_HEADERS),
+            extra: Vec::new()
+        };
+
+        mapping.extra = (0..self.headers.len())
+            .filter(|c| Some(*c) != mapping.name && Some(*c) != mapping.weight && Some(*c) != mapping.cost)
+            .collect();
+
+        mapping
+    }
+
+    pub fn to_entries(&self, mapping: &ColumnMapping) -> Result<Vec<TableEntry>, FormatError> {
+        self.entries_from_rows(&self.rows, mapping)
+    }
+
+    pub fn preview(&self, mapping: &ColumnMapping, count: usize) -> Result<Vec<TableEntry>, FormatError> {
+        self.entries_from_rows(&self.rows[..count.min(self.rows.len())], mapping)
+    }
+
+    fn entries_from_rows(&self, rows: &[Vec<String>], mapping: &ColumnMapping) -> Result<Vec<TableEntry>, FormatError> {
+        let name_column = mapping.name.ok_or(FormatError::MissingColumn("Name".to_string()))?;
+        let mut entries = Vec::with_capacity(rows.len());
+
+        for (index, row) in rows.iter().enumerate() {
+            let row_number = index + 2;
+            let cell = |column: Option<usize>| column.and_then(|c| row.get(c)).map(|v| v.as_str()).unwrap_or("");
+
+            let name = cell(Some(name_column));
+            if name.is_empty() {
+                continue;
+            }
+
+            let weight = parse_weight(cell(mapping.weight))
+                .map_err(|message| FormatError::InvalidValue { row: row_number, column: self.header_name(mapping.weight), message })?;
+
+            let cost = parse_cost(cell(mapping.cost))
+                .map_err(|message| FormatError::InvalidValue { row: row_number, column: self.header_name(mapping.cost), message })?;
+
+            let mut entry = TableEntry::with_weight(weight, cost);
+            entry.set_name(name);
+
+            for column in mapping.extra.iter() {
+                let value = cell(Some(*column));
+                if !value.is_empty() {
+                    entry.set_field(self.header_name(Some(*column)), value);
+                }
+            }
+
+            entries.push(entry);
+        }
+
+        Ok(entries)
+    }
+
+    fn header_name(&self, column: Option<usize>) -> String {
+        column.and_then(|c| self.headers.get(c))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+pub fn parse_weight(value: &str) -> Result<usize, String> {
+    if value.is_empty() {
+        return Ok(1);
+    }
+
+    match usize::from_str(value) {
+        Ok(0) => Err("Weight must be at least 1".to_string()),
+        Ok(weight) => Ok(weight),
+        Err(e) => Err(e.to_string())
+    }
+}
+
+pub fn parse_cost(value: &str) -> Result<Currency, String> {
+    if value.is_empty() {
+        return Ok(Currency::Copper(1));
+    }
+
+    if let Ok(copper) = u64::from_str(value) {
+        return Ok(Currency::from(copper));
+    }
+
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (amount, denomination) = value.split_at(split);
+
+    Currency::from_str(&format!("{} {}", amount, denomination.trim()))
+        .map_err(|_| format!("'{}' is not a valid cost", value))
+}
+
+pub fn export_delimited(table: &TableData, delimiter: Delimiter) -> Result<String, FormatError> {
+    let fields = table.field_names();
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter.as_byte())
+        .from_writer(Vec::new());
+
+    let mut headers = vec!["Name".to_string()];
+    if table.use_weight() {
+        headers.push("Weight".to_string());
+    }
+
+    if table.use_cost() {
+        headers.push("Cost".to_string());
+    }
+
+    headers.extend(fields.iter().cloned());
+    writer.write_record(&headers).map_err(|e| FormatError::Parse(e.to_string()))?;
+
+    for entry in table.iter() {
+        let mut record = vec![entry.name().to_string()];
+        if table.use_weight() {
+            record.push(entry.weight().to_string());
+        }
+
+        if table.use_cost() {
+            record.push(entry.cost().to_string());
+        }
+
+        record.extend(fields.iter().map(|f| entry.field(f).unwrap_or("").to_string()));
+        writer.write_record(&record).map_err(|e| FormatError::Parse(e.to_string()))?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| FormatError::Parse(e.to_string()))?;
+    String::from_utf8(bytes).map_err(|e| FormatError::Parse(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn import_maps_columns() {
+        let text = "Item,Weight,Price,Rarity\nLongsword,3,15 gp,Common\nDriftglobe,1,750gp,Uncommon\n,1,1 cp,\n";
+        let data = DelimitedData::parse(text, Delimiter::Comma).unwrap();
+        let mapping = data.guess_mapping();
+
+        assert_eq!(Some(0), mapping.name());
+        assert_eq!(Some(1), mapping.weight());
+        assert_eq!(Some(2), mapping.cost());
+        assert_eq!(&vec![3], mapping.extra());
+
+        let entries = data.to_entries(&mapping).unwrap();
+        assert_eq!(2, entries.len());
+        assert_eq!(3, entries[0].weight());
+        assert_eq!(Currency::Gold(15), entries[0].cost());
+        assert_eq!(Currency::Gold(750), entries[1].cost());
+        assert_eq!(Some("Uncommon"), entries[1].field("Rarity"));
+    }
+
+    #[test]
+    fn import_reports_invalid_rows() {
+        let text = "Name\tWeight\nGoblin\tlots\n";
+        let data = DelimitedData::parse(text, Delimiter::Tab).unwrap();
+
+        match data.to_entries(&data.guess_mapping()) {
+            Err(FormatError::InvalidValue { row, column, .. }) => {
+                assert_eq!(2, row);
+                assert_eq!("Weight", column);
+            },
+            other => panic!("Unexpected result: {:?}", other)
+        }
+    }
+
+    #[test]
+    fn export_round_trips() {
+        let mut table = TableData::new(true, true, "Loot", 0);
+        let mut entry = TableEntry::with_weight(2, Currency::Silver(5));
+        entry.set_name("Torch, bundle");
+        entry.set_field("Notes", "Burns for an hour");
+        table.push(entry);
+
+        let text = export_delimited(&table, Delimiter::Comma).unwrap();
+        let data = DelimitedData::parse(&text, Delimiter::Comma).unwrap();
+        let mut entries = data.to_entries(&data.guess_mapping()).unwrap();
+        for (imported, original) in entries.iter_mut().zip(table.entries()) {
+            imported.set_id(original.id());
+        }
+
+        assert_eq!(table.entries(), &entries);
+    }
+}
\ No newline at end of file
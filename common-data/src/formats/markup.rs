@@ -0,0 +1,255 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{Currency, RollResult, TableData};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Markup {
+    Markdown,
+    Html
+}
+
+impl Markup {
+    pub fn get_values() -> Vec<Self> {
+        vec![
+            Self::Markdown,
+            Self::Html
+        ]
+    }
+
+    pub fn render_table(&self, table: &TableData) -> String {
+        if table.is_empty() {
+            return match self {
+                Self::Markdown => format!("### {}\n\n_This table has no entries._\n", escape_markdown(table.name())),
+                Self::Html => format!("<h3>{}</h3>\n<p>This table has no entries.</p>\n", escape_html(table.name()))
+            };
+        }
+
+        let fields = table.field_names();
+        let mut headers = vec![format!("d{}", table.iter().map(|e| table.roll_weight(e)).sum::<usize>()), "Result".to_string()];
+        if table.use_cost() {
+            headers.push("Cost".to_string());
+        }
+
+        headers.extend(fields.iter().cloned());
+
+        let rows = die_ranges(table)
+            .into_iter()
+            .zip(table.iter())
+            .map(|((low, high), entry)| {
+                let mut row = vec![format_range(low, high), entry.name().to_string()];
+                if table.use_cost() {
+                    row.push(entry.cost().to_string());
+                }
+
+                row.extend(fields.iter().map(|f| entry.field(f).unwrap_or("").to_string()));
+                row
+            })
+            .collect::<Vec<_>>();
+
+        match self {
+            Self::Markdown => markdown_table(table.name(), &headers, &rows),
+            Self::Html => html_table(table.name(), &headers, &rows)
+        }
+    }
+
+    pub fn render_results(&self, title: &str, results: &[RollResult], show_cost: bool) -> String {
+        let total = Currency::from(results.iter().fold(0, |total: u64, r| total.saturating_add(result_value(r))));
+        let lines = results.iter()
+            .map(|r| {
+                let item_total = Currency::from(result_value(r));
+                let cost = if show_cost { format!(" ({})", item_total) } else { String::new() };
+                (format!("{}x", r.count()), r.entry().name().to_string(), cost)
+            })
+            .collect::<Vec<_>>();
+
+        match self {
+            Self::Markdown => {
+                let mut output = format!("### {}\n\n", escape_markdown(title));
+                for (count, name, cost) in lines {
+                    output.push_str(&format!("- {} **{}**{}\n", count, escape_markdown(&name), cost));
+                }
+
+                if show_cost {
+                    output.push_str(&format!("\n**Total value:** {}\n", total));
+                }
+
+                output
+            },
+            Self::Html => {
+                let mut output = format!("<h3>{}</h3>\n<ul>\n", escape_html(title));
+                for (count, name, cost) in lines {
+                    output.push_str(&format!("  <li>{} <strong>{}</strong>{}</li>\n", count, escape_html(&name), cost));
+                }
+
+                output.push_str("</ul>\n");
+                if show_cost {
+                    output.push_str(&format!("<p><strong>Total value:</strong> {}</p>\n", total));
+                }
+
+                output
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Markup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Markdown => write!(f, "Markdown"),
+            Self::Html => write!(f, "HTML")
+        }
+    }
+}
+
+/// The value of every item in a result. Saturates rather than overflowing, since this is only ever displayed.
+fn result_value(result: &RollResult) -> u64 {
+    u64::from(result.entry().cost()).saturating_mul(result.count() as u64)
+}
+
+pub fn die_ranges(table: &TableData) -> Vec<(usize, usize)> {
+    let mut low = 1;
+
+    table.iter()
+        .map(|entry| {
+            let weight = table.roll_weight(entry);
+            let range = (low, low + weight - 1);
+            low += weight;
+            range
+        })
+        .collect()
+}
+
+fn format_range(low: usize, high: usize) -> String {
+    if low == high {
+        low.to_string()
+    } else {
+        format!("{}–{}", low, high)
+    }
+}
+
+fn markdown_table(name: &str, headers: &[String], rows: &[Vec<String>]) -> String {
+    let format_row = |cells: &[String]| format!("| {} |\n", cells.iter().map(|c| escape_markdown(c)).collect::<Vec<_>>().join(" | "));
+
+    let mut output = format!("### {}\n\n", escape_markdown(name));
+    output.push_str(&format_row(headers));
+    output.push_str(&format!("|{}\n", headers.iter().map(|_| "---|").collect::<String>()));
+
+    for row in rows {
+        output.push_str(&format_row(row));
+    }
+
+    output
+}
+
+fn html_table(name: &str, headers: &[String], rows: &[Vec<String>]) -> String {
+    let format_row = |tag: &str, cells: &[String]| format!(
+        "    <tr>{}</tr>\n",
+        cells.iter().map(|c| format!("<{0}>{1}</{0}>", tag, escape_html(c))).collect::<String>()
+    );
+
+    let mut output = format!("<table>\n  <caption>{}</caption>\n  <thead>\n", escape_html(name));
+    output.push_str(&format_row("th", headers));
+    output.push_str("  </thead>\n  <tbody>\n");
+
+    for row in rows {
+        output.push_str(&format_row("td", row));
+    }
+
+    output.push_str("  </tbody>\n</table>\n");
+    output
+}
+
+fn escape_markdown(value: &str) -> String {
+    value.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace('*', "\\*")
+        .replace('_', "\\_")
+}
+
+fn escape_html(value: &str) -> String {
+    value.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TableEntry;
+
+    use super::*;
+
+    fn table() -> TableData {
+        let mut table = TableData::new(true, true, "Loot", 0);
+        for (name, weight, cost) in [("Gold | coins", 3, Currency::Gold(10)), ("Gem", 1, Currency::Gold(50)), ("Sword", 2, Currency::Gold(15))] {
+            let mut entry = TableEntry::with_weight(weight, cost);
+            entry.set_name(name);
+            table.push(entry);
+        }
+
+        table
+    }
+
+    #[test]
+    fn table_renders_die_ranges() {
+        let table = table();
+        assert_eq!(vec![(1, 3), (4, 4), (5, 6)], die_ranges(&table));
+
+        let markdown = Markup::Markdown.render_table(&table);
+        assert!(markdown.contains("| d6 | Result | Cost |\n|---|---|---|\n"));
+        assert!(markdown.contains("| 1–3 | Gold \\| coins | 10 gp |"));
+        assert!(markdown.contains("| 4 | Gem | 50 gp |"));
+
+        let html = Markup::Html.render_table(&table);
+        assert!(html.contains("<tr><td>5–6</td><td>Sword</td><td>15 gp</td></tr>"));
+    }
+
+    #[test]
+    fn unweighted_table_renders_one_face_per_entry() {
+        let mut table = table();
+        table.set_use_weight(false);
+        assert_eq!(vec![(1, 1), (2, 2), (3, 3)], die_ranges(&table));
+
+        let markdown = Markup::Markdown.render_table(&table);
+        assert!(markdown.contains("| d3 | Result | Cost |\n"));
+        assert!(markdown.contains("| 1 | Gold \\| coins | 10 gp |"));
+        assert!(markdown.contains("| 3 | Sword | 15 gp |"));
+    }
+
+    #[test]
+    fn empty_table_renders_without_a_die_column() {
+        let table = TableData::new(true, true, "Nothing", 0);
+
+        let markdown = Markup::Markdown.render_table(&table);
+        assert_eq!("### Nothing\n\n_This table has no entries._\n", markdown);
+
+        let html = Markup::Html.render_table(&table);
+        assert!(!html.contains("d0"));
+        assert!(html.contains("<p>This table has no entries.</p>"));
+    }
+
+    #[test]
+    fn results_render_as_loot_list() {
+        let results = table().iter()
+            .take(2)
+            .map(|e| RollResult::new(2, e.clone()))
+            .collect::<Vec<_>>();
+
+        let markdown = Markup::Markdown.render_results("Hoard", &results, true);
+        assert!(markdown.contains("- 2x **Gem** (10 pp)\n"));
+        assert!(markdown.contains("**Total value:** 12 pp"));
+
+        let html = Markup::Html.render_results("<Hoard>", &results, false);
+        assert!(html.starts_with("<h3>&lt;Hoard&gt;</h3>"));
+        assert!(html.contains("<li>2x <strong>Gem</strong></li>"));
+        assert!(!html.contains("Total value"));
+    }
+
+    #[test]
+    fn huge_results_do_not_overflow() {
+        let results = vec![RollResult::new(usize::MAX, TableEntry::new(Currency::Copper(u64::MAX)))];
+
+        let markdown = Markup::Markdown.render_results("Hoard", &results, true);
+        assert!(markdown.contains("**Total value:**"));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,187 @@
+use std::str::FromStr;
+
+use regex::Regex;
+
+use crate::{Currency, TableData, TableEntry, formats::FormatError};
+
+const RANGE_PATTERN: &str = r"^(\d+)\s*(?:-|–|—|to)\s*(\d+)\s*[.):]?\s+(.+)$";
+const NUMBERED_PATTERN: &str = r"^(\d+)\s*[.):]?\s+(.+)$";
+const BULLET_PATTERN: &str = r"^[-*+•]\s+(.+)$";
+const DIE_PATTERN: &str = r"^(?i)\d*d\d+$";
+const DEFAULT_NAME: &str = "Pasted table";
+
+struct Patterns {
+    range: Regex,
+    numbered: Regex,
+    bullet: Regex,
+    die: Regex
+}
+
+impl Patterns {
+    fn new() -> Result<Self, FormatError> {
+        let compile = |pattern: &str| Regex::new(pattern).map_err(|e| FormatError::Parse(e.to_string()));
+
+        Ok(Self {
+            range: compile(RANGE_PATTERN)?,
+            numbered: compile(NUMBERED_PATTERN)?,
+            bullet: compile(BULLET_PATTERN)?,
+            die: compile(DIE_PATTERN)?
+        })
+    }
+}
+
+enum Line {
+    Entry(TableEntry),
+    Header(Vec<String>),
+    Separator,
+    Text(String)
+}
+
+pub fn parse_pasted_table(text: &str) -> Result<TableData, FormatError> {
+    let patterns = Patterns::new()?;
+    let mut title = None;
+    let mut headers = Vec::<String>::new();
+    let mut entries = Vec::<TableEntry>::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let row = index + 1;
+        let parsed = if line.starts_with('|') || line.contains(" | ") {
+            parse_pipe_row(&patterns, line, row, !entries.is_empty(), &headers)?
+        } else {
+            parse_line(&patterns, line, row)?
+        };
+
+        match parsed {
+            Line::Entry(entry) => entries.push(entry),
+            Line::Header(cells) => headers = cells,
+            Line::Separator => {},
+            Line::Text(text) => match entries.last_mut() {
+                Some(entry) => {
+                    let name = format!("{} {}", entry.name(), text);
+                    entry.set_name(name);
+                },
+                None => title = Some(text)
+            }
+        }
+    }
+
+    if entries.is_empty() {
+        return Err(FormatError::Parse("No table entries were recognised".to_string()));
+    }
+
+    let use_weight = entries.iter().any(|e| e.weight() != 1);
+    let mut table = TableData::new(false, use_weight, title.as_deref().unwrap_or(DEFAULT_NAME), 0);
+    table.set_entries(entries);
+    Ok(table)
+}
+
+fn parse_line(patterns: &Patterns, line: &str, row: usize) -> Result<Line, FormatError> {
+    if let Some(captures) = patterns.range.captures(line) {
+        let weight = range_weight(&captures[1], &captures[2], row)?;
+        return Ok(Line::Entry(new_entry(weight, &captures[3])));
+    }
+
+    if let Some(captures) = patterns.numbered.captures(line) {
+        return Ok(Line::Entry(new_entry(1, &captures[2])));
+    }
+
+    if let Some(captures) = patterns.bullet.captures(line) {
+        return Ok(Line::Entry(new_entry(1, &captures[1])));
+    }
+
+    Ok(Line::Text(line.to_string()))
+}
+
+fn parse_pipe_row(patterns: &Patterns, line: &str, row: usize, has_entries: bool, headers: &[String]) -> Result<Line, FormatError> {
+    let cells = line.trim_matches('|')
+        .split('|')
+        .map(|c| c.trim().to_string())
+        .collect::<Vec<_>>();
+
+    if cells.iter().all(|c| !c.is_empty() && c.chars().all(|ch| matches!(ch, '-' | ':' | ' '))) {
+        return Ok(Line::Separator);
+    }
+
+    let first = cells.first().map(|c| c.as_str()).unwrap_or("");
+    if patterns.die.is_match(first) {
+        return Ok(Line::Header(cells));
+    }
+
+    let weight = match first.split(['-', '–', '—']).map(str::trim).collect::<Vec<_>>().as_slice() {
+        [single] if usize::from_str(single).is_ok() => Some(1),
+        [low, high] if usize::from_str(low).is_ok() && usize::from_str(high).is_ok() => Some(range_weight(low, high, row)?),
+        _ => None
+    };
+
+    match (weight, cells.get(1)) {
+        (Some(weight), Some(name)) if !name.is_empty() => {
+            let mut entry = new_entry(weight, name);
+
+            for (column, value) in cells.iter().enumerate().skip(2) {
+                if let (Some(header), false) = (headers.get(column), value.is_empty()) {
+                    entry.set_field(header.clone(), value.clone());
+                }
+            }
+
+            Ok(Line::Entry(entry))
+        },
+        _ if !has_entries => Ok(Line::Header(cells)),
+        _ => Ok(Line::Text(cells.join(" ").trim().to_string()))
+    }
+}
+
+fn new_entry(weight: usize, name: &str) -> TableEntry {
+    let mut entry = TableEntry::with_weight(weight, Currency::Copper(1));
+    entry.set_name(name.trim());
+    entry
+}
+
+fn range_weight(low: &str, high: &str, row: usize) -> Result<usize, FormatError> {
+    let parse = |value: &str| match value {
+        "00" | "000" => Ok(10_usize.pow(value.len() as u32)),
+        _ => usize::from_str(value).map_err(|e| FormatError::InvalidValue { row, column: "Range".to_string(), message: e.to_string() })
+    };
+
+    let (low, high) = (parse(low)?, parse(high)?);
+    if high < low {
+        return Err(FormatError::InvalidValue { row, column: "Range".to_string(), message: format!("{} is lower than {}", high, low) });
+    }
+
+    Ok(high - low + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ranges_and_lists() {
+        let text = "Random Encounters\n01–05 Goblin\n06-10 Wolf pack, hunting\nin the hills\n11–00 Nothing\n";
+        let table = parse_pasted_table(text).unwrap();
+
+        assert_eq!("Random Encounters", table.name());
+        assert!(table.use_weight());
+        assert_eq!(vec![5, 5, 90], table.iter().map(|e| e.weight()).collect::<Vec<_>>());
+        assert_eq!("Wolf pack, hunting in the hills", table.entries()[1].name());
+
+        let table = parse_pasted_table("1. Goblin\n2) Orc\n- Kobold\n• Troll").unwrap();
+        assert!(!table.use_weight());
+        assert_eq!(4, table.len());
+    }
+
+    #[test]
+    fn parses_pipe_tables() {
+        let text = "| d6 | Result | Notes |\n|:--:|--------|---|\n| 1-2 | Rats | Hungry |\n| 3 | Bandits | |\n| 4–6 | Ogre | Asleep |";
+        let table = parse_pasted_table(text).unwrap();
+
+        assert_eq!(vec![2, 1, 3], table.iter().map(|e| e.weight()).collect::<Vec<_>>());
+        assert_eq!(Some("Hungry"), table.entries()[0].field("Notes"));
+        assert_eq!(None, table.entries()[1].field("Notes"));
+        assert!(parse_pasted_table("just some prose").is_err());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,126 @@
+use std::io::{Read, Write};
+
+use base64::{engine::general_purpose, Engine};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+
+use crate::{FileTableData, formats::FormatError};
+
+pub const SHARE_CODE_PREFIX: &str = "rtr:";
+pub const SHARE_CODE_VERSION: u8 = 1;
+const HEADER_LENGTH: usize = 5;
+/// The most JSON a share code may inflate to. A few kilobytes of deflate can otherwise expand to gigabytes.
+const MAX_DECODED_LEN: u64 = 8 * 1024 * 1024;
+
+pub fn is_share_code(text: &str) -> bool {
+    text.trim_start().starts_with(SHARE_CODE_PREFIX)
+}
+
+pub fn encode_share_code(table: &FileTableData) -> Result<String, FormatError> {
+    let json = serde_json::to_vec(table).map_err(|e| FormatError::Parse(e.to_string()))?;
+    let mut bytes = Vec::with_capacity(HEADER_LENGTH + json.len() / 2);
+    bytes.push(SHARE_CODE_VERSION);
+    bytes.write_u32::<BigEndian>(crc32fast::hash(&json)).map_err(|e| FormatError::Parse(e.to_string()))?;
+
+    let mut encoder = DeflateEncoder::new(bytes, Compression::best());
+    encoder.write_all(&json).map_err(|e| FormatError::Parse(e.to_string()))?;
+    let bytes = encoder.finish().map_err(|e| FormatError::Parse(e.to_string()))?;
+
+    Ok(format!("{}{}", SHARE_CODE_PREFIX, general_purpose::URL_SAFE_NO_PAD.encode(bytes)))
+}
+
+pub fn decode_share_code(code: &str) -> Result<FileTableData, FormatError> {
+    let code = code.split_whitespace().collect::<String>();
+    let code = code.strip_prefix(SHARE_CODE_PREFIX)
+        .ok_or_else(|| FormatError::InvalidShareCode("The text is not a table share code".to_string()))?;
+    let bytes = general_purpose::URL_SAFE_NO_PAD.decode(code.trim_end_matches('='))
+        .map_err(|_| FormatError::InvalidShareCode("The code contains invalid characters".to_string()))?;
+
+    if bytes.len() <= HEADER_LENGTH {
+        return Err(FormatError::InvalidShareCode("The code is incomplete".to_string()));
+    }
+
+    let mut reader = &bytes[..];
+    let version = reader.read_u8().map_err(|e| FormatError::Parse(e.to_string()))?;
+    if version != SHARE_CODE_VERSION {
+        return Err(FormatError::UnsupportedShareCodeVersion(version));
+    }
+
+    let checksum = reader.read_u32::<BigEndian>().map_err(|e| FormatError::Parse(e.to_string()))?;
+    let mut json = Vec::new();
+    DeflateDecoder::new(reader).take(MAX_DECODED_LEN + 1).read_to_end(&mut json)
+        .map_err(|_| FormatError::InvalidShareCode("The code is incomplete or damaged".to_string()))?;
+
+    if json.len() as u64 > MAX_DECODED_LEN {
+        return Err(FormatError::InvalidShareCode(format!("The table is larger than {} MB", MAX_DECODED_LEN / (1024 * 1024))));
+    }
+
+    if crc32fast::hash(&json) != checksum {
+        return Err(FormatError::InvalidShareCode("The code is incomplete or damaged".to_string()));
+    }
+
+    serde_json::from_slice(&json).map_err(|e| FormatError::Parse(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Currency, TableData, TableEntry};
+
+    use super::*;
+
+    fn get_table() -> FileTableData {
+        let mut table = TableData::new(true, true, "Treasure", 0);
+        let mut entry = TableEntry::new(Currency::Gold(12));
+        entry.set_name("Silver chalice");
+        entry.set_weight(3);
+        table.set_entries(vec![entry, TableEntry::new(Currency::Copper(5))]);
+        table.set_tags(vec!["loot".to_string()]);
+        table.to_file_data()
+    }
+
+    #[test]
+    fn round_trips_tables() {
+        let table = get_table();
+        let code = encode_share_code(&table).unwrap();
+
+        assert!(is_share_code(&code));
+        assert_eq!(table, decode_share_code(&code).unwrap());
+
+        let wrapped = code.chars()
+            .collect::<Vec<_>>()
+            .chunks(20)
+            .map(|chunk| chunk.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(table, decode_share_code(&format!("  {}\n", wrapped)).unwrap());
+    }
+
+    #[test]
+    fn rejects_damaged_codes() {
+        let code = encode_share_code(&get_table()).unwrap();
+
+        assert!(decode_share_code(&code[..code.len() - 6]).is_err());
+        assert!(decode_share_code(&code[..SHARE_CODE_PREFIX.len() + 4]).is_err());
+        assert!(decode_share_code("just some text").is_err());
+
+        let mut bytes = general_purpose::URL_SAFE_NO_PAD.decode(&code[SHARE_CODE_PREFIX.len()..]).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0x55;
+        let damaged = format!("{}{}", SHARE_CODE_PREFIX, general_purpose::URL_SAFE_NO_PAD.encode(&bytes));
+        assert!(decode_share_code(&damaged).is_err());
+
+        bytes[0] = SHARE_CODE_VERSION + 1;
+        let future = format!("{}{}", SHARE_CODE_PREFIX, general_purpose::URL_SAFE_NO_PAD.encode(&bytes));
+        assert_eq!(Err(FormatError::UnsupportedShareCodeVersion(SHARE_CODE_VERSION + 1)), decode_share_code(&future));
+    }
+
+    #[test]
+    fn rejects_codes_that_inflate_too_far() {
+        let mut encoder = DeflateEncoder::new(vec![SHARE_CODE_VERSION, 0, 0, 0, 0], Compression::best());
+        encoder.write_all(&vec![b' '; MAX_DECODED_LEN as usize + 1]).unwrap();
+        let bytes = encoder.finish().unwrap();
+
+        let code = format!("{}{}", SHARE_CODE_PREFIX, general_purpose::URL_SAFE_NO_PAD.encode(&bytes));
+        assert!(matches!(decode_share_code(&code), Err(FormatError::InvalidShareCode(_))));
+    }
+}
\ No newline at end of file
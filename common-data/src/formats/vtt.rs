@@ -0,0 +1,261 @@
+use serde::{Serialize, Deserialize};
+use serde_json::{Map, Value};
+
+use crate::{Currency, TableData, TableEntry, formats::{FormatError, markup::die_ranges, delimited::parse_cost}};
+
+const FLAG_SCOPE: &str = "random-table-roller";
+const DEFAULT_IMAGE: &str = "icons/svg/d20-black.svg";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FoundryRollTable {
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    formula: String,
+    #[serde(default = "default_true")]
+    replacement: bool,
+    #[serde(default = "default_true")]
+    display_roll: bool,
+    #[serde(default)]
+    results: Vec<FoundryTableResult>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    flags: Map<String, Value>
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FoundryTableResult {
+    #[serde(rename = "type", default = "default_result_type")]
+    result_type: Value,
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    img: Option<String>,
+    #[serde(default)]
+    weight: Option<usize>,
+    #[serde(default)]
+    range: Option<[usize; 2]>,
+    #[serde(default)]
+    drawn: bool,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    flags: Map<String, Value>
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Roll20RollableTable {
+    name: String,
+    #[serde(default)]
+    showplayers: bool,
+    #[serde(default)]
+    items: Vec<Roll20TableItem>
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Roll20TableItem {
+    name: String,
+    #[serde(default = "default_weight")]
+    weight: usize,
+    #[serde(default)]
+    avatar: String
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_weight() -> usize {
+    1
+}
+
+fn default_result_type() -> Value {
+    Value::from(0)
+}
+
+pub fn import_foundry(text: &str) -> Result<TableData, FormatError> {
+    let document = serde_json::from_str::<FoundryRollTable>(text).map_err(|e| FormatError::Parse(e.to_string()))?;
+    let mut entries = Vec::with_capacity(document.results.len());
+    let mut use_cost = false;
+
+    for (index, result) in document.results.iter().enumerate() {
+        let weight = match (result.range, result.weight) {
+            (Some([low, high]), _) if high >= low && low > 0 => high - low + 1,
+            (_, Some(weight)) if weight > 0 => weight,
+            (Some([low, high]), _) => return Err(FormatError::InvalidValue {
+                row: index + 1,
+                column: "range".to_string(),
+                message: format!("[{}, {}] is not a valid range", low, high)
+            }),
+            _ => 1
+        };
+
+        let cost = match result.flags.get(FLAG_SCOPE).and_then(|f| f.get("cost")).and_then(|c| c.as_str()) {
+            Some(cost) => {
+                use_cost = true;
+                parse_cost(cost).map_err(|message| FormatError::InvalidValue { row: index + 1, column: "cost".to_string(), message })?
+            },
+            None => Currency::Copper(1)
+        };
+
+        let mut entry = TableEntry::with_weight(weight, cost);
+        entry.set_name(result.text.trim());
+        entries.push(entry);
+    }
+
+    let use_weight = entries.iter().any(|e| e.weight() != 1);
+    let mut table = TableData::new(use_cost, use_weight, document.name, 0);
+    table.set_entries(entries);
+    Ok(table)
+}
+
+pub fn export_foundry(table: &TableData) -> Result<String, FormatError> {
+    let ranges = die_ranges(table);
+    let total = ranges.last().map(|(_, high)| *high).unwrap_or(1);
+
+    let results = table.iter()
+        .zip(ranges)
+        .map(|(entry, (low, high))| {
+            let mut flags = Map::new();
+            if table.use_cost() {
+                let mut scope = Map::new();
+                scope.insert("cost".to_string(), Value::from(entry.cost().to_string()));
+                flags.insert(FLAG_SCOPE.to_string(), Value::Object(scope));
+            }
+
+            FoundryTableResult {
+                result_type: default_result_type(),
+                text: entry.name().to_string(),
+                img: Some(DEFAULT_IMAGE.to_string()),
+                weight: Some(table.roll_weight(entry)),
+                range: Some([low, high]),
+                drawn: false,
+                flags
+            }
+        })
+        .collect();
+
+    let document = FoundryRollTable {
+        name: table.name().to_string(),
+        description: String::new(),
+        formula: format!("1d{}", total),
+        replacement: true,
+        display_roll: true,
+        results,
+        flags: Map::new()
+    };
+
+    serde_json::to_string_pretty(&document).map_err(|e| FormatError::Parse(e.to_string()))
+}
+
+pub fn import_roll20(text: &str) -> Result<TableData, FormatError> {
+    let document = serde_json::from_str::<Roll20RollableTable>(text).map_err(|e| FormatError::Parse(e.to_string()))?;
+    let mut entries = Vec::with_capacity(document.items.len());
+
+    for (index, item) in document.items.iter().enumerate() {
+        if item.weight == 0 {
+            return Err(FormatError::InvalidValue { row: index + 1, column: "weight".to_string(), message: "Weight must be at least 1".to_string() });
+        }
+
+        let mut entry = TableEntry::with_weight(item.weight, Currency::Copper(1));
+        entry.set_name(item.name.trim());
+        entries.push(entry);
+    }
+
+    let use_weight = entries.iter().any(|e| e.weight() != 1);
+    let mut table = TableData::new(false, use_weight, document.name, 0);
+    table.set_entries(entries);
+    Ok(table)
+}
+
+pub fn export_roll20(table: &TableData) -> Result<String, FormatError> {
+    let document = Roll20RollableTable {
+        name: table.name().replace(char::is_whitespace, "-"),
+        showplayers: false,
+        items: table.iter()
+            .map(|entry| Roll20TableItem {
+                name: entry.name().to_string(),
+                weight: table.roll_weight(entry),
+                avatar: String::new()
+            })
+            .collect()
+    };
+
+    serde_json::to_string_pretty(&document).map_err(|e| FormatError::Parse(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> TableData {
+        let mut table = TableData::new(true, true, "Hoard", 0);
+        for (name, weight, cost) in [("Coins", 4, Currency::Gold(10)), ("Gem", 1, Currency::Gold(50)), ("Sword", 3, Currency::Gold(15))] {
+            let mut entry = TableEntry::with_weight(weight, cost);
+            entry.set_name(name);
+            table.push(entry);
+        }
+
+        table
+    }
+
+    #[test]
+    fn foundry_round_trips() {
+        let table = table();
+        let json = export_foundry(&table).unwrap();
+        let value = serde_json::from_str::<Value>(&json).unwrap();
+
+        assert_eq!("1d8", value["formula"]);
+        assert_eq!(serde_json::json!([5, 5]), value["results"][1]["range"]);
+        assert_eq!(serde_json::json!([6, 8]), value["results"][2]["range"]);
+
+        let mut imported = import_foundry(&json).unwrap();
+        for (entry, original) in imported.entries_mut().iter_mut().zip(table.entries()) {
+            entry.set_id(original.id());
+        }
+
+        assert_eq!(table.entries(), imported.entries());
+        assert!(imported.use_cost());
+    }
+
+    #[test]
+    fn foundry_ranges_take_precedence() {
+        let json = r#"{"name": "Encounters", "results": [
+            {"type": "text", "text": "Goblins", "weight": 1, "range": [1, 5]},
+            {"type": 0, "text": "Dragon", "weight": 1, "range": [6, 6]}
+        ]}"#;
+
+        let table = import_foundry(json).unwrap();
+        assert_eq!(vec![5, 1], table.iter().map(|e| e.weight()).collect::<Vec<_>>());
+        assert!(!table.use_cost());
+    }
+
+    #[test]
+    fn roll20_round_trips() {
+        let table = table();
+        let json = export_roll20(&table).unwrap();
+        let imported = import_roll20(&json).unwrap();
+
+        assert_eq!(vec![4, 1, 3], imported.iter().map(|e| e.weight()).collect::<Vec<_>>());
+        assert_eq!("Sword", imported.entries()[2].name());
+        assert!(import_roll20(r#"{"name": "x", "items": [{"name": "y", "weight": 0}]}"#).is_err());
+    }
+
+    #[test]
+    fn unweighted_tables_export_equal_weights() {
+        let mut table = table();
+        table.set_use_weight(false);
+
+        let json = export_foundry(&table).unwrap();
+        let value = serde_json::from_str::<Value>(&json).unwrap();
+        assert_eq!("1d3", value["formula"]);
+        assert_eq!(serde_json::json!([3, 3]), value["results"][2]["range"]);
+
+        let imported = import_foundry(&json).unwrap();
+        assert_eq!(vec![1, 1, 1], imported.iter().map(|e| e.weight()).collect::<Vec<_>>());
+        assert!(!imported.use_weight());
+
+        let imported = import_roll20(&export_roll20(&table).unwrap()).unwrap();
+        assert_eq!(vec![1, 1, 1], imported.iter().map(|e| e.weight()).collect::<Vec<_>>());
+        assert!(!imported.use_weight());
+    }
+}
\ No newline at end of file
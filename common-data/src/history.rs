@@ -0,0 +1,29 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryState {
+    undo_count: usize,
+    redo_count: usize
+}
+
+impl HistoryState {
+    pub fn new(undo_count: usize, redo_count: usize) -> Self {
+        Self { undo_count, redo_count }
+    }
+
+    pub fn undo_count(&self) -> usize {
+        self.undo_count
+    }
+
+    pub fn redo_count(&self) -> usize {
+        self.redo_count
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.undo_count > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.redo_count > 0
+    }
+}
@@ -0,0 +1,292 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDateTime;
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+use crate::{Balance, BackendError, Currency, RollResult};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerTransaction {
+    id: Uuid,
+    date: NaiveDateTime,
+    description: String,
+    category: String,
+    amount: Balance
+}
+
+impl LedgerTransaction {
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn date(&self) -> NaiveDateTime {
+        self.date
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn category(&self) -> &str {
+        &self.category
+    }
+
+    pub fn amount(&self) -> Balance {
+        self.amount
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerItem {
+    name: String,
+    category: String,
+    count: usize,
+    cost: Currency
+}
+
+impl LedgerItem {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn category(&self) -> &str {
+        &self.category
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn cost(&self) -> Currency {
+        self.cost
+    }
+
+    pub fn total_cost(&self) -> Result<Currency, BackendError> {
+        u64::from(self.cost).checked_mul(self.count as u64)
+            .map(Currency::from)
+            .ok_or_else(|| BackendError::validation_error("ledger item", format!("The value of {}x {} is too large", self.count, self.name)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategoryTotal {
+    category: String,
+    item_count: usize,
+    item_value: Currency,
+    balance: Balance
+}
+
+impl CategoryTotal {
+    fn new(category: impl Into<String>) -> Self {
+        Self {
+            category: category.into(),
+            item_count: 0,
+            item_value: Currency::default(),
+            balance: Balance::default()
+        }
+    }
+
+    pub fn category(&self) -> &str {
+        &self.category
+    }
+
+    pub fn item_count(&self) -> usize {
+        self.item_count
+    }
+
+    pub fn item_value(&self) -> Currency {
+        self.item_value
+    }
+
+    pub fn balance(&self) -> Balance {
+        self.balance
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ledger {
+    items: Vec<LedgerItem>,
+    transactions: Vec<LedgerTransaction>
+}
+
+impl Ledger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn items(&self) -> &Vec<LedgerItem> {
+        &self.items
+    }
+
+    pub fn transactions(&self) -> &Vec<LedgerTransaction> {
+        &self.transactions
+    }
+
+    pub fn balance(&self) -> Balance {
+        self.transactions.iter().map(|t| t.amount).sum()
+    }
+
+    pub fn item_value(&self) -> Result<Currency, BackendError> {
+        self.items.iter().try_fold(Currency::default(), |total, item| checked_add_value(total, item.total_cost()?))
+    }
+
+    pub fn add_transaction(&mut self, date: NaiveDateTime, description: impl Into<String>, category: impl Into<String>, amount: Balance) -> Uuid {
+        let id = Uuid::new_v4();
+
+        self.transactions.push(LedgerTransaction {
+            id,
+            date,
+            description: description.into(),
+            category: category.into(),
+            amount
+        });
+
+        self.transactions.sort_by_key(|t| t.date);
+
+        id
+    }
+
+    pub fn remove_transaction(&mut self, id: Uuid) -> Option<LedgerTransaction> {
+        let index = self.transactions.iter().position(|t| t.id == id)?;
+        Some(self.transactions.remove(index))
+    }
+
+    /// Adds to a matching item's count, or adds a new item. Fails without changing the ledger if the item's value would overflow.
+    pub fn add_item(&mut self, name: impl Into<String>, category: impl Into<String>, count: usize, cost: Currency) -> Result<(), BackendError> {
+        let name = name.into();
+        let category = category.into();
+
+        match self.items.iter_mut().find(|i| i.name == name && i.category == category && u64::from(i.cost) == u64::from(cost)) {
+            Some(item) => {
+                let merged = LedgerItem {
+                    count: item.count.checked_add(count)
+                        .ok_or_else(|| BackendError::validation_error("ledger item", format!("Too many of {} to count", name)))?,
+                    ..item.clone()
+                };
+
+                merged.total_cost()?;
+                *item = merged;
+            },
+            None => {
+                let item = LedgerItem { name, category, count, cost };
+                item.total_cost()?;
+                self.items.push(item);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds every result, or none of them if any would overflow.
+    pub fn add_roll_results(&mut self, results: &[RollResult], category: impl Into<String>) -> Result<(), BackendError> {
+        let category = category.into();
+        let before = self.items.clone();
+
+        for result in results {
+            if let Err(e) = self.add_item(result.entry().name(), category.clone(), result.count(), result.entry().cost()) {
+                self.items = before;
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn remove_item(&mut self, index: usize) -> Option<LedgerItem> {
+        if index >= self.items.len() {
+            None
+        } else {
+            Some(self.items.remove(index))
+        }
+    }
+
+    pub fn totals_by_category(&self) -> Result<Vec<CategoryTotal>, BackendError> {
+        let mut totals: BTreeMap<&str, CategoryTotal> = BTreeMap::new();
+
+        for item in self.items.iter() {
+            let total = totals.entry(&item.category).or_insert_with(|| CategoryTotal::new(&item.category));
+            total.item_count = total.item_count.saturating_add(item.count);
+            total.item_value = checked_add_value(total.item_value, item.total_cost()?)?;
+        }
+
+        for transaction in self.transactions.iter() {
+            let total = totals.entry(&transaction.category).or_insert_with(|| CategoryTotal::new(&transaction.category));
+            total.balance += transaction.amount;
+        }
+
+        Ok(totals.into_values().collect())
+    }
+
+    pub fn split(&self, members: usize) -> Vec<Balance> {
+        self.balance().split(members)
+    }
+}
+
+fn checked_add_value(a: Currency, b: Currency) -> Result<Currency, BackendError> {
+    u64::from(a).checked_add(u64::from(b))
+        .map(Currency::from)
+        .ok_or_else(|| BackendError::validation_error("ledger", "The total value of the items is too large"))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn date(day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2023, 5, day).unwrap().and_hms_opt(12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn balance_allows_debt() {
+        let mut ledger = Ledger::new();
+        ledger.add_transaction(date(1), "Loot", "Dungeon", Balance::from(Currency::Gold(5)));
+        ledger.add_transaction(date(2), "Inn", "Expenses", Balance::from(Currency::Gold(8)).checked_neg().unwrap());
+
+        assert_eq!(Balance::from_copper(-300), ledger.balance());
+        assert_eq!("-3 gp", ledger.balance().to_string());
+    }
+
+    #[test]
+    fn split_distributes_remainder() {
+        assert_eq!(vec![Balance::from_copper(4), Balance::from_copper(3), Balance::from_copper(3)], Balance::from_copper(10).split(3));
+        assert_eq!(vec![Balance::from_copper(-4), Balance::from_copper(-3), Balance::from_copper(-3)], Balance::from_copper(-10).split(3));
+        assert!(Balance::from_copper(10).split(0).is_empty());
+    }
+
+    #[test]
+    fn items_merge_and_total_by_category() {
+        let mut ledger = Ledger::new();
+        ledger.add_item("Potion", "Consumables", 2, Currency::Gold(50)).unwrap();
+        ledger.add_item("Potion", "Consumables", 1, Currency::Gold(50)).unwrap();
+        ledger.add_item("Rope", "Gear", 1, Currency::Gold(1)).unwrap();
+        ledger.add_transaction(date(1), "Sold gems", "Gear", Balance::from(Currency::Gold(10)));
+
+        assert_eq!(2, ledger.items().len());
+        assert_eq!(3, ledger.items()[0].count());
+
+        let totals = ledger.totals_by_category().unwrap();
+        assert_eq!("Consumables", totals[0].category());
+        assert_eq!(Currency::Platinum(15), totals[0].item_value());
+        assert_eq!("Gear", totals[1].category());
+        assert_eq!(Balance::from(Currency::Gold(10)), totals[1].balance());
+    }
+
+    #[test]
+    fn overflowing_values_are_errors() {
+        let mut ledger = Ledger::new();
+        ledger.add_item("Hoard", "Loot", 2, Currency::Copper(u64::MAX / 4)).unwrap();
+        assert!(ledger.add_item("Hoard", "Loot", 2, Currency::Copper(u64::MAX / 4)).is_ok());
+        assert!(ledger.add_item("Hoard", "Loot", 1, Currency::Copper(u64::MAX / 4)).is_err());
+        assert_eq!(4, ledger.items()[0].count());
+
+        ledger.add_item("Another hoard", "Loot", 4, Currency::Copper(u64::MAX / 4)).unwrap();
+        assert!(ledger.item_value().is_err());
+        assert!(ledger.totals_by_category().is_err());
+
+        assert!(Balance::from_copper(i64::MIN).checked_neg().is_err());
+        assert_eq!(Balance::from_copper(i64::MIN + 1), Balance::from_copper(i64::MAX).checked_neg().unwrap());
+    }
+}
\ No newline at end of file
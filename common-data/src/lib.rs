@@ -1,37 +1,93 @@
+pub mod balance;
+pub mod bundle;
 pub mod currency;
+pub mod file_change;
+pub mod formats;
+pub mod history;
 pub mod id_name_pair;
+pub mod ledger;
+pub mod library;
+pub mod search;
+pub mod settings;
+pub mod split;
 pub mod table;
+pub mod table_event;
+pub mod table_summary;
+
+use std::path::{Path, PathBuf};
 
 use serde::{Serialize, Deserialize};
 
+pub use crate::balance::Balance;
 pub use crate::currency::Currency;
+pub use crate::file_change::{TableFileChange, ConflictResolution};
 pub use crate::id_name_pair::IdNamePair;
 pub use crate::table::*;
+pub use crate::table_event::{TableEvent, TABLE_EVENT};
+pub use crate::table_summary::TableSummary;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "code")]
 pub enum BackendError {
-    InternalError(String),
-    ArgumentError{ arg_name: String, message: String }
+    #[serde(rename = "internal")]
+    InternalError { message: String },
+    #[serde(rename = "invalid_argument")]
+    ArgumentError { arg_name: String, message: String },
+    #[serde(rename = "not_found")]
+    NotFound { kind: String, id: String },
+    #[serde(rename = "io")]
+    IoError { path: Option<PathBuf>, message: String },
+    #[serde(rename = "parse")]
+    ParseError { path: Option<PathBuf>, line: Option<usize>, column: Option<usize>, message: String },
+    #[serde(rename = "validation")]
+    ValidationError { field: String, message: String },
+    #[serde(rename = "roll")]
+    RollError { message: String }
 }
 
-impl<E: std::error::Error> From<E> for BackendError {
+impl<E: std::error::Error + 'static> From<E> for BackendError {
     fn from(value: E) -> Self {
-        Self::InternalError(value.to_string())
+        let error: &(dyn std::error::Error + 'static) = &value;
+
+        if let Some(e) = error.downcast_ref::<std::io::Error>() {
+            Self::IoError { path: None, message: e.to_string() }
+        } else if let Some(e) = error.downcast_ref::<serde_json::Error>() {
+            Self::from_json_error(None, e)
+        } else {
+            Self::InternalError { message: value.to_string() }
+        }
     }
 }
 
 impl std::fmt::Display for BackendError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::InternalError(msg) => write!(f, "Internal error: {}", msg),
-            Self::ArgumentError { arg_name, message } => write!(f, "Error with argument '{}': {}", arg_name, message)
+            Self::InternalError { message } => write!(f, "Internal error: {}", message),
+            Self::ArgumentError { arg_name, message } => write!(f, "Error with argument '{}': {}", arg_name, message),
+            Self::NotFound { kind, id } => write!(f, "Could not find {} '{}'", kind, id),
+            Self::IoError { path: Some(path), message } => write!(f, "Could not access {:?}: {}", path, message),
+            Self::IoError { path: None, message } => write!(f, "I/O error: {}", message),
+            Self::ParseError { path, line, column, message } => {
+                match path {
+                    Some(path) => write!(f, "Could not parse {:?}", path)?,
+                    None => write!(f, "Could not parse data")?
+                }
+
+                if let (Some(line), Some(column)) = (line, column) {
+                    write!(f, " at line {}, column {}", line, column)?;
+                }
+
+                write!(f, ": {}", message)
+            },
+            Self::ValidationError { field, message } => write!(f, "Invalid {}: {}", field, message),
+            Self::RollError { message } => write!(f, "Could not roll: {}", message)
         }
     }
 }
 
 impl BackendError {
     pub fn internal_error(message: impl Into<String>) -> Self {
-        Self::InternalError(message.into())
+        Self::InternalError { message: message.into() }
     }
 
     pub fn argument_error(arg_name: impl Into<String>, message: impl Into<String>) -> Self {
@@ -40,4 +96,101 @@ impl BackendError {
             message: message.into()
         }
     }
+
+    pub fn not_found(kind: impl Into<String>, id: impl ToString) -> Self {
+        Self::NotFound {
+            kind: kind.into(),
+            id: id.to_string()
+        }
+    }
+
+    pub fn io_error(path: &Path, error: &std::io::Error) -> Self {
+        Self::IoError {
+            path: Some(path.to_path_buf()),
+            message: error.to_string()
+        }
+    }
+
+    pub fn parse_error(path: &Path, error: &serde_json::Error) -> Self {
+        Self::from_json_error(Some(path), error)
+    }
+
+    pub fn format_error(path: &Path, error: &formats::FormatError) -> Self {
+        Self::ParseError {
+            path: Some(path.to_path_buf()),
+            line: None,
+            column: None,
+            message: error.to_string()
+        }
+    }
+
+    pub fn validation_error(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::ValidationError {
+            field: field.into(),
+            message: message.into()
+        }
+    }
+
+    pub fn roll_error(message: impl Into<String>) -> Self {
+        Self::RollError { message: message.into() }
+    }
+
+    fn from_json_error(path: Option<&Path>, error: &serde_json::Error) -> Self {
+        let path = path.map(Path::to_path_buf);
+
+        if error.is_io() {
+            return Self::IoError { path, message: error.to_string() };
+        }
+
+        let position = (error.line() > 0).then_some((error.line(), error.column()));
+
+        Self::ParseError {
+            path,
+            line: position.map(|(line, _)| line),
+            column: position.map(|(_, column)| column),
+            message: error.to_string()
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InternalError { .. } => "internal",
+            Self::ArgumentError { .. } => "invalid_argument",
+            Self::NotFound { .. } => "not_found",
+            Self::IoError { .. } => "io",
+            Self::ParseError { .. } => "parse",
+            Self::ValidationError { .. } => "validation",
+            Self::RollError { .. } => "roll"
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::InternalError { .. } | Self::IoError { .. } | Self::RollError { .. })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_io_and_json_errors() {
+        let io = BackendError::from(std::io::Error::new(std::io::ErrorKind::NotFound, "missing"));
+        assert_eq!("io", io.code());
+        assert!(io.is_retryable());
+
+        let json = serde_json::from_str::<Vec<usize>>("[1,\n2,,]").unwrap_err();
+        match BackendError::parse_error(Path::new("loot.table"), &json) {
+            BackendError::ParseError { path, line, column, .. } => {
+                assert_eq!(Some(PathBuf::from("loot.table")), path);
+                assert_eq!(Some(2), line);
+                assert_eq!(Some(3), column);
+            },
+            other => panic!("Unexpected error: {:?}", other)
+        }
+
+        let value = serde_json::to_value(BackendError::not_found("table", "abc")).unwrap();
+        assert_eq!("not_found", value["code"]);
+        assert_eq!("table", value["kind"]);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,234 @@
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDateTime;
+use serde::{Serialize, Deserialize};
+
+use crate::FileTableData;
+
+const MAX_MATCHING_ENTRIES: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibraryEntry {
+    path: PathBuf,
+    name: String,
+    tags: Vec<String>,
+    modified: NaiveDateTime,
+    entry_names: Vec<String>
+}
+
+impl LibraryEntry {
+    pub fn new(path: impl Into<PathBuf>, modified: NaiveDateTime, data: &FileTableData) -> Self {
+        Self {
+            path: path.into(),
+            name: data.name().to_string(),
+            tags: data.tags().clone(),
+            modified,
+            entry_names: data.entries().iter().map(|e| e.name().to_string()).collect()
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
+    pub fn modified(&self) -> NaiveDateTime {
+        self.modified
+    }
+
+    pub fn entry_count(&self) -> usize {
+        self.entry_names.len()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibrarySearchResult {
+    path: PathBuf,
+    name: String,
+    tags: Vec<String>,
+    modified: NaiveDateTime,
+    entry_count: usize,
+    matching_entries: Vec<String>
+}
+
+impl LibrarySearchResult {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
+    pub fn modified(&self) -> NaiveDateTime {
+        self.modified
+    }
+
+    pub fn entry_count(&self) -> usize {
+        self.entry_count
+    }
+
+    pub fn matching_entries(&self) -> &Vec<String> {
+        &self.matching_entries
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibraryIndex {
+    root: PathBuf,
+    entries: Vec<LibraryEntry>
+}
+
+impl LibraryIndex {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            entries: Vec::new()
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn entries(&self) -> &Vec<LibraryEntry> {
+        &self.entries
+    }
+
+    pub fn update<F>(&mut self, files: Vec<(PathBuf, NaiveDateTime)>, mut read: F) -> usize
+    where
+        F: FnMut(&Path) -> Option<FileTableData>
+    {
+        self.entries.retain(|e| files.iter().any(|(path, _)| *path == e.path));
+        let mut updated = 0;
+
+        for (path, modified) in files {
+            let existing = self.entries.iter().position(|e| e.path == path);
+            if matches!(existing, Some(index) if self.entries[index].modified == modified) {
+                continue;
+            }
+
+            let entry = read(&path).map(|data| LibraryEntry::new(path.clone(), modified, &data));
+
+            match (existing, entry) {
+                (Some(index), Some(entry)) => self.entries[index] = entry,
+                (Some(index), None) => { self.entries.remove(index); },
+                (None, Some(entry)) => self.entries.push(entry),
+                (None, None) => continue
+            }
+
+            updated += 1;
+        }
+
+        self.entries.sort_by_key(|e| e.name.to_lowercase());
+        updated
+    }
+
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags = self.entries.iter()
+            .flat_map(|e| e.tags.iter().cloned())
+            .collect::<Vec<_>>();
+
+        tags.sort_by_key(|t| t.to_lowercase());
+        tags.dedup();
+        tags
+    }
+
+    pub fn search(&self, query: &str, tag: Option<&str>) -> Vec<LibrarySearchResult> {
+        let query = query.trim().to_lowercase();
+
+        self.entries.iter()
+            .filter(|e| tag.map(|tag| e.tags.iter().any(|t| t == tag)).unwrap_or(true))
+            .filter_map(|e| {
+                let matching_entries = match query.is_empty() {
+                    true => Vec::new(),
+                    false => e.entry_names.iter()
+                        .filter(|n| n.to_lowercase().contains(&query))
+                        .take(MAX_MATCHING_ENTRIES)
+                        .cloned()
+                        .collect()
+                };
+
+                let is_match = query.is_empty() || e.name.to_lowercase().contains(&query) || !matching_entries.is_empty();
+
+                is_match.then(|| LibrarySearchResult {
+                    path: e.path.clone(),
+                    name: e.name.clone(),
+                    tags: e.tags.clone(),
+                    modified: e.modified,
+                    entry_count: e.entry_count(),
+                    matching_entries
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::{Currency, TableData, TableEntry};
+
+    use super::*;
+
+    fn file_data(name: &str, entries: &[&str], tags: &[&str]) -> FileTableData {
+        let mut table = TableData::new(false, false, name, 0);
+        table.set_tags(tags.iter().map(|t| t.to_string()).collect());
+
+        for entry_name in entries {
+            let mut entry = TableEntry::new(Currency::Copper(1));
+            entry.set_name(*entry_name);
+            table.push(entry);
+        }
+
+        table.to_file_data()
+    }
+
+    #[test]
+    fn update_only_reads_changed_files() {
+        let first = NaiveDate::from_ymd_opt(2023, 5, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        let second = NaiveDate::from_ymd_opt(2023, 5, 2).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        let mut index = LibraryIndex::new("/tables");
+        let mut reads = 0;
+
+        let files = vec![(PathBuf::from("/tables/loot.table"), first), (PathBuf::from("/tables/npcs.table"), first)];
+        assert_eq!(2, index.update(files, |path| { reads += 1; Some(file_data(&path.to_string_lossy(), &[], &[])) }));
+
+        let files = vec![(PathBuf::from("/tables/loot.table"), second)];
+        assert_eq!(1, index.update(files, |_| { reads += 1; Some(file_data("Loot", &["Gold"], &[])) }));
+
+        assert_eq!(3, reads);
+        assert_eq!(1, index.entries().len());
+        assert_eq!(1, index.entries()[0].entry_count());
+    }
+
+    #[test]
+    fn search_matches_names_entries_and_tags() {
+        let modified = NaiveDate::from_ymd_opt(2023, 5, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        let mut index = LibraryIndex::new("/tables");
+        let files = vec![(PathBuf::from("/tables/a.table"), modified), (PathBuf::from("/tables/b.table"), modified)];
+
+        index.update(files, |path| match path.ends_with("a.table") {
+            true => Some(file_data("Dungeon Loot", &["Gold coins", "Rusty sword"], &["loot"])),
+            false => Some(file_data("Tavern Patrons", &["Goldsmith", "Bard"], &["npc"]))
+        });
+
+        assert_eq!(vec!["loot", "npc"], index.tags());
+        assert_eq!(2, index.search("gold", None).len());
+        assert_eq!(vec!["Goldsmith"], *index.search("gold", Some("npc"))[0].matching_entries());
+        assert_eq!("Dungeon Loot", index.search("LOOT", None)[0].name());
+        assert!(index.search("dragon", None).is_empty());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,219 @@
+use std::cmp::Reverse;
+
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+use crate::{TableData, TableEntry};
+
+pub const MAX_SEARCH_RESULTS: usize = 50;
+const SNIPPET_CONTEXT: usize = 30;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntrySearchResult {
+    table_id: Uuid,
+    table_name: String,
+    entry_id: Uuid,
+    entry_name: String,
+    field: Option<String>,
+    snippet: String,
+    score: i64
+}
+
+impl EntrySearchResult {
+    pub fn table_id(&self) -> Uuid {
+        self.table_id
+    }
+
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    pub fn entry_id(&self) -> Uuid {
+        self.entry_id
+    }
+
+    pub fn entry_name(&self) -> &str {
+        &self.entry_name
+    }
+
+    pub fn field(&self) -> Option<&str> {
+        self.field.as_deref()
+    }
+
+    pub fn snippet(&self) -> &str {
+        &self.snippet
+    }
+
+    pub fn score(&self) -> i64 {
+        self.score
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FuzzyMatch {
+    score: i64,
+    start: usize,
+    end: usize
+}
+
+fn is_word_start(chars: &[char], index: usize) -> bool {
+    index == 0 || !chars[index - 1].is_alphanumeric()
+}
+
+/// Matches `query` against `text`, preferring contiguous matches and falling back to the query's characters appearing in order.
+fn fuzzy_match(query: &[char], text: &str) -> Option<FuzzyMatch> {
+    let chars = text.to_lowercase().chars().collect::<Vec<_>>();
+    if query.is_empty() || chars.len() < query.len() {
+        return None;
+    }
+
+    if let Some(start) = chars.windows(query.len()).position(|window| window == query) {
+        let mut score = 100 - start.min(50) as i64;
+
+        if is_word_start(&chars, start) {
+            score += 20;
+        }
+
+        if chars.len() == query.len() {
+            score += 50;
+        }
+
+        return Some(FuzzyMatch { score, start, end: start + query.len() });
+    }
+
+    let mut positions = Vec::with_capacity(query.len());
+    let mut next = 0;
+
+    for c in query {
+        let index = next + chars[next..].iter().position(|t| t == c)?;
+        positions.push(index);
+        next = index + 1;
+    }
+
+    let start = positions[0];
+    let end = positions[positions.len() - 1] + 1;
+    if end - start > query.len() * 3 {
+        return None;
+    }
+
+    let consecutive = positions.windows(2).filter(|pair| pair[1] == pair[0] + 1).count() as i64;
+    let word_starts = positions.iter().filter(|index| is_word_start(&chars, **index)).count() as i64;
+    let gaps = (end - start - query.len()) as i64;
+
+    Some(FuzzyMatch { score: 40 + consecutive * 5 + word_starts * 3 - gaps, start, end })
+}
+
+fn snippet(text: &str, start: usize, end: usize) -> String {
+    let chars = text.chars().collect::<Vec<_>>();
+    let from = start.saturating_sub(SNIPPET_CONTEXT);
+    let to = (end + SNIPPET_CONTEXT).min(chars.len());
+    let from = from.min(to);
+
+    let mut snippet = chars[from..to].iter().collect::<String>().trim().to_string();
+    if from > 0 {
+        snippet.insert_str(0, "...");
+    }
+
+    if to < chars.len() {
+        snippet.push_str("...");
+    }
+
+    snippet
+}
+
+fn search_entry(query: &[char], table: &TableData, entry: &TableEntry) -> Option<EntrySearchResult> {
+    let name_match = fuzzy_match(query, entry.name()).map(|m| (None, entry.name().to_string(), m.score));
+    let field_match = entry.fields()
+        .iter()
+        .filter_map(|(field, value)| {
+            fuzzy_match(query, value).map(|m| (Some(field.to_string()), format!("{}: {}", field, snippet(value, m.start, m.end)), m.score - 10))
+        })
+        .max_by_key(|(_, _, score)| *score);
+
+    let (field, snippet, score) = match (name_match, field_match) {
+        (Some(name), Some(field)) if field.2 > name.2 => field,
+        (Some(name), _) => name,
+        (None, field) => field?
+    };
+
+    Some(EntrySearchResult {
+        table_id: table.id(),
+        table_name: table.name().to_string(),
+        entry_id: entry.id(),
+        entry_name: entry.name().to_string(),
+        field,
+        snippet,
+        score
+    })
+}
+
+/// Searches the entries of every table, returning the best matches first and keeping table order for ties.
+pub fn search_entries<'a>(tables: impl IntoIterator<Item = &'a TableData>, query: &str) -> Vec<EntrySearchResult> {
+    let query = query.trim().to_lowercase().chars().collect::<Vec<_>>();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut results = tables.into_iter()
+        .flat_map(|table| table.iter().filter_map(|entry| search_entry(&query, table, entry)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    results.sort_by_key(|result| Reverse(result.score));
+    results.truncate(MAX_SEARCH_RESULTS);
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Currency;
+
+    use super::*;
+
+    fn table(name: &str, entries: &[(&str, Option<&str>)]) -> TableData {
+        let mut table = TableData::new(false, false, name, 0);
+
+        for (entry_name, description) in entries {
+            let mut entry = TableEntry::new(Currency::Copper(1));
+            entry.set_name(*entry_name);
+
+            if let Some(description) = description {
+                entry.set_field("Description", *description);
+            }
+
+            table.push(entry);
+        }
+
+        table
+    }
+
+    #[test]
+    fn finds_entries_across_tables() {
+        let loot = table("Loot", &[("Gold coins", None), ("Driftglobe", None)]);
+        let magic = table("Magic items", &[("Bag of holding", None), ("Driftglobe lantern", None)]);
+
+        let results = search_entries([&loot, &magic], "driftglobe");
+        assert_eq!(vec![("Loot", "Driftglobe"), ("Magic items", "Driftglobe lantern")], results.iter().map(|r| (r.table_name(), r.entry_name())).collect::<Vec<_>>());
+        assert_eq!(loot.id(), results[0].table_id());
+        assert_eq!(loot.entries()[1].id(), results[0].entry_id());
+        assert!(search_entries([&loot, &magic], "  ").is_empty());
+    }
+
+    #[test]
+    fn matches_fuzzily() {
+        let loot = table("Loot", &[("Driftglobe", None), ("Rope", None)]);
+
+        let results = search_entries([&loot], "drftglb");
+        assert_eq!(vec!["Driftglobe"], results.iter().map(|r| r.entry_name()).collect::<Vec<_>>());
+        assert!(search_entries([&loot], "dragon").is_empty());
+    }
+
+    #[test]
+    fn matches_descriptions_with_snippets() {
+        let loot = table("Loot", &[("Glass orb", Some("A small sphere of thick glass that can float like a driftglobe and shine on command"))]);
+
+        let results = search_entries([&loot], "driftglobe");
+        assert_eq!(1, results.len());
+        assert_eq!(Some("Description"), results[0].field());
+        assert_eq!("Description: ...k glass that can float like a driftglobe and shine on command", results[0].snippet());
+    }
+}
@@ -0,0 +1,108 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Serialize, Deserialize};
+
+pub const DEFAULT_BACKUP_COUNT: usize = 3;
+pub const MAX_BACKUP_COUNT: usize = 20;
+pub const DEFAULT_API_PORT: u16 = 17423;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    backup_count: usize,
+    library_dir: Option<PathBuf>,
+    restore_session: bool,
+    api_enabled: bool,
+    api_port: u16,
+    api_token: String
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            backup_count: DEFAULT_BACKUP_COUNT,
+            library_dir: None,
+            restore_session: false,
+            api_enabled: false,
+            api_port: DEFAULT_API_PORT,
+            api_token: String::new()
+        }
+    }
+}
+
+impl Settings {
+    /// Brings values that bypassed the setters, such as ones read from disk or sent by the frontend, back into range.
+    pub fn sanitised(mut self) -> Self {
+        self.backup_count = self.backup_count.min(MAX_BACKUP_COUNT);
+
+        if self.api_port == 0 {
+            self.api_port = DEFAULT_API_PORT;
+        }
+
+        self
+    }
+
+    pub fn backup_count(&self) -> usize {
+        self.backup_count
+    }
+
+    pub fn set_backup_count(&mut self, backup_count: usize) {
+        self.backup_count = backup_count.min(MAX_BACKUP_COUNT);
+    }
+
+    pub fn library_dir(&self) -> Option<&Path> {
+        self.library_dir.as_deref()
+    }
+
+    pub fn set_library_dir(&mut self, library_dir: Option<PathBuf>) {
+        self.library_dir = library_dir;
+    }
+
+    pub fn restore_session(&self) -> bool {
+        self.restore_session
+    }
+
+    pub fn set_restore_session(&mut self, restore_session: bool) {
+        self.restore_session = restore_session;
+    }
+
+    pub fn api_enabled(&self) -> bool {
+        self.api_enabled
+    }
+
+    pub fn set_api_enabled(&mut self, api_enabled: bool) {
+        self.api_enabled = api_enabled;
+    }
+
+    pub fn api_port(&self) -> u16 {
+        self.api_port
+    }
+
+    pub fn set_api_port(&mut self, api_port: u16) {
+        self.api_port = api_port;
+    }
+
+    pub fn api_token(&self) -> &str {
+        &self.api_token
+    }
+
+    pub fn set_api_token(&mut self, api_token: impl Into<String>) {
+        self.api_token = api_token.into();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitised_clamps_deserialised_values() {
+        let settings = serde_json::from_str::<Settings>(r#"{"backup_count": 500, "api_port": 0, "restore_session": true}"#).unwrap();
+        assert_eq!(500, settings.backup_count());
+
+        let settings = settings.sanitised();
+        assert_eq!(MAX_BACKUP_COUNT, settings.backup_count());
+        assert_eq!(DEFAULT_API_PORT, settings.api_port());
+        assert!(settings.restore_session());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,115 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{Balance, Currency, RollResult};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LootShare {
+    items: Vec<RollResult>,
+    item_value: Currency,
+    settlement: Balance
+}
+
+impl LootShare {
+    pub fn items(&self) -> &Vec<RollResult> {
+        &self.items
+    }
+
+    pub fn item_value(&self) -> Currency {
+        self.item_value
+    }
+
+    pub fn settlement(&self) -> Balance {
+        self.settlement
+    }
+
+    pub fn change(&self) -> Vec<Currency> {
+        self.settlement.magnitude().to_denominations()
+    }
+
+    pub fn total_value(&self) -> Balance {
+        Balance::from(self.item_value) + self.settlement
+    }
+}
+
+pub fn split_loot(results: &[RollResult], members: usize) -> Vec<LootShare> {
+    if members == 0 {
+        return Vec::new();
+    }
+
+    let mut units = results.iter()
+        .enumerate()
+        .flat_map(|(index, result)| std::iter::repeat_n(index, result.count()))
+        .collect::<Vec<_>>();
+
+    units.sort_by_key(|index| std::cmp::Reverse(u64::from(results[*index].entry().cost())));
+
+    let mut values = vec![0_u64; members];
+    let mut counts = vec![vec![0_usize; results.len()]; members];
+
+    for index in units {
+        let share = (0..members).min_by_key(|share| values[*share]).unwrap_or(0);
+        values[share] += u64::from(results[index].entry().cost());
+        counts[share][index] += 1;
+    }
+
+    let total = values.iter().map(|v| Balance::from(Currency::from(*v))).sum::<Balance>();
+    let targets = total.split(members);
+
+    values.into_iter()
+        .zip(counts)
+        .zip(targets)
+        .map(|((value, counts), target)| {
+            let items = counts.into_iter()
+                .enumerate()
+                .filter(|(_, count)| *count > 0)
+                .map(|(index, count)| RollResult::new(count, results[index].entry().clone()))
+                .collect();
+
+            let item_value = Currency::from(value);
+
+            LootShare {
+                items,
+                item_value,
+                settlement: target - Balance::from(item_value)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TableEntry;
+
+    use super::*;
+
+    fn result(name: &str, count: usize, cost: Currency) -> RollResult {
+        let mut entry = TableEntry::new(cost);
+        entry.set_name(name);
+        RollResult::new(count, entry)
+    }
+
+    #[test]
+    fn split_balances_shares() {
+        let results = vec![
+            result("Sword", 1, Currency::Gold(15)),
+            result("Potion", 2, Currency::Gold(5)),
+            result("Gem", 1, Currency::Gold(10)),
+        ];
+
+        let shares = split_loot(&results, 2);
+        assert_eq!(2, shares.len());
+        assert!(shares.iter().all(|s| s.total_value() == Balance::from_copper(1750)));
+        assert_eq!(Balance::default(), shares.iter().map(|s| s.settlement()).sum());
+    }
+
+    #[test]
+    fn uneven_split_is_settled_with_change() {
+        let results = vec![result("Crown", 1, Currency::Gold(9))];
+
+        let shares = split_loot(&results, 2);
+        assert_eq!(Balance::from_copper(-450), shares[0].settlement());
+        assert_eq!(vec![Currency::Gold(4), Currency::Silver(5)], shares[0].change());
+        assert!(shares[1].items().is_empty());
+        assert_eq!(Balance::from_copper(450), shares[1].settlement());
+    }
+}
\ No newline at end of file
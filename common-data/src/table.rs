@@ -1,4 +1,4 @@
-use std::{path::PathBuf, cmp::Ordering, sync::{Arc, Mutex, MutexGuard}, slice::Iter, collections::HashMap};
+use std::{path::PathBuf, cmp::Ordering, sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard}, slice::Iter, collections::{HashSet, BTreeMap}};
 
 use rand::{rngs::StdRng, SeedableRng, Rng};
 use serde::{Serialize, Deserialize};
@@ -43,6 +43,10 @@ pub struct RollResult {
 }
 
 impl RollResult {
+    pub fn new(count: usize, entry: TableEntry) -> Self {
+        Self { count, entry }
+    }
+
     pub fn count(&self) -> usize {
         self.count
     }
@@ -54,9 +58,13 @@ impl RollResult {
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TableEntry {
+    #[serde(default = "Uuid::new_v4")]
+    id: Uuid,
     weight: usize,
     name: String,
-    cost: Currency
+    cost: Currency,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    fields: BTreeMap<String, String>
 }
 
 impl PartialOrd for TableEntry {
@@ -74,20 +82,36 @@ impl Ord for TableEntry {
 impl TableEntry {
     pub fn new(cost: Currency) -> Self {
         Self {
+            id: Uuid::new_v4(),
             weight: 1,
             name: String::new(),
-            cost
+            cost,
+            fields: BTreeMap::new()
         }
     }
 
     pub fn with_weight(weight: usize, cost: Currency) -> Self {
         Self {
+            id: Uuid::new_v4(),
             name: String::new(),
             weight,
-            cost
+            cost,
+            fields: BTreeMap::new()
         }
     }
 
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn set_id(&mut self, id: Uuid) {
+        self.id = id;
+    }
+
+    pub fn regenerate_id(&mut self) {
+        self.id = Uuid::new_v4();
+    }
+
     pub fn weight(&self) -> usize {
         self.weight
     }
@@ -115,17 +139,33 @@ impl TableEntry {
     pub fn set_cost(&mut self, cost: Currency) {
         self.cost = cost;
     }
+
+    pub fn fields(&self) -> &BTreeMap<String, String> {
+        &self.fields
+    }
+
+    pub fn field(&self, key: &str) -> Option<&str> {
+        self.fields.get(key).map(|v| v.as_str())
+    }
+
+    pub fn set_field(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.fields.insert(key.into(), value.into());
+    }
+
+    pub fn remove_field(&mut self, key: &str) -> Option<String> {
+        self.fields.remove(key)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Table {
-    data: Arc<Mutex<TableData>>
+    data: Arc<RwLock<TableData>>
 }
 
 impl From<TableData> for Table {
     fn from(value: TableData) -> Self {
         Self {
-            data: Arc::new(Mutex::new(value))
+            data: Arc::new(RwLock::new(value))
         }
     }
 }
@@ -134,7 +174,9 @@ impl Serialize for Table {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         where
             S: serde::Serializer {
-        self.data.lock().unwrap().serialize(serializer)
+        self.read_data()
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
     }
 }
 
@@ -143,7 +185,7 @@ impl<'de> Deserialize<'de> for Table {
         where
             D: serde::Deserializer<'de> {
         let data = TableData::deserialize(deserializer)?;
-        Ok(Self { data: Arc::new(Mutex::new(data)) })
+        Ok(Self { data: Arc::new(RwLock::new(data)) })
     }
 }
 
@@ -152,18 +194,22 @@ impl Table {
         let table = TableData::new(use_cost, use_weight, name, order);
         let id = table.id();
 
-        (id, Self { data: Arc::new(Mutex::new(table)) })
+        (id, Self { data: Arc::new(RwLock::new(table)) })
     }
 
     pub fn with_capacity(use_cost: bool, use_weight: bool, name: impl Into<String>, capacity: usize, order: usize) -> (Uuid, Self) {
         let table = TableData::with_capacity(use_cost, use_weight, name, capacity, order);
         let id = table.id();
 
-        (id, Self { data: Arc::new(Mutex::new(table)) })
+        (id, Self { data: Arc::new(RwLock::new(table)) })
+    }
+
+    pub fn read_data(&self) -> Result<RwLockReadGuard<TableData>, BackendError> {
+        self.data.read().map_err(|_| BackendError::internal_error("Unable to lock table data for reading"))
     }
 
-    pub fn get_data(&self) -> Result<MutexGuard<TableData>, BackendError> {
-        self.data.lock().map_err(|_| BackendError::internal_error("Unable to lock table data"))
+    pub fn write_data(&self) -> Result<RwLockWriteGuard<TableData>, BackendError> {
+        self.data.write().map_err(|_| BackendError::internal_error("Unable to lock table data for writing"))
     }
 }
 
@@ -173,22 +219,55 @@ pub struct FileTableData {
     use_weight: bool,
     name: String,
     entries: Vec<TableEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>
 }
 
 impl FileTableData {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
+    pub fn entries(&self) -> &Vec<TableEntry> {
+        &self.entries
+    }
+
     pub fn into_table_data(self, order: usize, path: Option<PathBuf>) -> TableData {
+        self.into_table_data_with_id(Uuid::new_v4(), order, path)
+    }
+
+    pub fn into_table_data_with_id(self, id: Uuid, order: usize, path: Option<PathBuf>) -> TableData {
         TableData {
             use_weight: self.use_weight,
             use_cost: self.use_cost,
-            id: Uuid::new_v4(),
+            id,
             order,
             name: self.name,
-            entries: self.entries,
-            path
+            entries: with_unique_ids(self.entries),
+            tags: self.tags,
+            path,
+            modified: false
         }
     }
 }
 
+fn with_unique_ids(entries: Vec<TableEntry>) -> Vec<TableEntry> {
+    let mut ids = HashSet::with_capacity(entries.len());
+
+    entries.into_iter()
+        .map(|mut entry| {
+            while !ids.insert(entry.id()) {
+                entry.regenerate_id();
+            }
+            entry
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TableData {
     use_cost: bool,
@@ -198,7 +277,11 @@ pub struct TableData {
     order: usize,
     name: String,
     entries: Vec<TableEntry>,
-    path: Option<PathBuf>
+    #[serde(default)]
+    tags: Vec<String>,
+    path: Option<PathBuf>,
+    #[serde(default)]
+    modified: bool
 }
 
 impl TableData {
@@ -210,7 +293,9 @@ impl TableData {
             id: Uuid::new_v4(),
             name: name.into(),
             entries: Vec::new(),
-            path: None
+            tags: Vec::new(),
+            path: None,
+            modified: true
         }
     }
 
@@ -222,7 +307,9 @@ impl TableData {
             id: Uuid::new_v4(),
             name: name.into(),
             entries: Vec::with_capacity(capacity),
-            path: None
+            tags: Vec::new(),
+            path: None,
+            modified: true
         }
     }
 
@@ -231,14 +318,30 @@ impl TableData {
             use_weight: self.use_weight,
             use_cost: self.use_cost,
             name: self.name.clone(),
-            entries: self.entries.clone()
+            entries: self.entries.clone(),
+            tags: self.tags.clone()
         }
     }
 
+    pub fn field_names(&self) -> Vec<String> {
+        let mut names = self.entries.iter()
+            .flat_map(|e| e.fields.keys().cloned())
+            .collect::<Vec<_>>();
+
+        names.sort();
+        names.dedup();
+        names
+    }
+
     pub fn total_weight(&self) -> usize {
         self.entries.iter().map(|e| e.weight).sum()
     }
 
+    /// The weight `entry` rolls with in this table, which is always 1 when the table doesn't use weights.
+    pub fn roll_weight(&self, entry: &TableEntry) -> usize {
+        if self.use_weight { entry.weight } else { 1 }
+    }
+
     pub fn total_cost(&self) -> Currency {
         self.entries.iter().map(|e| e.cost()).sum()
     }
@@ -253,6 +356,7 @@ impl TableData {
 
     pub fn set_use_cost(&mut self, use_cost: bool) {
         self.use_cost = use_cost;
+        self.modified = true;
     }
 
     pub fn use_weight(&self) -> bool {
@@ -261,6 +365,7 @@ impl TableData {
 
     pub fn set_use_weight(&mut self, use_weight: bool) {
         self.use_weight = use_weight;
+        self.modified = true;
     }
 
     pub fn id(&self) -> Uuid {
@@ -281,6 +386,24 @@ impl TableData {
 
     pub fn set_name(&mut self, name: impl Into<String>) {
         self.name = name.into();
+        self.modified = true;
+    }
+
+    pub fn tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
+    pub fn set_tags(&mut self, tags: Vec<String>) {
+        self.tags = tags;
+        self.modified = true;
+    }
+
+    pub fn is_modified(&self) -> bool {
+        self.modified
+    }
+
+    pub fn set_modified(&mut self, modified: bool) {
+        self.modified = modified;
     }
 
     pub fn path(&self) -> Option<PathBuf> {
@@ -295,12 +418,22 @@ impl TableData {
         self.entries.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
     pub fn iter(&self) -> Iter<TableEntry> {
         self.entries.iter()
     }
 
     pub fn push(&mut self, item: impl Into<TableEntry>) {
-        self.entries.push(item.into());
+        let mut entry = item.into();
+        while self.entry_index(entry.id()).is_some() {
+            entry.regenerate_id();
+        }
+
+        self.entries.push(entry);
+        self.modified = true;
     }
 
     pub fn get(&self, index: usize) -> Option<&TableEntry> {
@@ -308,25 +441,81 @@ impl TableData {
     }
 
     pub fn get_mut(&mut self, index: usize) -> Option<&mut TableEntry> {
+        self.modified = true;
         self.entries.get_mut(index)
     }
 
+    pub fn entry_index(&self, id: Uuid) -> Option<usize> {
+        self.entries.iter().position(|e| e.id() == id)
+    }
+
+    pub fn get_entry(&self, id: Uuid) -> Option<&TableEntry> {
+        self.entries.iter().find(|e| e.id() == id)
+    }
+
+    pub fn get_entry_mut(&mut self, id: Uuid) -> Option<&mut TableEntry> {
+        let index = self.entry_index(id)?;
+        self.get_mut(index)
+    }
+
+    pub fn remove_entry(&mut self, id: Uuid) -> Option<TableEntry> {
+        let index = self.entry_index(id)?;
+        self.remove(index)
+    }
+
+    pub fn move_entry(&mut self, id: Uuid, index: usize) -> Option<usize> {
+        let current = self.entry_index(id)?;
+        let index = index.min(self.len() - 1);
+
+        if current != index {
+            let entry = self.entries.remove(current);
+            self.entries.insert(index, entry);
+            self.modified = true;
+        }
+
+        Some(index)
+    }
+
     pub fn entries(&self) -> &Vec<TableEntry> {
         &self.entries
     }
 
     pub fn entries_mut(&mut self) -> &mut Vec<TableEntry> {
+        self.modified = true;
         &mut self.entries
     }
 
     pub fn set_entries(&mut self, entries: Vec<TableEntry>) {
-        self.entries = entries;
+        self.entries = with_unique_ids(entries);
+        self.modified = true;
+    }
+
+    pub fn merge_entries(&mut self, entries: impl IntoIterator<Item = TableEntry>) -> usize {
+        let mut added = 0;
+
+        for mut entry in entries {
+            if !self.entries.iter().any(|e| e.name() == entry.name()) {
+                while self.entry_index(entry.id()).is_some() {
+                    entry.regenerate_id();
+                }
+
+                self.entries.push(entry);
+                added += 1;
+            }
+        }
+
+        if added > 0 {
+            self.modified = true;
+        }
+
+        added
     }
 
     pub fn remove(&mut self, index: usize) -> Option<TableEntry> {
         if index >= self.len() {
             None
         } else {
+            self.modified = true;
             Some(self.entries.remove(index))
         }
     }
@@ -353,10 +542,18 @@ impl TableData {
 
     pub fn get_random_set_by_count(&self, use_weights: bool, count: usize, allow_duplicates: bool) -> Result<Vec<RollResult>, getrandom::Error> {
         let mut rng = create_rng()?;
-        let mut rolls: HashMap<usize, usize> = HashMap::new();
+        Ok(self.get_random_set_by_count_with_rng(&mut rng, use_weights, count, allow_duplicates))
+    }
+
+    pub fn get_random_set_by_count_with_rng<R: Rng>(&self, rng: &mut R, use_weights: bool, count: usize, allow_duplicates: bool) -> Vec<RollResult> {
+        let mut rolls: BTreeMap<usize, usize> = BTreeMap::new();
 
         for _ in 0..count {
             let allowed = self.get_allowed_indexes(use_weights, |i, _| allow_duplicates || !rolls.contains_key(&i));
+            if allowed.is_empty() {
+                break;
+            }
+
             let roll = allowed[rng.gen_range(0..allowed.len())];
 
             match rolls.get_mut(&roll) {
@@ -374,18 +571,25 @@ impl TableData {
 
         output.sort_by(|a, b| a.entry().cmp(&b.entry()));
 
-        Ok(output)
+        output
     }
 
     pub fn get_random_set_by_cost(&self, use_weights: bool, cost: Currency, allow_duplicates: bool) -> Result<Vec<RollResult>, getrandom::Error> {
-        let mut remaining = cost;
         let mut rng = create_rng()?;
-        let mut rolls: HashMap<usize, usize> = HashMap::new();
+        Ok(self.get_random_set_by_cost_with_rng(&mut rng, use_weights, cost, allow_duplicates))
+    }
+
+    pub fn get_random_set_by_cost_with_rng<R: Rng>(&self, rng: &mut R, use_weights: bool, cost: Currency, allow_duplicates: bool) -> Vec<RollResult> {
+        let mut remaining = cost;
+        let mut rolls: BTreeMap<usize, usize> = BTreeMap::new();
 
         while self.entries.iter().any(|entry| entry.cost() <= remaining) {
             let allowed =  self.get_allowed_indexes(use_weights, |i, e| {
                 e.cost() <= remaining && (allow_duplicates || !rolls.contains_key(&i))
             });
+            if allowed.is_empty() {
+                break;
+            }
 
             let roll = allowed[rng.gen_range(0..allowed.len())];
 
@@ -406,7 +610,7 @@ impl TableData {
 
         output.sort_by(|a, b| a.entry().cmp(&b.entry()));
 
-        Ok(output)
+        output
     }
 }
 
@@ -415,4 +619,8 @@ pub fn create_rng() -> Result<StdRng, getrandom::Error> {
     getrandom::getrandom(&mut buffer)?;
 
     Ok(StdRng::from_seed(buffer))
+}
+
+pub fn create_seeded_rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
 }
\ No newline at end of file
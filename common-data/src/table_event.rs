@@ -0,0 +1,24 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+use crate::TableSummary;
+
+pub const TABLE_EVENT: &str = "table-event";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TableEvent {
+    Added(TableSummary),
+    Removed(Uuid),
+    Changed(TableSummary),
+    Reordered(Vec<Uuid>)
+}
+
+impl TableEvent {
+    pub fn id(&self) -> Option<Uuid> {
+        match self {
+            Self::Added(summary) | Self::Changed(summary) => Some(summary.id()),
+            Self::Removed(id) => Some(*id),
+            Self::Reordered(_) => None
+        }
+    }
+}
\ No newline at end of file
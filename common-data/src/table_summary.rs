@@ -0,0 +1,35 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+use crate::TableData;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableSummary {
+    id: Uuid,
+    name: String,
+    modified: bool
+}
+
+impl TableSummary {
+    pub fn new(id: Uuid, name: String, modified: bool) -> Self {
+        Self { id, name, modified }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_modified(&self) -> bool {
+        self.modified
+    }
+}
+
+impl From<&TableData> for TableSummary {
+    fn from(value: &TableData) -> Self {
+        Self::new(value.id(), value.name().to_string(), value.is_modified())
+    }
+}
\ No newline at end of file
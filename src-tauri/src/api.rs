@@ -0,0 +1,443 @@
+use std::{io::{self, BufRead, BufReader, ErrorKind, Read, Write}, net::{TcpListener, TcpStream}, sync::{Arc, Mutex, RwLock, atomic::{AtomicBool, AtomicUsize, Ordering}, mpsc::{self, Receiver, SyncSender, TryRecvError, TrySendError}}, thread, time::{Duration, Instant}};
+
+use common_data::{BackendError, RollResult};
+use serde::Serialize;
+use serde_json::Value;
+use tungstenite::{handshake::derive_accept_key, protocol::Role, Message, WebSocket};
+use uuid::Uuid;
+
+const COMMAND_PATH: &str = "/api/";
+const EVENTS_PATH: &str = "/api/events";
+
+/// How long a client has to send its whole request, however slowly it trickles in.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Connections beyond this, including open event clients, are turned away.
+const MAX_CONNECTIONS: usize = 32;
+const MAX_LINE_LENGTH: u64 = 8 * 1024;
+const MAX_HEADER_COUNT: usize = 64;
+const MAX_BODY_LENGTH: usize = 1024 * 1024;
+
+/// How many events may queue up for a client before it is considered too slow and dropped.
+const CLIENT_QUEUE_SIZE: usize = 64;
+const CLIENT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+const CLIENT_WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+type Client = SyncSender<String>;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum ApiEvent {
+    #[serde(rename = "roll")]
+    Roll {
+        #[serde(rename = "tableId")]
+        table_id: Uuid,
+        #[serde(rename = "tableName")]
+        table_name: String,
+        results: Vec<RollResult>
+    }
+}
+
+pub struct ApiServer {
+    port: u16,
+    stopped: Arc<AtomicBool>,
+    token: Arc<RwLock<String>>,
+    clients: Arc<Mutex<Vec<Client>>>
+}
+
+impl ApiServer {
+    pub fn start<F>(port: u16, token: impl Into<String>, handler: F) -> Result<Self, BackendError>
+        where F: Fn(&str, Value) -> Result<Value, BackendError> + Send + Sync + 'static {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .map_err(|e| BackendError::internal_error(format!("Could not start the local API on port {}: {}", port, e)))?;
+        let stopped = Arc::new(AtomicBool::new(false));
+        let token = Arc::new(RwLock::new(token.into()));
+        let clients = Arc::new(Mutex::new(Vec::new()));
+        let connections = Arc::new(AtomicUsize::new(0));
+        let handler = Arc::new(handler);
+
+        {
+            let stopped = stopped.clone();
+            let token = token.clone();
+            let clients = clients.clone();
+
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stopped.load(Ordering::SeqCst) {
+                        break;
+                    }
+
+                    match stream {
+                        Ok(stream) => {
+                            let slot = match ConnectionSlot::take(&connections) {
+                                Some(slot) => slot,
+                                None => {
+                                    log::warn!("Turning away an API connection, {} are already open", MAX_CONNECTIONS);
+                                    stream.set_write_timeout(Some(REQUEST_TIMEOUT)).ok();
+                                    respond_json(&stream, 503, &BackendError::internal_error("Too many open connections")).ok();
+                                    continue;
+                                }
+                            };
+
+                            let token = token.clone();
+                            let clients = clients.clone();
+                            let handler = handler.clone();
+
+                            thread::spawn(move || {
+                                if let Err(e) = handle_connection(stream, &token, &clients, handler.as_ref()) {
+                                    log::error!("Failed to respond to API request: {}", e);
+                                }
+
+                                drop(slot);
+                            });
+                        },
+                        Err(e) => log::error!("Failed to accept API connection: {}", e)
+                    }
+                }
+
+                log::info!("Local API on port {} stopped", port);
+            });
+        }
+
+        log::info!("Local API listening on http://127.0.0.1:{}", port);
+
+        Ok(Self {
+            port,
+            stopped,
+            token,
+            clients
+        })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn set_token(&self, token: impl Into<String>) -> Result<(), BackendError> {
+        let mut current = self.token.write().map_err(|_| BackendError::internal_error("Unable to lock API token"))?;
+        *current = token.into();
+        Ok(())
+    }
+
+    /// Queues the event for every connected client without waiting on any of them. Clients whose queue is full are dropped.
+    pub fn broadcast<T: Serialize>(&self, event: &T) -> Result<(), BackendError> {
+        let text = serde_json::to_string(event)?;
+        let mut clients = self.clients.lock().map_err(|_| BackendError::internal_error("Unable to lock API clients"))?;
+
+        clients.retain(|client| match client.try_send(text.clone()) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                log::warn!("Dropping an API event client that stopped reading events");
+                false
+            },
+            Err(TrySendError::Disconnected(_)) => false
+        });
+
+        Ok(())
+    }
+}
+
+impl Drop for ApiServer {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+
+        // Wake the accept loop so it sees the stop flag and releases the port
+        TcpStream::connect(("127.0.0.1", self.port)).ok();
+
+        // Dropping the senders makes each client thread close its socket
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.clear();
+        }
+    }
+}
+
+/// One of the `MAX_CONNECTIONS` connections the server allows at a time, given back when dropped.
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+    fn take(connections: &Arc<AtomicUsize>) -> Option<Self> {
+        connections.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| (count < MAX_CONNECTIONS).then_some(count + 1))
+            .ok()
+            .map(|_| Self(connections.clone()))
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Reads from the stream until the deadline, shortening the socket's read timeout as it approaches.
+struct DeadlineReader {
+    stream: TcpStream,
+    deadline: Instant
+}
+
+impl Read for DeadlineReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::Error::new(ErrorKind::TimedOut, "The request took too long to arrive"));
+        }
+
+        self.stream.set_read_timeout(Some(remaining))?;
+        self.stream.read(buf)
+    }
+}
+
+struct Request {
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>
+}
+
+impl Request {
+    fn path(&self) -> &str {
+        self.url.split('?').next().unwrap_or_default()
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(field, _)| field.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn read_line<R: BufRead>(reader: &mut R) -> Result<String, BackendError> {
+    let mut line = String::new();
+    if reader.by_ref().take(MAX_LINE_LENGTH).read_line(&mut line)? == 0 {
+        return Err(BackendError::argument_error("request", "Connection closed before the request was complete"));
+    }
+
+    if !line.ends_with('\n') {
+        return Err(BackendError::argument_error("request", "Request line or header is too long"));
+    }
+
+    Ok(line.trim_end().to_string())
+}
+
+fn read_request<R: BufRead>(reader: &mut R) -> Result<Request, BackendError> {
+    let request_line = read_line(reader)?;
+    let mut parts = request_line.split_whitespace();
+    let (method, url) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(method), Some(url), Some(version)) if version.starts_with("HTTP/") => (method.to_string(), url.to_string()),
+        _ => return Err(BackendError::argument_error("request", format!("Malformed request line '{}'", request_line)))
+    };
+
+    let mut headers = Vec::new();
+    loop {
+        let line = read_line(reader)?;
+        if line.is_empty() {
+            break;
+        }
+
+        if headers.len() >= MAX_HEADER_COUNT {
+            return Err(BackendError::argument_error("request", "Too many headers"));
+        }
+
+        match line.split_once(':') {
+            Some((name, value)) => headers.push((name.trim().to_string(), value.trim().to_string())),
+            None => return Err(BackendError::argument_error("request", format!("Malformed header '{}'", line)))
+        }
+    }
+
+    let mut request = Request { method, url, headers, body: Vec::new() };
+
+    if request.header("Transfer-Encoding").is_some() {
+        return Err(BackendError::argument_error("Transfer-Encoding", "Chunked request bodies are not supported, send a Content-Length instead"));
+    }
+
+    let length = match request.header("Content-Length") {
+        Some(length) => length.parse::<usize>().map_err(|_| BackendError::argument_error("Content-Length", format!("Invalid length '{}'", length)))?,
+        None => 0
+    };
+
+    if length > MAX_BODY_LENGTH {
+        return Err(BackendError::argument_error("Content-Length", format!("Request bodies are limited to {} bytes", MAX_BODY_LENGTH)));
+    }
+
+    request.body = vec![0; length];
+    reader.read_exact(&mut request.body)?;
+
+    Ok(request)
+}
+
+fn handle_connection<F>(stream: TcpStream, token: &RwLock<String>, clients: &Mutex<Vec<Client>>, handler: &F) -> Result<(), BackendError>
+    where F: Fn(&str, Value) -> Result<Value, BackendError> {
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+
+    let mut reader = BufReader::new(DeadlineReader { stream: stream.try_clone()?, deadline: Instant::now() + REQUEST_TIMEOUT });
+    let request = match read_request(&mut reader) {
+        Ok(request) => request,
+        Err(e) => return respond_json(&stream, 400, &e)
+    };
+
+    let path = request.path();
+
+    if path == EVENTS_PATH {
+        // Browsers can't set headers on a WebSocket, so the token may also come as one of the offered subprotocols,
+        // which the handshake has to echo back
+        let protocol = match authorize_events(&request, token) {
+            Some(protocol) => protocol,
+            None => return respond_json(&stream, 401, &BackendError::argument_error("token", "Missing or invalid API token"))
+        };
+
+        return match request.header("Sec-WebSocket-Key") {
+            Some(key) => {
+                let accept = derive_accept_key(key.as_bytes());
+                let mut headers = vec![("Upgrade", "websocket"), ("Connection", "Upgrade"), ("Sec-WebSocket-Accept", accept.as_str())];
+                if let Some(protocol) = &protocol {
+                    headers.push(("Sec-WebSocket-Protocol", protocol));
+                }
+
+                write_response(&stream, 101, &headers, &[])?;
+
+                // Frames the client sent straight after the handshake may already be sitting in the reader's buffer
+                let buffered = reader.buffer().to_vec();
+                add_client(stream, buffered, clients)
+            },
+            None => respond_json(&stream, 400, &BackendError::argument_error("Sec-WebSocket-Key", "Expected a WebSocket upgrade request"))
+        };
+    }
+
+    if !is_authorized(&request, token) {
+        return respond_json(&stream, 401, &BackendError::argument_error("token", "Missing or invalid API token"));
+    }
+
+    let command = match path.strip_prefix(COMMAND_PATH) {
+        Some(command) if !command.is_empty() => command,
+        _ => return respond_json(&stream, 404, &BackendError::argument_error("path", format!("Unknown path '{}'", path)))
+    };
+
+    let body = match std::str::from_utf8(&request.body) {
+        Ok(body) => body,
+        Err(e) => return respond_json(&stream, 400, &BackendError::argument_error("body", e.to_string()))
+    };
+
+    let args = if body.trim().is_empty() {
+        Ok(Value::Object(Default::default()))
+    } else {
+        serde_json::from_str::<Value>(body)
+    };
+
+    let result = args.map_err(|e| BackendError::argument_error("body", e.to_string())).and_then(|args| handler(command, args));
+
+    match result {
+        Ok(value) => respond_json(&stream, 200, &value),
+        Err(e @ (BackendError::ArgumentError { .. } | BackendError::ValidationError { .. } | BackendError::ParseError { .. })) => respond_json(&stream, 400, &e),
+        Err(e @ BackendError::NotFound { .. }) => respond_json(&stream, 404, &e),
+        Err(e) => respond_json(&stream, 500, &e)
+    }
+}
+
+fn add_client(stream: TcpStream, buffered: Vec<u8>, clients: &Mutex<Vec<Client>>) -> Result<(), BackendError> {
+    stream.set_read_timeout(Some(CLIENT_POLL_INTERVAL))?;
+    stream.set_write_timeout(Some(CLIENT_WRITE_TIMEOUT))?;
+
+    let (sender, events) = mpsc::sync_channel(CLIENT_QUEUE_SIZE);
+    clients.lock().map_err(|_| BackendError::internal_error("Unable to lock API clients"))?.push(sender);
+
+    serve_client(WebSocket::from_partially_read(stream, buffered, Role::Server, None), events);
+    Ok(())
+}
+
+/// Writes queued events to a single client and reads its frames so pings are answered and closes are noticed.
+/// Reads time out every `CLIENT_POLL_INTERVAL` so new events go out without waiting on the client.
+fn serve_client(mut socket: WebSocket<TcpStream>, events: Receiver<String>) {
+    loop {
+        loop {
+            match events.try_recv() {
+                Ok(text) => {
+                    if let Err(e) = socket.send(Message::Text(text)) {
+                        log::warn!("Dropping an API event client that could not be written to: {}", e);
+                        return;
+                    }
+                },
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    socket.close(None).ok();
+                    socket.flush().ok();
+                    return;
+                }
+            }
+        }
+
+        match socket.read() {
+            Ok(_) => {},
+            Err(tungstenite::Error::Io(e)) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {},
+            Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => return,
+            Err(e) => {
+                log::warn!("Dropping an API event client after a read error: {}", e);
+                return;
+            }
+        }
+    }
+}
+
+fn matches_token(value: &str, token: &RwLock<String>) -> bool {
+    match token.read() {
+        Ok(token) if !token.is_empty() => constant_time_eq(value.as_bytes(), token.as_bytes()),
+        _ => false
+    }
+}
+
+/// Compares every byte regardless of where the first difference is, so response times don't reveal how much of a guess was right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |difference, (x, y)| difference | (x ^ y)) == 0
+}
+
+fn is_authorized(request: &Request, token: &RwLock<String>) -> bool {
+    request.header("Authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|value| matches_token(value.trim(), token))
+        .unwrap_or(false)
+}
+
+/// Authorizes an event client by its `Authorization` header or by an offered subprotocol that is the token.
+/// Returns the subprotocol to accept, if the token came that way.
+fn authorize_events(request: &Request, token: &RwLock<String>) -> Option<Option<String>> {
+    if is_authorized(request, token) {
+        return Some(None);
+    }
+
+    request.header("Sec-WebSocket-Protocol")?
+        .split(',')
+        .map(str::trim)
+        .find(|protocol| matches_token(protocol, token))
+        .map(|protocol| Some(protocol.to_string()))
+}
+
+fn status_text(status: u16) -> &'static str {
+    match status {
+        101 => "Switching Protocols",
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error"
+    }
+}
+
+fn write_response(mut stream: &TcpStream, status: u16, headers: &[(&str, &str)], body: &[u8]) -> Result<(), BackendError> {
+    let mut response = format!("HTTP/1.1 {} {}\r\n", status, status_text(status));
+    for (name, value) in headers {
+        response.push_str(&format!("{}: {}\r\n", name, value));
+    }
+
+    response.push_str("\r\n");
+
+    let mut bytes = response.into_bytes();
+    bytes.extend_from_slice(body);
+    stream.write_all(&bytes)?;
+    stream.flush()?;
+    Ok(())
+}
+
+fn respond_json<T: Serialize>(stream: &TcpStream, status: u16, value: &T) -> Result<(), BackendError> {
+    let text = serde_json::to_string(value).unwrap_or_else(|e| format!("\"{}\"", e));
+    let length = text.len().to_string();
+    write_response(stream, status, &[("Content-Type", "application/json"), ("Content-Length", &length), ("Connection", "close")], text.as_bytes())
+}
@@ -0,0 +1,31 @@
+use std::{collections::HashSet, path::{Path, PathBuf}};
+
+use common_data::BackendError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GrantKind {
+    Import,
+    Export
+}
+
+/// Paths the user picked in an import or export dialog. The import and export commands only touch a path granted here, so the
+/// webview can't read or write files the user never chose.
+#[derive(Debug, Default)]
+pub struct FileGrants {
+    grants: HashSet<(GrantKind, PathBuf)>
+}
+
+impl FileGrants {
+    pub fn grant(&mut self, kind: GrantKind, path: &Path) {
+        self.grants.insert((kind, path.to_path_buf()));
+    }
+
+    /// Uses up the grant for `path`. Each pick of a dialog allows a single import or export.
+    pub fn take(&mut self, kind: GrantKind, path: &Path) -> Result<(), BackendError> {
+        if self.grants.remove(&(kind, path.to_path_buf())) {
+            Ok(())
+        } else {
+            Err(BackendError::argument_error("path", format!("{:?} was not chosen in a file dialog", path)))
+        }
+    }
+}
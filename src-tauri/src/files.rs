@@ -0,0 +1,84 @@
+use std::{path::{Path, PathBuf}, fs::{self, File}, io::{self, Write}};
+
+use common_data::{BackendError, FileTableData};
+use serde::Serialize;
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn get_temp_path(path: &Path) -> PathBuf {
+    path.with_file_name(format!(".{}.tmp", file_name(path)))
+}
+
+pub fn get_backup_path(path: &Path, index: usize) -> PathBuf {
+    path.with_file_name(format!("{}.{}.bak", file_name(path), index))
+}
+
+fn write_and_sync(path: &Path, contents: &[u8]) -> Result<(), io::Error> {
+    let mut file = File::create(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+pub fn read_table_file(path: &Path) -> Result<FileTableData, BackendError> {
+    let file = File::open(path).map_err(|e| BackendError::io_error(path, &e))?;
+    serde_json::from_reader(file).map_err(|e| BackendError::parse_error(path, &e))
+}
+
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), BackendError> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() && !parent.exists() {
+            fs::create_dir_all(parent).map_err(|e| BackendError::io_error(parent, &e))?;
+        }
+    }
+
+    let temp_path = get_temp_path(path);
+    let result = write_and_sync(&temp_path, contents).and_then(|_| fs::rename(&temp_path, path));
+
+    if let Err(e) = result {
+        if temp_path.exists() {
+            fs::remove_file(&temp_path).map_err(|e| BackendError::io_error(&temp_path, &e))?;
+        }
+
+        return Err(BackendError::io_error(path, &e));
+    }
+
+    Ok(())
+}
+
+pub fn write_json_atomic<T: Serialize>(path: &Path, value: &T) -> Result<(), BackendError> {
+    let contents = serde_json::to_vec_pretty(value)?;
+    write_atomic(path, &contents)
+}
+
+pub fn rotate_backups(path: &Path, count: usize) -> Result<(), BackendError> {
+    if count == 0 || !path.exists() {
+        return Ok(());
+    }
+
+    let oldest = get_backup_path(path, count);
+    if oldest.exists() {
+        fs::remove_file(&oldest).map_err(|e| BackendError::io_error(&oldest, &e))?;
+    }
+
+    for index in (1..count).rev() {
+        let backup = get_backup_path(path, index);
+        if backup.exists() {
+            fs::rename(&backup, get_backup_path(path, index + 1)).map_err(|e| BackendError::io_error(&backup, &e))?;
+        }
+    }
+
+    fs::copy(path, get_backup_path(path, 1)).map_err(|e| BackendError::io_error(path, &e))?;
+
+    Ok(())
+}
+
+pub fn list_backups(path: &Path) -> Vec<PathBuf> {
+    (1..)
+        .map(|index| get_backup_path(path, index))
+        .take_while(|backup| backup.exists())
+        .collect()
+}
\ No newline at end of file
@@ -0,0 +1,53 @@
+use std::{collections::VecDeque, time::{Duration, Instant}};
+
+use common_data::{TableData, history::HistoryState};
+
+pub const MAX_HISTORY_DEPTH: usize = 100;
+pub const GROUP_WINDOW: Duration = Duration::from_secs(2);
+
+/// Undo and redo snapshots for a single table. Edits sharing a group key within `GROUP_WINDOW` of each other undo together.
+#[derive(Debug, Default)]
+pub struct TableHistory {
+    undo: VecDeque<TableData>,
+    redo: Vec<TableData>,
+    last_group: Option<(String, Instant)>
+}
+
+impl TableHistory {
+    pub fn record(&mut self, before: TableData, group: Option<&str>, now: Instant) {
+        let is_grouped = match (&self.last_group, group) {
+            (Some((last, at)), Some(group)) => last == group && now.duration_since(*at) < GROUP_WINDOW,
+            _ => false
+        };
+
+        self.redo.clear();
+        self.last_group = group.map(|group| (group.to_string(), now));
+
+        if is_grouped && !self.undo.is_empty() {
+            return;
+        }
+
+        self.undo.push_back(before);
+        if self.undo.len() > MAX_HISTORY_DEPTH {
+            self.undo.pop_front();
+        }
+    }
+
+    pub fn undo(&mut self, current: &TableData) -> Option<TableData> {
+        let previous = self.undo.pop_back()?;
+        self.redo.push(current.clone());
+        self.last_group = None;
+        Some(previous)
+    }
+
+    pub fn redo(&mut self, current: &TableData) -> Option<TableData> {
+        let next = self.redo.pop()?;
+        self.undo.push_back(current.clone());
+        self.last_group = None;
+        Some(next)
+    }
+
+    pub fn state(&self) -> HistoryState {
+        HistoryState::new(self.undo.len(), self.redo.len())
+    }
+}
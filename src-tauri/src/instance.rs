@@ -0,0 +1,102 @@
+use std::{fs, io::{BufRead, BufReader, Write}, net::{SocketAddr, TcpListener, TcpStream}, path::PathBuf, thread, time::Duration};
+
+use common_data::BackendError;
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+use crate::{logging::get_data_dir, files::write_json_atomic};
+
+const INSTANCE_TIMEOUT: Duration = Duration::from_millis(500);
+const ACKNOWLEDGEMENT: &str = "ok";
+
+fn get_instance_file() -> PathBuf {
+    get_data_dir().join("instance.json")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct InstanceInfo {
+    port: u16,
+    token: String
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct InstanceMessage {
+    token: String,
+    args: Vec<String>,
+    cwd: String
+}
+
+/// Hands the arguments to an already running instance. Returns false when there is no instance to hand them to.
+pub fn notify_running_instance(args: Vec<String>, cwd: String) -> bool {
+    let info = match fs::read(get_instance_file()).ok().and_then(|bytes| serde_json::from_slice::<InstanceInfo>(&bytes).ok()) {
+        Some(info) => info,
+        None => return false
+    };
+
+    match send_message(&info, InstanceMessage { token: info.token.clone(), args, cwd }) {
+        Ok(acknowledged) => acknowledged,
+        Err(e) => {
+            log::info!("No running instance answered on port {}: {}", info.port, e);
+            false
+        }
+    }
+}
+
+fn send_message(info: &InstanceInfo, message: InstanceMessage) -> Result<bool, BackendError> {
+    let stream = TcpStream::connect_timeout(&SocketAddr::from(([127, 0, 0, 1], info.port)), INSTANCE_TIMEOUT)?;
+    stream.set_read_timeout(Some(INSTANCE_TIMEOUT))?;
+    stream.set_write_timeout(Some(INSTANCE_TIMEOUT))?;
+
+    let mut line = serde_json::to_vec(&message)?;
+    line.push(b'\n');
+    (&stream).write_all(&line)?;
+
+    let mut reply = String::new();
+    BufReader::new(&stream).read_line(&mut reply)?;
+
+    Ok(reply.trim() == ACKNOWLEDGEMENT)
+}
+
+pub struct InstanceListener {
+    listener: TcpListener,
+    token: String
+}
+
+impl InstanceListener {
+    pub fn bind() -> Result<Self, BackendError> {
+        let listener = TcpListener::bind(("127.0.0.1", 0))?;
+        let token = Uuid::new_v4().simple().to_string();
+
+        write_json_atomic(&get_instance_file(), &InstanceInfo { port: listener.local_addr()?.port(), token: token.clone() })?;
+
+        Ok(Self { listener, token })
+    }
+
+    pub fn listen<F: Fn(Vec<String>, String) + Send + 'static>(self, on_message: F) {
+        thread::spawn(move || {
+            for stream in self.listener.incoming() {
+                match stream.map_err(BackendError::from).and_then(|stream| self.read_message(stream)) {
+                    Ok(Some(message)) => on_message(message.args, message.cwd),
+                    Ok(None) => log::warn!("Ignoring a message from another instance with the wrong token"),
+                    Err(e) => log::error!("Failed to read a message from another instance: {}", e)
+                }
+            }
+        });
+    }
+
+    fn read_message(&self, stream: TcpStream) -> Result<Option<InstanceMessage>, BackendError> {
+        stream.set_read_timeout(Some(INSTANCE_TIMEOUT))?;
+        stream.set_write_timeout(Some(INSTANCE_TIMEOUT))?;
+
+        let mut line = String::new();
+        BufReader::new(&stream).read_line(&mut line)?;
+
+        let message: InstanceMessage = serde_json::from_str(&line)?;
+        if message.token != self.token {
+            return Ok(None);
+        }
+
+        (&stream).write_all(format!("{}\n", ACKNOWLEDGEMENT).as_bytes())?;
+        Ok(Some(message))
+    }
+}
@@ -0,0 +1,53 @@
+use std::{path::PathBuf, fs::{self, File}};
+
+use chrono::Local;
+use common_data::{ledger::Ledger, BackendError};
+
+use crate::{logging::get_data_dir, files::write_json_atomic};
+
+fn get_ledger_file() -> PathBuf {
+    get_data_dir().join("ledger.json")
+}
+
+/// Loads the saved ledger. A ledger that can't be parsed is moved aside to `ledger.json.corrupt-<timestamp>` and the returned error
+/// points at the moved file, so starting a new ledger can't overwrite the user's history.
+pub fn load_ledger() -> Result<Ledger, BackendError> {
+    let path = get_ledger_file();
+    if !path.exists() {
+        return Ok(Ledger::new());
+    }
+
+    let file = File::open(&path).map_err(|e| BackendError::io_error(&path, &e))?;
+    let error = match serde_json::from_reader(file) {
+        Ok(ledger) => return Ok(ledger),
+        Err(e) => e
+    };
+
+    match move_ledger_aside() {
+        Ok(Some(moved_to)) => {
+            log::error!("Failed to load ledger from {:?}, moved it to {:?}: {}", path, moved_to, error);
+            Err(BackendError::parse_error(&moved_to, &error))
+        },
+        result => {
+            log::error!("Failed to load ledger from {:?} and could not move it aside: {}, {:?}", path, error, result);
+            Err(BackendError::parse_error(&path, &error))
+        }
+    }
+}
+
+/// Renames the ledger file to `ledger.json.corrupt-<timestamp>`, returning the new path, or `None` when there is no ledger file.
+pub fn move_ledger_aside() -> Result<Option<PathBuf>, BackendError> {
+    let path = get_ledger_file();
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let moved_to = path.with_file_name(format!("ledger.json.corrupt-{}", Local::now().format("%Y%m%d-%H%M%S")));
+    fs::rename(&path, &moved_to).map_err(|e| BackendError::io_error(&path, &e))?;
+
+    Ok(Some(moved_to))
+}
+
+pub fn save_ledger(ledger: &Ledger) -> Result<(), BackendError> {
+    write_json_atomic(&get_ledger_file(), ledger)
+}
@@ -0,0 +1,84 @@
+use std::{path::{Path, PathBuf}, fs::{self, File}};
+
+use chrono::{DateTime, Local, NaiveDateTime};
+use common_data::{library::LibraryIndex, BackendError};
+
+use crate::{logging::get_data_dir, files::{read_table_file, write_json_atomic}};
+
+fn get_library_file() -> PathBuf {
+    get_data_dir().join("library.json")
+}
+
+pub fn load_library() -> Option<LibraryIndex> {
+    let path = get_library_file();
+    if !path.exists() {
+        return None;
+    }
+
+    let result = File::open(&path)
+        .map_err(BackendError::from)
+        .and_then(|file| serde_json::from_reader(file).map_err(BackendError::from));
+
+    match result {
+        Ok(library) => Some(library),
+        Err(e) => {
+            log::error!("Failed to load library index from {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+pub fn save_library(library: &LibraryIndex) -> Result<(), BackendError> {
+    write_json_atomic(&get_library_file(), library)
+}
+
+fn get_modified_time(path: &Path) -> Result<NaiveDateTime, BackendError> {
+    let modified = fs::metadata(path)?.modified()?;
+    Ok(DateTime::<Local>::from(modified).naive_local())
+}
+
+/// Collects the table files under `directory`. Entries that can't be read are logged and skipped rather than failing the whole walk.
+/// Symlinked directories aren't followed, so a link back up the tree can't make the walk recurse forever.
+fn find_table_files(directory: &Path, files: &mut Vec<(PathBuf, NaiveDateTime)>) -> Result<(), BackendError> {
+    for entry in fs::read_dir(directory).map_err(|e| BackendError::io_error(directory, &e))? {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                log::warn!("Skipping an entry of {:?} in library: {}", directory, e);
+                continue;
+            }
+        };
+
+        let path = entry.path();
+        let is_dir = entry.file_type().map(|file_type| file_type.is_dir()).unwrap_or(false);
+
+        if is_dir {
+            if let Err(e) = find_table_files(&path, files) {
+                log::warn!("Skipping {:?} in library: {}", path, e);
+            }
+        } else if path.extension().map(|e| e == "table").unwrap_or(false) {
+            match get_modified_time(&path) {
+                Ok(modified) => files.push((path, modified)),
+                Err(e) => log::warn!("Skipping {:?} in library: {}", path, e)
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn refresh_library_index(library: &mut LibraryIndex) -> Result<usize, BackendError> {
+    let mut files = Vec::new();
+    find_table_files(library.root(), &mut files)?;
+
+    let updated = library.update(files, |path| match read_table_file(path) {
+        Ok(data) => Some(data),
+        Err(e) => {
+            log::warn!("Skipping {:?} in library: {}", path, e);
+            None
+        }
+    });
+
+    log::info!("Updated {} of {} tables in library", updated, library.entries().len());
+    Ok(updated)
+}
\ No newline at end of file
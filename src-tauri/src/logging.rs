@@ -6,7 +6,7 @@ use log4rs::{append::{console::{ConsoleAppender, Target}, file::FileAppender}, e
 
 const MAX_LOG_COUNT: usize = 10;
 
-fn get_data_dir() -> PathBuf {
+pub fn get_data_dir() -> PathBuf {
     dirs::data_dir().unwrap_or(PathBuf::from("/data"))
         .join("TableApp")
 }
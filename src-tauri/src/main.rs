@@ -1,34 +1,307 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+pub mod api;
+pub mod file_grants;
+pub mod files;
+pub mod history;
+pub mod instance;
+pub mod ledger;
+pub mod library;
 pub mod logging;
+pub mod recovery;
+pub mod service;
+pub mod session;
+pub mod settings;
+pub mod watcher;
 
-use std::{collections::HashMap, sync::{Mutex, MutexGuard}, cmp::Ordering, path::PathBuf, fs::{self, File}};
+use std::{collections::HashMap, sync::{Mutex, MutexGuard, atomic::{AtomicBool, Ordering}}, path::{Path, PathBuf}, fs::{self, File}, thread, time::Duration};
 
-use common_data::{BackendError, Table, IdNamePair, TableData, RollResult, FileTableData, TableEntry, RollLimit};
+use api::{ApiServer, ApiEvent};
+use chrono::Local;
+use common_data::{BackendError, Table, IdNamePair, TableSummary, TableFileChange, TableEvent, TABLE_EVENT, ConflictResolution, RollResult, FileTableData, TableEntry, RollLimit, Balance, bundle::CampaignBundle, formats::{ImportFormat, ExportFormat, ImportedFile, markup::Markup}, history::HistoryState, ledger::{Ledger, LedgerItem}, library::{LibraryIndex, LibrarySearchResult}, search::EntrySearchResult, settings::Settings};
+use instance::{InstanceListener, notify_running_instance};
+use file_grants::{FileGrants, GrantKind};
+use files::{read_table_file, write_atomic, write_json_atomic, list_backups};
+use ledger::{load_ledger, save_ledger, move_ledger_aside};
+use library::{load_library, save_library, refresh_library_index};
 use log::SetLoggerError;
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::Value;
 use logging::{setup_logging, cleanup_logs};
-use tauri::{State, Manager};
+use recovery::{Recovery, AUTOSAVE_INTERVAL_SECONDS};
+use service::TableService;
+use session::{RecentFiles, Session, SessionTable};
+use settings::{load_settings, save_settings};
+use watcher::TableWatcher;
+use tauri::{State, Manager, AppHandle, RunEvent, WindowEvent, api::dialog::blocking::FileDialogBuilder};
 use uuid::Uuid;
 
 struct AppState {
-    tables: Mutex<HashMap<Uuid, Table>>
+    tables: TableService,
+    ledger: Mutex<Ledger>,
+    ledger_problem: Mutex<Option<BackendError>>,
+    recovery: Mutex<Recovery>,
+    settings: Mutex<Settings>,
+    library: Mutex<Option<LibraryIndex>>,
+    recent_files: Mutex<RecentFiles>,
+    watcher: Mutex<Option<TableWatcher>>,
+    /// The file contents each watched table last saw on disk, so repeated change events for one write are handled once.
+    last_seen: Mutex<HashMap<Uuid, FileTableData>>,
+    api: Mutex<Option<ApiServer>>,
+    file_grants: Mutex<FileGrants>,
+    discarding_changes: AtomicBool
 }
 
-impl Default for AppState {
-    fn default() -> Self {
+impl AppState {
+    fn new(ledger: Result<Ledger, BackendError>, recovery: Recovery, settings: Settings, library: Option<LibraryIndex>, recent_files: RecentFiles) -> Self {
+        let (ledger, ledger_problem) = match ledger {
+            Ok(ledger) => (ledger, None),
+            Err(e) => (Ledger::new(), Some(e))
+        };
+
         Self {
-            tables: Mutex::new(HashMap::new())
+            tables: TableService::new(),
+            ledger: Mutex::new(ledger),
+            ledger_problem: Mutex::new(ledger_problem),
+            recovery: Mutex::new(recovery),
+            settings: Mutex::new(settings),
+            library: Mutex::new(library),
+            recent_files: Mutex::new(recent_files),
+            watcher: Mutex::new(None),
+            last_seen: Mutex::new(HashMap::new()),
+            api: Mutex::new(None),
+            file_grants: Mutex::new(FileGrants::default()),
+            discarding_changes: AtomicBool::new(false)
         }
     }
-}
 
-impl AppState {
-    fn lock_tables(&self) -> Result<MutexGuard<HashMap<Uuid, Table>>, BackendError> {
-        self.tables.lock().map_err(|_| BackendError::internal_error("Unable to lock tables"))
+    /// Fails while the saved ledger couldn't be loaded, so nothing overwrites it until the user chooses to start a new one.
+    fn lock_ledger(&self) -> Result<MutexGuard<Ledger>, BackendError> {
+        if let Some(problem) = self.lock_ledger_problem()?.clone() {
+            return Err(problem);
+        }
+
+        self.ledger.lock().map_err(|_| BackendError::internal_error("Unable to lock ledger"))
+    }
+
+    fn lock_ledger_problem(&self) -> Result<MutexGuard<Option<BackendError>>, BackendError> {
+        self.ledger_problem.lock().map_err(|_| BackendError::internal_error("Unable to lock ledger problem"))
+    }
+
+    fn lock_recovery(&self) -> Result<MutexGuard<Recovery>, BackendError> {
+        self.recovery.lock().map_err(|_| BackendError::internal_error("Unable to lock recovery"))
+    }
+
+    fn lock_settings(&self) -> Result<MutexGuard<Settings>, BackendError> {
+        self.settings.lock().map_err(|_| BackendError::internal_error("Unable to lock settings"))
+    }
+
+    fn lock_library(&self) -> Result<MutexGuard<Option<LibraryIndex>>, BackendError> {
+        self.library.lock().map_err(|_| BackendError::internal_error("Unable to lock library"))
+    }
+
+    fn lock_recent_files(&self) -> Result<MutexGuard<RecentFiles>, BackendError> {
+        self.recent_files.lock().map_err(|_| BackendError::internal_error("Unable to lock recent files"))
+    }
+
+    fn lock_watcher(&self) -> Result<MutexGuard<Option<TableWatcher>>, BackendError> {
+        self.watcher.lock().map_err(|_| BackendError::internal_error("Unable to lock watcher"))
+    }
+
+    fn lock_last_seen(&self) -> Result<MutexGuard<HashMap<Uuid, FileTableData>>, BackendError> {
+        self.last_seen.lock().map_err(|_| BackendError::internal_error("Unable to lock last seen files"))
+    }
+
+    fn lock_api(&self) -> Result<MutexGuard<Option<ApiServer>>, BackendError> {
+        self.api.lock().map_err(|_| BackendError::internal_error("Unable to lock API server"))
+    }
+
+    fn lock_file_grants(&self) -> Result<MutexGuard<FileGrants>, BackendError> {
+        self.file_grants.lock().map_err(|_| BackendError::internal_error("Unable to lock file grants"))
+    }
+
+    fn configure_api(&self, app: &AppHandle) -> Result<(), BackendError> {
+        let mut settings = self.lock_settings()?;
+        let mut api = self.lock_api()?;
+
+        if !settings.api_enabled() {
+            *api = None;
+            return Ok(());
+        }
+
+        if settings.api_token().is_empty() {
+            settings.set_api_token(Uuid::new_v4().simple().to_string());
+            save_settings(&settings)?;
+        }
+
+        match api.as_ref() {
+            Some(server) if server.port() == settings.api_port() => server.set_token(settings.api_token()),
+            _ => {
+                *api = None;
+
+                let handle = app.clone();
+                *api = Some(ApiServer::start(settings.api_port(), settings.api_token(), move |command, args| {
+                    log_result(handle_api_request(&handle, command, args))
+                })?);
+
+                Ok(())
+            }
+        }
+    }
+
+    fn broadcast_api_event(&self, event: &ApiEvent) -> Result<(), BackendError> {
+        match self.lock_api()?.as_ref() {
+            Some(server) => server.broadcast(event),
+            None => Ok(())
+        }
+    }
+
+    fn watch_table(&self, id: Uuid, path: &Path) -> Result<(), BackendError> {
+        self.lock_last_seen()?.remove(&id);
+
+        match self.lock_watcher()?.as_mut() {
+            Some(watcher) => watcher.watch(id, path),
+            None => Ok(())
+        }
+    }
+
+    fn unwatch_table(&self, id: Uuid) -> Result<(), BackendError> {
+        self.lock_last_seen()?.remove(&id);
+
+        match self.lock_watcher()?.as_mut() {
+            Some(watcher) => watcher.unwatch(id),
+            None => Ok(())
+        }
+    }
+
+    fn open_table_file(&self, path: &Path) -> Result<Uuid, BackendError> {
+        let id = self.tables.open_table(path)?;
+
+        self.lock_recovery()?.mark_persisted(&self.tables.get_data(id)?)?;
+        self.watch_table(id, path)?;
+
+        Ok(id)
+    }
+
+    fn open_table_files(&self, paths: Vec<PathBuf>) -> Result<Vec<Uuid>, BackendError> {
+        let mut opened = Vec::new();
+
+        for path in paths {
+            log::info!("Opening {:?} from the command line...", &path);
+
+            match self.open_table_file(&path) {
+                Ok(id) => {
+                    self.lock_recent_files()?.add(&path)?;
+                    opened.push(id);
+                },
+                Err(e) => log::error!("Failed to open {:?}: {}", &path, e)
+            }
+        }
+
+        Ok(opened)
+    }
+
+    fn watch_open_tables(&self) -> Result<(), BackendError> {
+        for (id, table) in self.tables.snapshot()? {
+            let path = table.read_data()?.path();
+
+            if let Some(path) = path {
+                self.watch_table(id, &path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn restore_session(&self) -> Result<(), BackendError> {
+        if !self.lock_settings()?.restore_session() {
+            return Ok(());
+        }
+
+        let session = Session::load();
+        log::info!("Restoring {} tables from the last session...", session.tables().len());
+
+        for table in session.tables() {
+            let result = match table {
+                SessionTable::File(path) => self.open_table_file(path).map(|_| ()),
+                SessionTable::Recovered { recovery } => self.restore_recovered_table(*recovery)
+            };
+
+            if let Err(e) = result {
+                log::error!("Failed to restore {:?}: {}", table, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reopens a table with unsaved changes from its recovery copy, in the next tab.
+    fn restore_recovered_table(&self, id: Uuid) -> Result<(), BackendError> {
+        let table_data = self.lock_recovery()?
+            .take(id, self.tables.next_order())
+            .ok_or(BackendError::not_found("recovered table", id))?;
+
+        if let Some(path) = table_data.path() {
+            self.watch_table(id, &path)?;
+        }
+
+        self.tables.insert(table_data)?;
+        Ok(())
+    }
+
+    /// Records every open table in tab order. Tables with unsaved changes are stored by their recovery id, except when the user is
+    /// discarding changes: then a saved table reopens from its file and a never saved one is left out.
+    fn save_session(&self) -> Result<(), BackendError> {
+        let discarding = self.discarding_changes.load(Ordering::SeqCst);
+        let mut tables = Vec::new();
+
+        for table in self.tables.sorted()? {
+            let data = table.read_data()?;
+
+            match (data.path(), data.is_modified()) {
+                (Some(path), false) => tables.push(SessionTable::File(path)),
+                (Some(path), true) if discarding => tables.push(SessionTable::File(path)),
+                (None, _) if discarding => {},
+                _ => tables.push(SessionTable::Recovered { recovery: data.id() })
+            }
+        }
+
+        Session::new(tables).save()
+    }
+
+    fn has_unsaved_changes(&self) -> Result<bool, BackendError> {
+        Ok(self.tables.has_unsaved_changes()?)
+    }
+
+    fn autosave(&self) -> Result<usize, BackendError> {
+        if self.discarding_changes.load(Ordering::SeqCst) {
+            return Ok(0);
+        }
+
+        let tables = self.tables.snapshot()?;
+        let mut recovery = self.lock_recovery()?;
+        recovery.autosave(&tables)
+    }
+
+    /// Stops autosaving and drops the recovery files of open tables, so changes the user chose to throw away aren't offered back on the next launch.
+    fn discard_unsaved_changes(&self) -> Result<(), BackendError> {
+        self.discarding_changes.store(true, Ordering::SeqCst);
+
+        let mut recovery = self.lock_recovery()?;
+        for id in self.tables.order()? {
+            recovery.forget(id)?;
+        }
+
+        Ok(())
     }
 }
 
+fn emit_table_event(app: &AppHandle, event: TableEvent) {
+    log::info!("Emitting table event {:?}", &event);
+    log_result(app.emit_all(TABLE_EVENT, event)).ok();
+}
+
 fn log_result<T, E: std::fmt::Display>(result: Result<T, E>) -> Result<T, E> {
     if let Err(e) = &result {
         log::error!("{}", e);
@@ -38,236 +311,769 @@ fn log_result<T, E: std::fmt::Display>(result: Result<T, E>) -> Result<T, E> {
 }
 
 #[tauri::command]
-fn get_tables(state: State<AppState>) -> Result<Vec<IdNamePair>, BackendError> {
+fn get_tables(state: State<AppState>) -> Result<Vec<TableSummary>, BackendError> {
     log::info!("Getting tables...");
-    let tables = log_result(state.lock_tables())?;
-    let mut table_vec = tables.iter().collect::<Vec<_>>();
-
-    table_vec.sort_by(|(_, a), (_, b)| table_order(a, b));
-
-    let ids = table_vec.into_iter()
-        .filter_map(|(k, v)| match v.get_data() {
-            Err(_) => None,
-            Ok(data) => Some(IdNamePair::new(*k, data.name().to_string()))
-        })
-        .collect::<Vec<_>>();
-
-    Ok(ids)
-}
-
-fn table_order(a: &Table, b: &Table) -> Ordering {
-    let a_guard = match a.get_data() {
-        Ok(guard) => guard,
-        Err(_) => return Ordering::Equal
-    };
-
-    let b_guard = match b.get_data() {
-        Ok(guard) => guard,
-        Err(_) => return Ordering::Equal
-    };
-
-    a_guard.order().cmp(&b_guard.order())
+    let tables = &state.tables;
+    Ok(log_result(tables.summaries())?)
 }
 
 #[tauri::command]
 fn get_table(state: State<AppState>, id: Uuid) -> Result<Table, BackendError> {
     log::info!("Getting table with id '{}'...", id);
-    let tables = log_result(state.lock_tables())?;
-    log_result(match tables.get(&id) {
-        Some(table) => Ok(table.clone()),
-        None => Err(BackendError::argument_error("id", format!("Could not find table with id '{}'", id)))
-    })
+    let tables = &state.tables;
+    Ok(log_result(tables.get(id))?)
 }
 
 #[tauri::command]
-fn new_table(state: State<AppState>, use_cost: bool, use_weight: bool, name: String, entries: Vec<TableEntry>) -> Result<Uuid, BackendError> {
+fn new_table(app: AppHandle, state: State<AppState>, use_cost: bool, use_weight: bool, name: String, entries: Vec<TableEntry>) -> Result<Uuid, BackendError> {
     log::info!("Adding new table with name '{}'...", &name);
-    let mut tables = log_result(state.lock_tables())?;
+    let tables = &state.tables;
+    let id = log_result(tables.add_table(use_cost, use_weight, name, entries))?;
 
-    let mut table_data = TableData::new(use_cost, use_weight, name, tables.len());
-    let id = table_data.id();
-
-    for mut entry in entries {
-        let trimmed = entry.name().trim().to_string();
-        entry.set_name(&trimmed);
-        
-        if entry.cost().amount() == 0 {
-            entry.set_cost(entry.cost().with_amount(1));
-        }
-
-        if !trimmed.is_empty() {
-            table_data.push(entry);
-        }
-    }
-
-    table_data.sort();
-    tables.insert(id, Table::from(table_data));
+    emit_table_event(&app, TableEvent::Added(log_result(tables.summary(id))?));
 
     Ok(id)
 }
 
 #[tauri::command]
 fn update_table(
+    app: AppHandle,
     state: State<AppState>,
     id: Uuid,
     name: Option<String>,
     use_cost: Option<bool>,
     use_weight: Option<bool>,
-    entries: Option<Vec<TableEntry>>
+    entries: Option<Vec<TableEntry>>,
+    tags: Option<Vec<String>>
 ) -> Result<(), BackendError> {
     log::info!("Updatng table with id '{}'...", id);
-    let mut tables = log_result(state.lock_tables())?;
-    let table = log_result(tables.get_mut(&id)
-        .ok_or(BackendError::argument_error("id", format!("Could not find table with id '{}'", id))))?;
+    let tables = &state.tables;
+    log_result(tables.update_table(id, name, use_cost, use_weight, entries, tags))?;
 
-    let mut data = log_result(table.get_data())?;
+    emit_table_event(&app, TableEvent::Changed(log_result(tables.summary(id))?));
 
-    if let Some(name) = name {
-        data.set_name(name);
-    }
+    Ok(())
+}
 
-    if let Some(use_cost) = use_cost {
-        data.set_use_cost(use_cost);
-    }
+#[tauri::command]
+fn remove_table(app: AppHandle, state: State<AppState>, id: Uuid) -> Result<Table, BackendError> {
+    log::info!("Removing table with id '{}'...", id);
+    let tables = &state.tables;
+    let table = log_result(tables.remove_table(id))?;
+    log_result(state.lock_recovery()?.forget(id))?;
+    log_result(state.unwatch_table(id))?;
 
-    if let Some(use_weight) = use_weight {
-        data.set_use_weight(use_weight);
-    }
+    emit_table_event(&app, TableEvent::Removed(id));
 
-    if let Some(entries) = entries {
-        data.set_entries(entries);
-    }
+    Ok(table)
+}
 
-    Ok(())
+#[tauri::command]
+fn move_table(app: AppHandle, state: State<AppState>, id: Uuid, index: usize) -> Result<Vec<Uuid>, BackendError> {
+    log::info!("Moving table with id '{}' to index {}...", id, index);
+    let order = log_result(state.tables.move_table(id, index))?;
+    log_result(state.save_session()).ok();
+
+    emit_table_event(&app, TableEvent::Reordered(order.clone()));
+
+    Ok(order)
 }
 
 #[tauri::command]
-fn remove_table(state: State<AppState>, id: Uuid) -> Result<Table, BackendError> {
-    log::info!("Removing table with id '{}'...", id);
-    let mut tables = log_result(state.lock_tables())?;
-    log_result(tables.remove(&id).ok_or(BackendError::argument_error("id", format!("Could not find table with id '{}'", id))))
+fn reorder_tables(app: AppHandle, state: State<AppState>, ids: Vec<Uuid>) -> Result<Vec<Uuid>, BackendError> {
+    log::info!("Reordering tables to {:?}...", &ids);
+    let order = log_result(state.tables.reorder_tables(ids))?;
+    log_result(state.save_session()).ok();
+
+    emit_table_event(&app, TableEvent::Reordered(order.clone()));
+
+    Ok(order)
 }
 
 #[tauri::command]
-fn change_table_name(state: State<AppState>, id: Uuid, name: String) -> Result<(), BackendError> {
-    let mut tables = log_result(state.lock_tables())?;
-    let table = log_result(tables.get_mut(&id)
-        .ok_or(BackendError::argument_error("id", format!("Could not find table with id '{}'", id))))?;
+fn change_table_name(app: AppHandle, state: State<AppState>, id: Uuid, name: String) -> Result<(), BackendError> {
+    let tables = &state.tables;
+    log_result(tables.rename_table(id, name))?;
 
-    let mut data = log_result(table.get_data().map_err(BackendError::from))?;
-    data.set_name(name);
+    emit_table_event(&app, TableEvent::Changed(log_result(tables.summary(id))?));
 
     Ok(())
 }
 
 #[tauri::command]
-fn add_entries(state: State<AppState>, id: Uuid, entries: Vec<TableEntry>) -> Result<(), BackendError> {
+fn add_entries(app: AppHandle, state: State<AppState>, id: Uuid, entries: Vec<TableEntry>) -> Result<(), BackendError> {
     log::info!("Adding '{:?}' to table with id '{}'...", &entries, id);
-    let mut tables = log_result(state.lock_tables())?;
-    let table = log_result(tables.get_mut(&id)
-        .ok_or(BackendError::argument_error("id", format!("Could not find table with id '{}'", id))))?;
+    let tables = &state.tables;
+    log_result(tables.add_entries(id, entries))?;
 
-    let mut data = table.get_data()?;
-    for mut entry in entries {
-        let trimmed = entry.name().trim().to_string();
-        entry.set_name(&trimmed);
+    emit_table_event(&app, TableEvent::Changed(log_result(tables.summary(id))?));
 
-        if entry.cost().amount() == 0 {
-            entry.set_cost(entry.cost().with_amount(1));
-        }
+    Ok(())
+}
 
-        if !trimmed.is_empty() {
-            data.push(entry);
-        }
-    }
+#[tauri::command]
+fn get_entry(state: State<AppState>, id: Uuid, entry_id: Uuid) -> Result<TableEntry, BackendError> {
+    log::info!("Getting entry '{}' from table with id '{}'...", entry_id, id);
+    let tables = &state.tables;
+    Ok(log_result(tables.get_entry(id, entry_id))?)
+}
+
+#[tauri::command]
+fn update_entry(app: AppHandle, state: State<AppState>, id: Uuid, entry_id: Uuid, entry: TableEntry) -> Result<TableEntry, BackendError> {
+    log::info!("Updating entry '{}' in table with id '{}' to '{:?}'...", entry_id, id, &entry);
+    let tables = &state.tables;
+    let entry = log_result(tables.update_entry(id, entry_id, entry))?;
 
-    data.sort();
+    emit_table_event(&app, TableEvent::Changed(log_result(tables.summary(id))?));
 
-    Ok(())
+    Ok(entry)
 }
 
 #[tauri::command]
-fn remove_entry(state: State<AppState>, id: Uuid, index: usize) -> Result<TableEntry, BackendError> {
-    log::info!("Removing entry {} from table with id '{}'...", index, id);
-    let mut tables = log_result(state.lock_tables())?;
-    let table = log_result(tables.get_mut(&id)
-        .ok_or(BackendError::argument_error("id", format!("Could not find table with id '{}'", id))))?;
+fn move_entry(app: AppHandle, state: State<AppState>, id: Uuid, entry_id: Uuid, index: usize) -> Result<usize, BackendError> {
+    log::info!("Moving entry '{}' in table with id '{}' to index {}...", entry_id, id, index);
+    let tables = &state.tables;
+    let index = log_result(tables.move_entry(id, entry_id, index))?;
+
+    emit_table_event(&app, TableEvent::Changed(log_result(tables.summary(id))?));
 
-    let mut data = table.get_data()?;
-    
-    log_result(data.remove(index)
-        .ok_or(BackendError::argument_error("index", format!("Could not find entry with index '{}'", index))))
+    Ok(index)
 }
 
 #[tauri::command]
+fn remove_entry(app: AppHandle, state: State<AppState>, id: Uuid, entry_id: Uuid) -> Result<TableEntry, BackendError> {
+    log::info!("Removing entry '{}' from table with id '{}'...", entry_id, id);
+    let tables = &state.tables;
+    let entry = log_result(tables.remove_entry(id, entry_id))?;
+
+    emit_table_event(&app, TableEvent::Changed(log_result(tables.summary(id))?));
+
+    Ok(entry)
+}
+
+#[tauri::command]
+fn undo_table_edit(app: AppHandle, state: State<AppState>, id: Uuid) -> Result<HistoryState, BackendError> {
+    log::info!("Undoing last edit to table with id '{}'...", id);
+    let tables = &state.tables;
+
+    if log_result(tables.undo(id))? {
+        emit_table_event(&app, TableEvent::Changed(log_result(tables.summary(id))?));
+    }
+
+    Ok(log_result(tables.history(id))?)
+}
+
+#[tauri::command]
+fn redo_table_edit(app: AppHandle, state: State<AppState>, id: Uuid) -> Result<HistoryState, BackendError> {
+    log::info!("Redoing last undone edit to table with id '{}'...", id);
+    let tables = &state.tables;
+
+    if log_result(tables.redo(id))? {
+        emit_table_event(&app, TableEvent::Changed(log_result(tables.summary(id))?));
+    }
+
+    Ok(log_result(tables.history(id))?)
+}
+
+#[tauri::command]
+fn get_table_history(state: State<AppState>, id: Uuid) -> Result<HistoryState, BackendError> {
+    let tables = &state.tables;
+    Ok(log_result(tables.history(id))?)
+}
+
+#[tauri::command]
+fn search_entries(state: State<AppState>, query: String) -> Result<Vec<EntrySearchResult>, BackendError> {
+    log::info!("Searching open tables for '{}'...", &query);
+    let tables = &state.tables;
+    Ok(log_result(tables.search_entries(&query))?)
+}
+
+#[tauri::command(async)]
 fn get_random(state: State<AppState>, id: Uuid) -> Result<TableEntry, BackendError> {
     log::info!("Getting random entry from table with id '{}'...", id);
-    let tables = log_result(state.lock_tables())?;
-    let table = log_result(tables.get(&id)
-        .ok_or(BackendError::argument_error("id", format!("Could not find table with id '{}'", id))))?;
+    let tables = &state.tables;
+    let entry = log_result(tables.roll(id))?;
 
-    let data = table.get_data()?;
-    let entry = log_result(data.get_random().map_err(|e| BackendError::from(e)))?;
+    log_result(state.broadcast_api_event(&ApiEvent::Roll {
+        table_id: id,
+        table_name: tables.with_table(id, |data| data.name().to_string())?,
+        results: vec![RollResult::new(1, entry.clone())]
+    })).ok();
 
-    Ok(entry.clone())
+    Ok(entry)
 }
 
-#[tauri::command]
+#[tauri::command(async)]
 fn get_random_set(state: State<AppState>, id: Uuid, limit: RollLimit, allow_duplicates: bool, use_weight: bool) -> Result<Vec<RollResult>, BackendError> {
     log::info!("Getting '{:?}' random entries from table with id '{}'...", limit, id);
-    let tables = log_result(state.lock_tables())?;
-    let table = log_result(tables.get(&id)
-        .ok_or(BackendError::argument_error("id", format!("Could not find table with id '{}'", id))))?;
-
-    let data = table.get_data()?;
-    let entries = match limit {
-        RollLimit::Count(count) => log_result(data.get_random_set_by_count(use_weight, count, allow_duplicates).map_err(|e| BackendError::from(e)))?,
-        RollLimit::Cost(cost) => log_result(data.get_random_set_by_cost(use_weight, cost, allow_duplicates).map_err(|e| BackendError::from(e)))?,
-    };
+    let tables = &state.tables;
+    let entries = log_result(tables.roll_set(id, limit, allow_duplicates, use_weight))?;
 
     log::info!("Random rolls: {:?}", &entries);
 
+    log_result(state.broadcast_api_event(&ApiEvent::Roll {
+        table_id: id,
+        table_name: tables.with_table(id, |data| data.name().to_string())?,
+        results: entries.clone()
+    })).ok();
+
     Ok(entries)
 }
 
+#[tauri::command(async)]
+fn save_table(app: AppHandle, state: State<AppState>, id: Uuid, path: PathBuf) -> Result<(), BackendError> {
+    let tables = &state.tables;
+    let backup_count = log_result(state.lock_settings())?.backup_count();
+
+    log_result(tables.save_table(id, &path, backup_count))?;
+
+    log_result(state.watch_table(id, &path))?;
+    log_result(state.lock_recent_files()?.add(&path))?;
+    let data = tables.get_data(id)?;
+    if !data.is_modified() {
+        log_result(state.lock_recovery()?.mark_persisted(&data))?;
+    }
+
+    emit_table_event(&app, TableEvent::Changed(log_result(tables.summary(id))?));
+
+    Ok(())
+}
+
+#[tauri::command(async)]
+fn open_table(app: AppHandle, state: State<AppState>, path: PathBuf) -> Result<(), BackendError> {
+    log::info!("Opening table from {:?}...", &path);
+
+    if !path.exists() {
+        log_result(state.lock_recent_files()?.remove(&path))?;
+    }
+
+    let id = log_result(state.open_table_file(&path))?;
+    emit_table_event(&app, TableEvent::Added(log_result(state.tables.summary(id))?));
+
+    log_result(state.lock_recent_files()?.add(&path))
+}
+
+#[tauri::command(async)]
+fn resolve_table_conflict(app: AppHandle, state: State<AppState>, id: Uuid, resolution: ConflictResolution) -> Result<(), BackendError> {
+    log::info!("Resolving file conflict for table '{}' with {:?}...", id, resolution);
+    let tables = &state.tables;
+    log_result(tables.resolve_conflict(id, resolution))?;
+
+    if resolution == ConflictResolution::Reload {
+        log_result(state.lock_recovery()?.mark_persisted(&tables.get_data(id)?))?;
+    }
+
+    emit_table_event(&app, TableEvent::Changed(log_result(tables.summary(id))?));
+
+    Ok(())
+}
+
+fn handle_file_change(app: &AppHandle, id: Uuid, path: PathBuf) -> Result<(), BackendError> {
+    let file_data = match read_table_file(&path) {
+        Ok(file_data) => file_data,
+        Err(e) => {
+            log::warn!("Ignoring change to {:?}, the file could not be read: {}", &path, e);
+            return Ok(());
+        }
+    };
+
+    let state = app.state::<AppState>();
+
+    {
+        let mut last_seen = state.lock_last_seen()?;
+        if last_seen.get(&id) == Some(&file_data) {
+            return Ok(());
+        }
+
+        last_seen.insert(id, file_data.clone());
+    }
+
+    let table = match state.tables.get(id) {
+        Ok(table) => table,
+        Err(_) => return Ok(())
+    };
+
+    let reloaded = {
+        let mut data = table.write_data()?;
+        if data.to_file_data() == file_data {
+            return Ok(());
+        }
+
+        if data.is_modified() {
+            log::info!("{:?} changed on disk while the table has unsaved changes", &path);
+            app.emit_all("table-file-conflict", TableFileChange::new(id, data.name()))?;
+            return Ok(());
+        }
+
+        log::info!("Reloading {:?} after it changed on disk", &path);
+        *data = file_data.into_table_data_with_id(id, data.order(), data.path());
+        data.clone()
+    };
+
+    state.lock_recovery()?.mark_persisted(&reloaded)?;
+    emit_table_event(app, TableEvent::Changed(TableSummary::from(&reloaded)));
+
+    Ok(())
+}
+
 #[tauri::command]
-fn save_table(state: State<AppState>, id: Uuid, path: PathBuf) -> Result<(), BackendError> {
-    let tables = log_result(state.lock_tables())?;
-    let table = log_result(tables.get(&id)
-        .ok_or(BackendError::argument_error("id", format!("Could not find table with id '{}'", id))))?;
+fn list_table_backups(path: PathBuf) -> Vec<PathBuf> {
+    log::info!("Listing backups of {:?}...", &path);
+    list_backups(&path)
+}
+
+#[tauri::command(async)]
+fn open_table_backup(app: AppHandle, state: State<AppState>, path: PathBuf) -> Result<PathBuf, BackendError> {
+    log::info!("Opening latest readable backup of {:?}...", &path);
+    let tables = &state.tables;
+    let (id, backup) = log_result(tables.open_backup(&path))?;
+    log_result(state.watch_table(id, &path))?;
+
+    emit_table_event(&app, TableEvent::Added(log_result(tables.summary(id))?));
+
+    Ok(backup)
+}
+
+fn read_bundle(path: &PathBuf) -> Result<CampaignBundle, BackendError> {
+    let file = log_result(File::open(path).map_err(|e| BackendError::io_error(path, &e)))?;
+    let bundle: CampaignBundle = log_result(serde_json::from_reader(file).map_err(|e| BackendError::parse_error(path, &e)))?;
+
+    if !bundle.is_supported() {
+        return log_result(Err(BackendError::validation_error("version", format!("Bundle version {} is not supported by this version of the app", bundle.version()))));
+    }
 
-    let mut data = log_result(table.get_data())?;
-    let file_data = data.to_file_data();
+    Ok(bundle)
+}
 
-    if let Some(parent) = path.parent() {
-        if !parent.exists() {
-            log_result(fs::create_dir_all(parent).map_err(BackendError::from))?;
+#[tauri::command(async)]
+fn save_bundle(state: State<AppState>, name: String, path: PathBuf) -> Result<(), BackendError> {
+    log::info!("Saving campaign bundle '{}' to {:?}...", &name, &path);
+    let tables = &state.tables;
+    let ledger = match state.lock_ledger() {
+        Ok(ledger) => Some(ledger.clone()),
+        Err(e) => {
+            log::warn!("Saving the bundle without a ledger: {}", e);
+            None
         }
+    };
+
+    let sorted = log_result(tables.sorted())?;
+    let guards = log_result(sorted.iter().map(|t| t.read_data()).collect::<Result<Vec<_>, _>>())?;
+    let bundle = CampaignBundle::new(name, guards.iter().map(|g| &**g), ledger);
+
+    log_result(write_json_atomic(&path, &bundle))
+}
+
+#[tauri::command(async)]
+fn open_bundle(app: AppHandle, state: State<AppState>, path: PathBuf, discard_unsaved: bool, import_ledger: bool) -> Result<(), BackendError> {
+    log::info!("Opening campaign bundle from {:?}...", &path);
+    let tables = &state.tables;
+
+    if !discard_unsaved && log_result(state.has_unsaved_changes())? {
+        return log_result(Err(BackendError::validation_error("tables", "Some open tables have unsaved changes")));
+    }
+
+    let (bundle_tables, bundle_ledger) = read_bundle(&path)?.into_workspace();
+    let mut ledger = match bundle_ledger {
+        Some(_) if import_ledger => Some(log_result(state.lock_ledger())?),
+        _ => None
+    };
+
+    let mut recovery = log_result(state.lock_recovery())?;
+    for id in log_result(tables.clear())? {
+        log_result(recovery.forget(id))?;
+        log_result(state.unwatch_table(id))?;
+        emit_table_event(&app, TableEvent::Removed(id));
     }
 
-    let file = log_result(File::create(&path).map_err(BackendError::from))?;
-    log_result(serde_json::to_writer_pretty(file, &file_data).map_err(BackendError::from))?;
+    for table_data in bundle_tables {
+        log_result(recovery.mark_persisted(&table_data))?;
+        emit_table_event(&app, TableEvent::Added(TableSummary::from(&table_data)));
+        log_result(tables.insert(table_data))?;
+    }
 
-    data.set_path(Some(path));
+    match (bundle_ledger, ledger.as_mut()) {
+        (Some(bundle_ledger), Some(ledger)) => {
+            log::info!("Replacing the ledger with the one from the bundle");
+            **ledger = bundle_ledger;
+            log_result(save_ledger(&**ledger))?;
+        },
+        (Some(_), None) => log::info!("Keeping the current ledger instead of the one in the bundle"),
+        (None, _) => {}
+    }
 
     Ok(())
 }
 
 #[tauri::command]
-fn open_table(state: State<AppState>, path: PathBuf) -> Result<(), BackendError> {
-    let mut tables = log_result(state.lock_tables())?;
-    let file = log_result(File::open(&path).map_err(BackendError::from))?;
-    let table_data: FileTableData = log_result(serde_json::from_reader(file).map_err(BackendError::from))?;
-    let mut table_data = table_data.into_table_data(tables.len(), Some(path));
-    let id = table_data.id();
+fn bundle_has_ledger(path: PathBuf) -> Result<bool, BackendError> {
+    log::info!("Checking campaign bundle {:?} for a ledger...", &path);
+    Ok(read_bundle(&path)?.ledger().is_some())
+}
+
+#[tauri::command]
+fn list_bundle_tables(path: PathBuf) -> Result<Vec<IdNamePair>, BackendError> {
+    log::info!("Listing tables in campaign bundle {:?}...", &path);
+    Ok(read_bundle(&path)?.summaries())
+}
+
+#[tauri::command(async)]
+fn extract_bundle_table(app: AppHandle, state: State<AppState>, path: PathBuf, id: Uuid) -> Result<Uuid, BackendError> {
+    log::info!("Extracting table with id '{}' from campaign bundle {:?}...", id, &path);
+    let bundle = read_bundle(&path)?;
+    let tables = &state.tables;
 
-    table_data.set_order(tables.len());
-    tables.insert(id, Table::from(table_data));
+    let table_data = log_result(bundle.extract_table(id, tables.next_order())
+        .ok_or(BackendError::not_found("table", id)))?;
+
+    emit_table_event(&app, TableEvent::Added(TableSummary::from(&table_data)));
+
+    Ok(log_result(tables.insert(table_data))?)
+}
+
+#[tauri::command]
+fn get_recovered_tables(state: State<AppState>) -> Result<Vec<IdNamePair>, BackendError> {
+    log::info!("Getting recovered tables...");
+    let recovery = log_result(state.lock_recovery())?;
+    Ok(recovery.pending())
+}
+
+#[tauri::command]
+fn restore_recovered_tables(app: AppHandle, state: State<AppState>) -> Result<(), BackendError> {
+    log::info!("Restoring recovered tables...");
+    let tables = &state.tables;
+    let mut recovery = log_result(state.lock_recovery())?;
+
+    for table_data in recovery.take_pending(tables.next_order()) {
+        if let Some(path) = table_data.path() {
+            log_result(state.watch_table(table_data.id(), &path))?;
+        }
+
+        emit_table_event(&app, TableEvent::Added(TableSummary::from(&table_data)));
+        log_result(tables.insert(table_data))?;
+    }
 
     Ok(())
 }
 
+#[tauri::command]
+fn discard_recovered_tables(state: State<AppState>) -> Result<(), BackendError> {
+    log::info!("Discarding recovered tables...");
+    let mut recovery = log_result(state.lock_recovery())?;
+    log_result(recovery.discard_pending())
+}
+
+#[tauri::command]
+fn exit_app(app: AppHandle, state: State<AppState>) {
+    log::info!("Exiting without saving...");
+    log_result(state.discard_unsaved_changes()).ok();
+    app.exit(0);
+}
+
+#[tauri::command]
+fn get_settings(state: State<AppState>) -> Result<Settings, BackendError> {
+    log::info!("Getting settings...");
+    let settings = log_result(state.lock_settings())?;
+    Ok(settings.clone())
+}
+
+#[tauri::command]
+fn update_settings(app: AppHandle, state: State<AppState>, settings: Settings) -> Result<(), BackendError> {
+    log::info!("Updating settings...");
+    let settings = settings.sanitised();
+
+    {
+        let mut current = log_result(state.lock_settings())?;
+        log_result(save_settings(&settings))?;
+        *current = settings;
+    }
+
+    log_result(state.configure_api(&app))
+}
+
+#[tauri::command(async)]
+fn refresh_library(state: State<AppState>) -> Result<usize, BackendError> {
+    let root = log_result(log_result(state.lock_settings())?.library_dir()
+        .map(Path::to_path_buf)
+        .ok_or(BackendError::argument_error("libraryDir", "No library folder has been chosen")))?;
+
+    log::info!("Refreshing library in {:?}...", &root);
+    let mut library = log_result(state.lock_library())?;
+
+    if library.as_ref().map(|l| l.root() != root).unwrap_or(true) {
+        *library = Some(LibraryIndex::new(&root));
+    }
+
+    let index = library.get_or_insert_with(|| LibraryIndex::new(&root));
+    log_result(refresh_library_index(index))?;
+    log_result(save_library(index))?;
+
+    Ok(index.entries().len())
+}
+
+#[tauri::command]
+fn search_library(state: State<AppState>, query: String, tag: Option<String>) -> Result<Vec<LibrarySearchResult>, BackendError> {
+    log::info!("Searching library for '{}'...", &query);
+    let library = log_result(state.lock_library())?;
+
+    Ok(match library.as_ref() {
+        Some(index) => index.search(&query, tag.as_deref()),
+        None => Vec::new()
+    })
+}
+
+#[tauri::command]
+fn get_library_tags(state: State<AppState>) -> Result<Vec<String>, BackendError> {
+    let library = log_result(state.lock_library())?;
+
+    Ok(match library.as_ref() {
+        Some(index) => index.tags(),
+        None => Vec::new()
+    })
+}
+
+#[tauri::command]
+fn get_recent_files(state: State<AppState>) -> Result<Vec<PathBuf>, BackendError> {
+    let recent_files = log_result(state.lock_recent_files())?;
+    Ok(recent_files.paths().clone())
+}
+
+#[tauri::command]
+fn clear_recent_files(state: State<AppState>) -> Result<(), BackendError> {
+    log::info!("Clearing recent files...");
+    let mut recent_files = log_result(state.lock_recent_files())?;
+    log_result(recent_files.clear())
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiTableArgs {
+    id: Uuid
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiEntryArgs {
+    id: Uuid,
+    #[serde(rename = "entryId")]
+    entry_id: Uuid
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiRandomSetArgs {
+    id: Uuid,
+    limit: RollLimit,
+    #[serde(rename = "allowDuplicates")]
+    allow_duplicates: bool,
+    #[serde(rename = "useWeight")]
+    use_weight: bool
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiSearchArgs {
+    #[serde(default)]
+    query: String,
+    tag: Option<String>
+}
+
+fn get_api_args<T: DeserializeOwned>(args: Value) -> Result<T, BackendError> {
+    serde_json::from_value(args).map_err(|e| BackendError::argument_error("body", e.to_string()))
+}
+
+fn handle_api_request(app: &AppHandle, command: &str, args: Value) -> Result<Value, BackendError> {
+    log::info!("Handling API command '{}'...", command);
+    let state = app.state::<AppState>();
+
+    let value = match command {
+        "get_tables" => serde_json::to_value(get_tables(state)?)?,
+        "get_table" => {
+            let args: ApiTableArgs = get_api_args(args)?;
+            serde_json::to_value(get_table(state, args.id)?)?
+        },
+        "get_entry" => {
+            let args: ApiEntryArgs = get_api_args(args)?;
+            serde_json::to_value(get_entry(state, args.id, args.entry_id)?)?
+        },
+        "get_random" => {
+            let args: ApiTableArgs = get_api_args(args)?;
+            serde_json::to_value(get_random(state, args.id)?)?
+        },
+        "get_random_set" => {
+            let args: ApiRandomSetArgs = get_api_args(args)?;
+            serde_json::to_value(get_random_set(state, args.id, args.limit, args.allow_duplicates, args.use_weight)?)?
+        },
+        "search_entries" => {
+            let args: ApiSearchArgs = get_api_args(args)?;
+            serde_json::to_value(search_entries(state, args.query)?)?
+        },
+        "search_library" => {
+            let args: ApiSearchArgs = get_api_args(args)?;
+            serde_json::to_value(search_library(state, args.query, args.tag)?)?
+        },
+        "get_library_tags" => serde_json::to_value(get_library_tags(state)?)?,
+        _ => return Err(BackendError::argument_error("command", format!("Unknown command '{}'", command)))
+    };
+
+    Ok(value)
+}
+
+fn get_table_paths(args: impl IntoIterator<Item = String>, cwd: &Path) -> Vec<PathBuf> {
+    args.into_iter()
+        .skip(1)
+        .map(|arg| cwd.join(arg))
+        .filter(|path| path.extension().map(|e| e == "table").unwrap_or(false))
+        .collect()
+}
+
+fn open_from_second_instance(app: &AppHandle, args: Vec<String>, cwd: String) {
+    let paths = get_table_paths(args, Path::new(&cwd));
+    let state = app.state::<AppState>();
+
+    if let Ok(opened) = log_result(state.open_table_files(paths)) {
+        for id in opened {
+            if let Ok(summary) = log_result(state.tables.summary(id)) {
+                emit_table_event(app, TableEvent::Added(summary));
+            }
+        }
+    }
+
+    if let Some(window) = app.get_window("main") {
+        log_result(window.unminimize()).ok();
+        log_result(window.set_focus()).ok();
+    }
+}
+
+fn autosave(app: &AppHandle) {
+    match app.state::<AppState>().autosave() {
+        Ok(0) => {},
+        Ok(count) => log::info!("Autosaved {} tables", count),
+        Err(e) => log::error!("Autosave failed: {}", e)
+    }
+}
+
+#[tauri::command(async)]
+fn get_open_import_path(state: State<AppState>, format: ImportFormat) -> Result<Option<PathBuf>, BackendError> {
+    let path = FileDialogBuilder::new().add_filter(format.to_string(), &format.extensions()).pick_file();
+
+    if let Some(path) = &path {
+        log_result(state.lock_file_grants())?.grant(GrantKind::Import, path);
+    }
+
+    Ok(path)
+}
+
+#[tauri::command(async)]
+fn get_save_export_path(state: State<AppState>, format: ExportFormat) -> Result<Option<PathBuf>, BackendError> {
+    let path = FileDialogBuilder::new().add_filter(format.to_string(), &[format.extension()]).save_file();
+
+    if let Some(path) = &path {
+        log_result(state.lock_file_grants())?.grant(GrantKind::Export, path);
+    }
+
+    Ok(path)
+}
+
+#[tauri::command(async)]
+fn import_table_file(state: State<AppState>, path: PathBuf, format: ImportFormat) -> Result<ImportedFile, BackendError> {
+    log::info!("Importing {} file {:?}...", format, &path);
+    log_result(log_result(state.lock_file_grants())?.take(GrantKind::Import, &path))?;
+
+    let text = log_result(fs::read_to_string(&path).map_err(|e| BackendError::io_error(&path, &e)))?;
+    log_result(format.import_file(&text).map_err(|e| BackendError::format_error(&path, &e)))
+}
+
+#[tauri::command(async)]
+fn export_table(state: State<AppState>, id: Uuid, format: ExportFormat, path: PathBuf) -> Result<(), BackendError> {
+    log::info!("Exporting table '{}' as {} to {:?}...", id, format, &path);
+    log_result(log_result(state.lock_file_grants())?.take(GrantKind::Export, &path))?;
+
+    let contents = log_result(format.export_table(&state.tables.get_data(id)?).map_err(|e| BackendError::format_error(&path, &e)))?;
+    log_result(write_atomic(&path, contents.as_bytes()))
+}
+
+#[tauri::command(async)]
+fn export_roll_results(state: State<AppState>, title: String, results: Vec<RollResult>, markup: Markup, show_cost: bool, path: PathBuf) -> Result<(), BackendError> {
+    log::info!("Exporting {} roll results as {} to {:?}...", results.len(), markup, &path);
+    log_result(log_result(state.lock_file_grants())?.take(GrantKind::Export, &path))?;
+
+    log_result(write_atomic(&path, markup.render_results(&title, &results, show_cost).as_bytes()))
+}
+
+#[tauri::command]
+fn get_ledger(state: State<AppState>) -> Result<Ledger, BackendError> {
+    log::info!("Getting ledger...");
+    let ledger = log_result(state.lock_ledger())?;
+    Ok(ledger.clone())
+}
+
+#[tauri::command]
+fn start_new_ledger(state: State<AppState>) -> Result<(), BackendError> {
+    log::info!("Starting a new ledger...");
+    {
+        let mut problem = log_result(state.lock_ledger_problem())?;
+        if problem.is_some() {
+            if let Some(moved_to) = log_result(move_ledger_aside())? {
+                log::info!("Moved the unreadable ledger to {:?}", moved_to);
+            }
+        }
+
+        *problem = None;
+    }
+
+    let mut ledger = log_result(state.lock_ledger())?;
+    *ledger = Ledger::new();
+
+    log_result(save_ledger(&ledger))
+}
+
+#[tauri::command]
+fn add_ledger_transaction(state: State<AppState>, description: String, category: String, amount: Balance) -> Result<Uuid, BackendError> {
+    log::info!("Adding ledger transaction '{}' of {}...", &description, amount);
+    let mut ledger = log_result(state.lock_ledger())?;
+    let id = ledger.add_transaction(Local::now().naive_local(), description.trim(), category.trim(), amount);
+
+    log_result(save_ledger(&ledger))?;
+
+    Ok(id)
+}
+
+#[tauri::command]
+fn remove_ledger_transaction(state: State<AppState>, transaction_id: Uuid) -> Result<(), BackendError> {
+    log::info!("Removing ledger transaction with id '{}'...", transaction_id);
+    let mut ledger = log_result(state.lock_ledger())?;
+    log_result(ledger.remove_transaction(transaction_id)
+        .ok_or(BackendError::not_found("transaction", transaction_id)))?;
+
+    log_result(save_ledger(&ledger))
+}
+
+#[tauri::command]
+fn add_results_to_ledger(state: State<AppState>, results: Vec<RollResult>, category: String) -> Result<(), BackendError> {
+    log::info!("Adding {} roll results to the ledger...", results.len());
+    let mut ledger = log_result(state.lock_ledger())?;
+    log_result(ledger.add_roll_results(&results, category.trim()))?;
+
+    log_result(save_ledger(&ledger))
+}
+
+#[tauri::command]
+fn remove_ledger_item(state: State<AppState>, index: usize) -> Result<LedgerItem, BackendError> {
+    log::info!("Removing ledger item {}...", index);
+    let mut ledger = log_result(state.lock_ledger())?;
+    let item = log_result(ledger.remove_item(index)
+        .ok_or(BackendError::not_found("ledger item", index)))?;
+
+    log_result(save_ledger(&ledger))?;
+
+    Ok(item)
+}
+
 fn main() -> Result<(), SetLoggerError> {
+    // Hand off to a running instance before touching the logs, so a second launch can't clean up or rotate the log files the
+    // running instance is writing to.
+    let cwd = std::env::current_dir().map(|cwd| cwd.to_string_lossy().to_string()).unwrap_or_default();
+    if notify_running_instance(std::env::args().collect(), cwd) {
+        return Ok(());
+    }
+
     cleanup_logs().ok();
     if let Err(e) = setup_logging() {
         log::error!("{}", e);
@@ -275,33 +1081,121 @@ fn main() -> Result<(), SetLoggerError> {
 
     log::info!("Starting backend...");
 
+    let instance = log_result(InstanceListener::bind()).ok();
+
+    let state = AppState::new(load_ledger(), Recovery::load(), load_settings(), load_library(), RecentFiles::load());
+    log_result(state.restore_session()).ok();
+
+    if let Ok(cwd) = std::env::current_dir() {
+        log_result(state.open_table_files(get_table_paths(std::env::args(), &cwd))).ok();
+    }
+
     tauri::Builder::default()
-        .manage(AppState::default())
+        .manage(state)
         .invoke_handler(tauri::generate_handler![
             get_tables,
             get_table,
             new_table,
             update_table,
             remove_table,
+            move_table,
+            reorder_tables,
             change_table_name,
             add_entries,
+            get_entry,
+            update_entry,
+            move_entry,
             remove_entry,
+            undo_table_edit,
+            redo_table_edit,
+            get_table_history,
+            search_entries,
             get_random,
             get_random_set,
             save_table,
             open_table,
+            save_bundle,
+            open_bundle,
+            bundle_has_ledger,
+            list_bundle_tables,
+            extract_bundle_table,
+            get_recovered_tables,
+            restore_recovered_tables,
+            discard_recovered_tables,
+            exit_app,
+            list_table_backups,
+            open_table_backup,
+            get_settings,
+            update_settings,
+            resolve_table_conflict,
+            refresh_library,
+            search_library,
+            get_library_tags,
+            get_recent_files,
+            clear_recent_files,
+            get_open_import_path,
+            get_save_export_path,
+            import_table_file,
+            export_table,
+            export_roll_results,
+            get_ledger,
+            start_new_ledger,
+            add_ledger_transaction,
+            remove_ledger_transaction,
+            add_results_to_ledger,
+            remove_ledger_item,
         ])
-        .setup(|app| {
+        .setup(move |app| {
             #[cfg(debug_assertions)] // only include this code on debug builds
             {
                 let window = app.get_window("main").unwrap();
                 window.open_devtools();
                 window.close_devtools();
             }
+
+            let handle = app.handle();
+            let watcher = TableWatcher::new(move |id, path| {
+                log_result(handle_file_change(&handle, id, path)).ok();
+            });
+
+            if let (Ok(watcher), Ok(mut guard)) = (log_result(watcher), log_result(app.state::<AppState>().lock_watcher())) {
+                *guard = Some(watcher);
+            }
+
+            log_result(app.state::<AppState>().watch_open_tables()).ok();
+            log_result(app.state::<AppState>().configure_api(&app.handle())).ok();
+
+            if let Some(instance) = instance {
+                let handle = app.handle();
+                instance.listen(move |args, cwd| open_from_second_instance(&handle, args, cwd));
+            }
+
+            let handle = app.handle();
+            thread::spawn(move || loop {
+                thread::sleep(Duration::from_secs(AUTOSAVE_INTERVAL_SECONDS));
+                autosave(&handle);
+            });
+
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .on_window_event(|event| {
+            if let WindowEvent::CloseRequested { api, .. } = event.event() {
+                let state = event.window().state::<AppState>();
+
+                if let Ok(true) = log_result(state.has_unsaved_changes()) {
+                    api.prevent_close();
+                    log_result(event.window().emit("close-requested", ())).ok();
+                }
+            }
+        })
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                autosave(app);
+                log_result(app.state::<AppState>().save_session()).ok();
+            }
+        });
 
     Ok(())
 }
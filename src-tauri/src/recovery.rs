@@ -0,0 +1,156 @@
+use std::{path::PathBuf, fs::{self, File}, collections::{HashMap, hash_map::DefaultHasher}, hash::{Hash, Hasher}};
+
+use common_data::{BackendError, FileTableData, IdNamePair, Table, TableData};
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+use crate::{logging::get_data_dir, files::write_json_atomic};
+
+pub const AUTOSAVE_INTERVAL_SECONDS: u64 = 30;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecoveryTable {
+    id: Uuid,
+    order: usize,
+    path: Option<PathBuf>,
+    #[serde(flatten)]
+    data: FileTableData
+}
+
+impl RecoveryTable {
+    fn into_table_data(self, order: usize) -> TableData {
+        let mut data = self.data.into_table_data_with_id(self.id, order, self.path);
+        data.set_modified(true);
+        data
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Recovery {
+    persisted: HashMap<Uuid, u64>,
+    pending: Vec<RecoveryTable>
+}
+
+fn get_recovery_dir() -> PathBuf {
+    get_data_dir().join("recovery")
+}
+
+fn get_recovery_file(id: Uuid) -> PathBuf {
+    get_recovery_dir().join(format!("{}.json", id))
+}
+
+fn content_hash(data: &FileTableData) -> Result<u64, BackendError> {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(data)?.hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+fn remove_recovery_file(id: Uuid) -> Result<(), BackendError> {
+    let path = get_recovery_file(id);
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+impl Recovery {
+    pub fn load() -> Self {
+        let dir = get_recovery_dir();
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => return Self::default()
+        };
+
+        let mut pending = entries.filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().map(|e| e == "json").unwrap_or(false))
+            .filter_map(|path| {
+                let result = File::open(&path)
+                    .map_err(BackendError::from)
+                    .and_then(|file| serde_json::from_reader::<_, RecoveryTable>(file).map_err(BackendError::from));
+
+                match result {
+                    Ok(table) => Some(table),
+                    Err(e) => {
+                        log::error!("Failed to read recovery file {:?}: {}", path, e);
+                        None
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        pending.sort_by_key(|t| t.order);
+        log::info!("Found {} recoverable tables", pending.len());
+
+        Self {
+            persisted: HashMap::new(),
+            pending
+        }
+    }
+
+    pub fn pending(&self) -> Vec<IdNamePair> {
+        self.pending.iter()
+            .map(|t| IdNamePair::new(t.id, t.data.name().to_string()))
+            .collect()
+    }
+
+    pub fn take_pending(&mut self, first_order: usize) -> Vec<TableData> {
+        self.pending.drain(..)
+            .enumerate()
+            .map(|(index, t)| t.into_table_data(first_order + index))
+            .collect()
+    }
+
+    /// Takes a single pending table, so a session can put it back in its tab.
+    pub fn take(&mut self, id: Uuid, order: usize) -> Option<TableData> {
+        let index = self.pending.iter().position(|t| t.id == id)?;
+        Some(self.pending.remove(index).into_table_data(order))
+    }
+
+    pub fn discard_pending(&mut self) -> Result<(), BackendError> {
+        for table in self.pending.drain(..) {
+            remove_recovery_file(table.id)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn mark_persisted(&mut self, data: &TableData) -> Result<(), BackendError> {
+        self.persisted.insert(data.id(), content_hash(&data.to_file_data())?);
+        remove_recovery_file(data.id())
+    }
+
+    pub fn forget(&mut self, id: Uuid) -> Result<(), BackendError> {
+        self.persisted.remove(&id);
+        remove_recovery_file(id)
+    }
+
+    pub fn autosave(&mut self, tables: &HashMap<Uuid, Table>) -> Result<usize, BackendError> {
+        let mut saved = 0;
+
+        for (id, table) in tables.iter() {
+            let data = table.read_data()?;
+            let file_data = data.to_file_data();
+            let hash = content_hash(&file_data)?;
+
+            if self.persisted.get(id) == Some(&hash) {
+                continue;
+            }
+
+            let recovery_table = RecoveryTable {
+                id: *id,
+                order: data.order(),
+                path: data.path().map(|p| p.to_path_buf()),
+                data: file_data
+            };
+
+            write_json_atomic(&get_recovery_file(*id), &recovery_table)?;
+
+            self.persisted.insert(*id, hash);
+            saved += 1;
+        }
+
+        Ok(saved)
+    }
+}
\ No newline at end of file
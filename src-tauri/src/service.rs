@@ -0,0 +1,777 @@
+use std::{collections::HashMap, path::{Path, PathBuf}, sync::{Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard, atomic::{AtomicUsize, Ordering}}, time::Instant};
+
+use common_data::{BackendError, ConflictResolution, RollLimit, RollResult, Table, TableData, TableEntry, TableSummary, history::HistoryState, search::{EntrySearchResult, search_entries}};
+use uuid::Uuid;
+
+use crate::{files::{read_table_file, rotate_backups, write_json_atomic, list_backups}, history::TableHistory};
+
+#[derive(Debug)]
+pub enum ServiceError {
+    TableNotFound(Uuid),
+    EmptyTable(Uuid),
+    EntryNotFound { id: Uuid, entry_id: Uuid },
+    EmptyEntryName(Uuid),
+    NotSaved(Uuid),
+    NoBackups(PathBuf),
+    File(BackendError),
+    Random(String),
+    Lock(String)
+}
+
+impl std::fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TableNotFound(id) => write!(f, "Could not find table with id '{}'", id),
+            Self::EmptyTable(id) => write!(f, "Table with id '{}' has no entries to roll", id),
+            Self::EntryNotFound { id, entry_id } => write!(f, "Could not find entry with id '{}' in table '{}'", entry_id, id),
+            Self::EmptyEntryName(entry_id) => write!(f, "Entry with id '{}' must have a name", entry_id),
+            Self::NotSaved(id) => write!(f, "Table with id '{}' has not been saved to a file", id),
+            Self::NoBackups(path) => write!(f, "No readable backups found for {:?}", path),
+            Self::File(error) => write!(f, "{}", error),
+            Self::Random(message) => write!(f, "Could not generate random numbers: {}", message),
+            Self::Lock(message) => write!(f, "{}", message)
+        }
+    }
+}
+
+impl From<ServiceError> for BackendError {
+    fn from(value: ServiceError) -> Self {
+        match value {
+            ServiceError::TableNotFound(id) => BackendError::not_found("table", id),
+            ServiceError::EntryNotFound { entry_id, .. } => BackendError::not_found("entry", entry_id),
+            ServiceError::NoBackups(path) => BackendError::not_found("backup", path.display()),
+            ServiceError::EmptyTable(_) | ServiceError::Random(_) => BackendError::roll_error(value.to_string()),
+            ServiceError::EmptyEntryName(_) => BackendError::validation_error("name", value.to_string()),
+            ServiceError::NotSaved(_) => BackendError::argument_error("id", value.to_string()),
+            ServiceError::File(error) => error,
+            ServiceError::Lock(_) => BackendError::internal_error(value.to_string())
+        }
+    }
+}
+
+fn sanitise_entries(entries: Vec<TableEntry>) -> impl Iterator<Item = TableEntry> {
+    entries.into_iter()
+        .filter_map(|mut entry| {
+            let trimmed = entry.name().trim().to_string();
+            entry.set_name(&trimmed);
+
+            if entry.cost().amount() == 0 {
+                entry.set_cost(entry.cost().with_amount(1));
+            }
+
+            (!trimmed.is_empty()).then_some(entry)
+        })
+}
+
+#[derive(Debug, Default)]
+pub struct TableService {
+    tables: RwLock<HashMap<Uuid, Table>>,
+    histories: Mutex<HashMap<Uuid, TableHistory>>,
+    next_order: AtomicUsize
+}
+
+impl TableService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn read_tables(&self) -> Result<RwLockReadGuard<HashMap<Uuid, Table>>, ServiceError> {
+        self.tables.read().map_err(|_| ServiceError::Lock("Unable to lock tables for reading".to_string()))
+    }
+
+    fn write_tables(&self) -> Result<RwLockWriteGuard<HashMap<Uuid, Table>>, ServiceError> {
+        self.tables.write().map_err(|_| ServiceError::Lock("Unable to lock tables for writing".to_string()))
+    }
+
+    fn lock_histories(&self) -> Result<MutexGuard<HashMap<Uuid, TableHistory>>, ServiceError> {
+        self.histories.lock().map_err(|_| ServiceError::Lock("Unable to lock table history".to_string()))
+    }
+
+    pub fn snapshot(&self) -> Result<HashMap<Uuid, Table>, ServiceError> {
+        Ok(self.read_tables()?.clone())
+    }
+
+    pub fn len(&self) -> Result<usize, ServiceError> {
+        Ok(self.read_tables()?.len())
+    }
+
+    pub fn is_empty(&self) -> Result<bool, ServiceError> {
+        Ok(self.read_tables()?.is_empty())
+    }
+
+    pub fn get(&self, id: Uuid) -> Result<Table, ServiceError> {
+        self.read_tables()?
+            .get(&id)
+            .cloned()
+            .ok_or(ServiceError::TableNotFound(id))
+    }
+
+    pub fn with_table<T>(&self, id: Uuid, f: impl FnOnce(&TableData) -> T) -> Result<T, ServiceError> {
+        let table = self.get(id)?;
+        let data = table.read_data().map_err(|e| ServiceError::Lock(e.to_string()))?;
+        Ok(f(&data))
+    }
+
+    pub fn with_table_mut<T>(&self, id: Uuid, f: impl FnOnce(&mut TableData) -> T) -> Result<T, ServiceError> {
+        let table = self.get(id)?;
+        let mut data = table.write_data().map_err(|e| ServiceError::Lock(e.to_string()))?;
+        Ok(f(&mut data))
+    }
+
+    fn edit_table<T>(&self, id: Uuid, group: Option<&str>, f: impl FnOnce(&mut TableData) -> Option<T>) -> Result<Option<T>, ServiceError> {
+        self.with_table_mut(id, |data| {
+            let before = data.clone();
+            let result = f(data);
+
+            if result.is_some() && before.to_file_data() != data.to_file_data() {
+                self.lock_histories()?.entry(id).or_default().record(before, group, Instant::now());
+            }
+
+            Ok(result)
+        })?
+    }
+
+    fn step_history(&self, id: Uuid, step: impl FnOnce(&mut TableHistory, &TableData) -> Option<TableData>) -> Result<bool, ServiceError> {
+        self.with_table_mut(id, |data| {
+            let restored = match self.lock_histories()?.get_mut(&id).and_then(|history| step(history, data)) {
+                Some(restored) => restored,
+                None => return Ok(false)
+            };
+
+            let (order, path) = (data.order(), data.path());
+            *data = restored;
+            data.set_order(order);
+            data.set_path(path);
+            data.set_modified(true);
+
+            Ok(true)
+        })?
+    }
+
+    pub fn undo(&self, id: Uuid) -> Result<bool, ServiceError> {
+        self.step_history(id, TableHistory::undo)
+    }
+
+    pub fn redo(&self, id: Uuid) -> Result<bool, ServiceError> {
+        self.step_history(id, TableHistory::redo)
+    }
+
+    pub fn history(&self, id: Uuid) -> Result<HistoryState, ServiceError> {
+        self.get(id)?;
+        Ok(self.lock_histories()?.get(&id).map(TableHistory::state).unwrap_or_default())
+    }
+
+    pub fn get_data(&self, id: Uuid) -> Result<TableData, ServiceError> {
+        self.with_table(id, TableData::clone)
+    }
+
+    /// Clones the table handles before reading each table, so a table that is locked for a save or edit doesn't hold up the map.
+    pub fn sorted(&self) -> Result<Vec<Table>, ServiceError> {
+        let mut tables = self.snapshot()?
+            .values()
+            .map(|table| table.read_data().map(|data| (data.order(), table.clone())))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ServiceError::Lock(e.to_string()))?;
+
+        tables.sort_by_key(|(order, _)| *order);
+        Ok(tables.into_iter().map(|(_, table)| table).collect())
+    }
+
+    pub fn order(&self) -> Result<Vec<Uuid>, ServiceError> {
+        Self::sorted_ids(&self.snapshot()?)
+    }
+
+    pub fn next_order(&self) -> usize {
+        self.next_order.load(Ordering::SeqCst)
+    }
+
+    fn set_order(&self, tables: &HashMap<Uuid, Table>, ids: &[Uuid]) -> Result<(), ServiceError> {
+        for (order, id) in ids.iter().enumerate() {
+            if let Some(table) = tables.get(id) {
+                table.write_data().map_err(|e| ServiceError::Lock(e.to_string()))?.set_order(order);
+            }
+        }
+
+        self.next_order.store(ids.len(), Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn sorted_ids(tables: &HashMap<Uuid, Table>) -> Result<Vec<Uuid>, ServiceError> {
+        let mut ids = tables.iter()
+            .map(|(id, table)| table.read_data().map(|data| (data.order(), *id)))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ServiceError::Lock(e.to_string()))?;
+
+        ids.sort();
+        Ok(ids.into_iter().map(|(_, id)| id).collect())
+    }
+
+    pub fn move_table(&self, id: Uuid, index: usize) -> Result<Vec<Uuid>, ServiceError> {
+        let tables = self.write_tables()?;
+        let mut ids = Self::sorted_ids(&tables)?;
+        let current = ids.iter().position(|i| *i == id).ok_or(ServiceError::TableNotFound(id))?;
+
+        ids.remove(current);
+        ids.insert(index.min(ids.len()), id);
+        self.set_order(&tables, &ids)?;
+
+        Ok(ids)
+    }
+
+    pub fn reorder_tables(&self, ids: Vec<Uuid>) -> Result<Vec<Uuid>, ServiceError> {
+        let tables = self.write_tables()?;
+
+        if let Some(id) = ids.iter().find(|id| !tables.contains_key(id)) {
+            return Err(ServiceError::TableNotFound(*id));
+        }
+
+        let mut order = Vec::with_capacity(tables.len());
+        for id in ids.into_iter().chain(Self::sorted_ids(&tables)?) {
+            if !order.contains(&id) {
+                order.push(id);
+            }
+        }
+
+        self.set_order(&tables, &order)?;
+        Ok(order)
+    }
+
+    pub fn summary(&self, id: Uuid) -> Result<TableSummary, ServiceError> {
+        self.with_table(id, |data| TableSummary::from(data))
+    }
+
+    pub fn summaries(&self) -> Result<Vec<TableSummary>, ServiceError> {
+        self.sorted()?
+            .iter()
+            .map(|table| table.read_data().map(|data| TableSummary::from(&*data)))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ServiceError::Lock(e.to_string()))
+    }
+
+    pub fn insert(&self, table_data: TableData) -> Result<Uuid, ServiceError> {
+        let id = table_data.id();
+        let mut tables = self.write_tables()?;
+
+        self.next_order.fetch_max(table_data.order() + 1, Ordering::SeqCst);
+        tables.insert(id, Table::from(table_data));
+
+        Ok(id)
+    }
+
+    pub fn append(&self, mut table_data: TableData) -> Result<Uuid, ServiceError> {
+        let mut tables = self.write_tables()?;
+        let id = table_data.id();
+
+        table_data.set_order(self.next_order.fetch_add(1, Ordering::SeqCst));
+        tables.insert(id, Table::from(table_data));
+
+        Ok(id)
+    }
+
+    pub fn clear(&self) -> Result<Vec<Uuid>, ServiceError> {
+        let mut tables = self.write_tables()?;
+        self.next_order.store(0, Ordering::SeqCst);
+        self.lock_histories()?.clear();
+
+        Ok(tables.drain().map(|(id, _)| id).collect())
+    }
+
+    pub fn add_table(&self, use_cost: bool, use_weight: bool, name: impl Into<String>, entries: Vec<TableEntry>) -> Result<Uuid, ServiceError> {
+        let mut table_data = TableData::new(use_cost, use_weight, name, 0);
+
+        for entry in sanitise_entries(entries) {
+            table_data.push(entry);
+        }
+
+        table_data.sort();
+        self.append(table_data)
+    }
+
+    pub fn update_table(
+        &self,
+        id: Uuid,
+        name: Option<String>,
+        use_cost: Option<bool>,
+        use_weight: Option<bool>,
+        entries: Option<Vec<TableEntry>>,
+        tags: Option<Vec<String>>
+    ) -> Result<(), ServiceError> {
+        self.edit_table(id, None, |data| {
+            if let Some(name) = name {
+                data.set_name(name);
+            }
+
+            if let Some(use_cost) = use_cost {
+                data.set_use_cost(use_cost);
+            }
+
+            if let Some(use_weight) = use_weight {
+                data.set_use_weight(use_weight);
+            }
+
+            if let Some(entries) = entries {
+                data.set_entries(entries);
+            }
+
+            if let Some(tags) = tags {
+                data.set_tags(tags);
+            }
+
+            Some(())
+        })?;
+
+        Ok(())
+    }
+
+    pub fn rename_table(&self, id: Uuid, name: impl Into<String>) -> Result<(), ServiceError> {
+        self.edit_table(id, Some("name"), |data| {
+            data.set_name(name);
+            Some(())
+        })?;
+
+        Ok(())
+    }
+
+    pub fn remove_table(&self, id: Uuid) -> Result<Table, ServiceError> {
+        let table = self.write_tables()?.remove(&id).ok_or(ServiceError::TableNotFound(id))?;
+        self.lock_histories()?.remove(&id);
+
+        Ok(table)
+    }
+
+    pub fn add_entries(&self, id: Uuid, entries: Vec<TableEntry>) -> Result<(), ServiceError> {
+        self.edit_table(id, None, |data| {
+            for entry in sanitise_entries(entries) {
+                data.push(entry);
+            }
+
+            data.sort();
+            Some(())
+        })?;
+
+        Ok(())
+    }
+
+    pub fn get_entry(&self, id: Uuid, entry_id: Uuid) -> Result<TableEntry, ServiceError> {
+        self.with_table(id, |data| data.get_entry(entry_id).cloned())?
+            .ok_or(ServiceError::EntryNotFound { id, entry_id })
+    }
+
+    pub fn update_entry(&self, id: Uuid, entry_id: Uuid, entry: TableEntry) -> Result<TableEntry, ServiceError> {
+        let mut entry = sanitise_entries(vec![entry])
+            .next()
+            .ok_or(ServiceError::EmptyEntryName(entry_id))?;
+        entry.set_id(entry_id);
+
+        self.edit_table(id, Some(&format!("entry:{}", entry_id)), |data| {
+            data.get_entry_mut(entry_id).map(|existing| {
+                *existing = entry.clone();
+                entry
+            })
+        })?
+        .ok_or(ServiceError::EntryNotFound { id, entry_id })
+    }
+
+    pub fn move_entry(&self, id: Uuid, entry_id: Uuid, index: usize) -> Result<usize, ServiceError> {
+        self.edit_table(id, Some(&format!("move:{}", entry_id)), |data| data.move_entry(entry_id, index))?
+            .ok_or(ServiceError::EntryNotFound { id, entry_id })
+    }
+
+    pub fn remove_entry(&self, id: Uuid, entry_id: Uuid) -> Result<TableEntry, ServiceError> {
+        self.edit_table(id, None, |data| data.remove_entry(entry_id))?
+            .ok_or(ServiceError::EntryNotFound { id, entry_id })
+    }
+
+    pub fn search_entries(&self, query: &str) -> Result<Vec<EntrySearchResult>, ServiceError> {
+        let sorted = self.sorted()?;
+        let guards = sorted.iter()
+            .map(|table| table.read_data())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ServiceError::Lock(e.to_string()))?;
+
+        Ok(search_entries(guards.iter().map(|guard| &**guard), query))
+    }
+
+    pub fn roll(&self, id: Uuid) -> Result<TableEntry, ServiceError> {
+        self.with_table(id, |data| {
+            if data.is_empty() {
+                return Err(ServiceError::EmptyTable(id));
+            }
+
+            data.get_random()
+                .cloned()
+                .map_err(|e| ServiceError::Random(e.to_string()))
+        })?
+    }
+
+    pub fn roll_set(&self, id: Uuid, limit: RollLimit, allow_duplicates: bool, use_weight: bool) -> Result<Vec<RollResult>, ServiceError> {
+        self.with_table(id, |data| {
+            let results = match limit {
+                RollLimit::Count(count) => data.get_random_set_by_count(use_weight, count, allow_duplicates),
+                RollLimit::Cost(cost) => data.get_random_set_by_cost(use_weight, cost, allow_duplicates)
+            };
+
+            results.map_err(|e| ServiceError::Random(e.to_string()))
+        })?
+    }
+
+    /// Writes a snapshot of the table with no lock held, so a slow disk doesn't block other commands. The table is only marked as
+    /// saved if it wasn't edited while the file was being written.
+    pub fn save_table(&self, id: Uuid, path: &Path, backup_count: usize) -> Result<(), ServiceError> {
+        let file_data = self.with_table(id, TableData::to_file_data)?;
+
+        rotate_backups(path, backup_count).map_err(ServiceError::File)?;
+        write_json_atomic(path, &file_data).map_err(ServiceError::File)?;
+
+        self.with_table_mut(id, |data| {
+            data.set_path(Some(path.to_path_buf()));
+            if data.to_file_data() == file_data {
+                data.set_modified(false);
+            }
+        })
+    }
+
+    pub fn open_table(&self, path: &Path) -> Result<Uuid, ServiceError> {
+        let file_data = read_table_file(path).map_err(ServiceError::File)?;
+        self.append(file_data.into_table_data(0, Some(path.to_path_buf())))
+    }
+
+    pub fn open_backup(&self, path: &Path) -> Result<(Uuid, PathBuf), ServiceError> {
+        for backup in list_backups(path) {
+            let file_data = match read_table_file(&backup) {
+                Ok(file_data) => file_data,
+                Err(e) => {
+                    log::error!("Failed to read backup {:?}: {}", &backup, e);
+                    continue;
+                }
+            };
+
+            let mut table_data = file_data.into_table_data(0, Some(path.to_path_buf()));
+            table_data.set_modified(true);
+
+            return Ok((self.append(table_data)?, backup));
+        }
+
+        Err(ServiceError::NoBackups(path.to_path_buf()))
+    }
+
+    pub fn resolve_conflict(&self, id: Uuid, resolution: ConflictResolution) -> Result<(), ServiceError> {
+        let path = self.with_table(id, |data| data.path())?.ok_or(ServiceError::NotSaved(id))?;
+        if resolution == ConflictResolution::KeepLocal {
+            return Ok(());
+        }
+
+        let file_data = read_table_file(&path).map_err(ServiceError::File)?;
+
+        self.with_table_mut(id, |data| match resolution {
+            ConflictResolution::KeepLocal => {},
+            ConflictResolution::Reload => {
+                *data = file_data.into_table_data_with_id(id, data.order(), Some(path));
+            },
+            ConflictResolution::Merge => {
+                let added = data.merge_entries(file_data.entries().iter().cloned());
+                log::info!("Merged {} entries from {:?}", added, &path);
+            }
+        })
+    }
+
+    pub fn has_unsaved_changes(&self) -> Result<bool, ServiceError> {
+        for table in self.read_tables()?.values() {
+            if table.read_data().map_err(|e| ServiceError::Lock(e.to_string()))?.is_modified() {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, sync::{Arc, mpsc}, thread, time::Duration};
+
+    use common_data::Currency;
+
+    use crate::history::MAX_HISTORY_DEPTH;
+
+    use super::*;
+
+    fn entry(name: &str, cost: Currency) -> TableEntry {
+        let mut entry = TableEntry::new(cost);
+        entry.set_name(name);
+        entry
+    }
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("table-service-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn add_sanitises_and_sorts_entries() {
+        let service = TableService::new();
+        let first = service.add_table(true, false, "Loot", vec![entry("  Torch ", Currency::Copper(1)), entry("   ", Currency::Copper(1)), entry("Axe", Currency::Gold(0))]).unwrap();
+        let second = service.add_table(false, false, "Names", Vec::new()).unwrap();
+
+        let data = service.get_data(first).unwrap();
+        assert_eq!(vec!["Axe", "Torch"], data.iter().map(|e| e.name()).collect::<Vec<_>>());
+        assert_eq!(Currency::Gold(1), data.entries()[0].cost());
+        drop(data);
+
+        let ids = service.summaries().unwrap().iter().map(|s| s.id()).collect::<Vec<_>>();
+        assert_eq!(vec![first, second], ids);
+    }
+
+    #[test]
+    fn update_and_remove_tables_and_entries() {
+        let service = TableService::new();
+        let id = service.add_table(false, false, "Loot", vec![entry("Torch", Currency::Copper(1))]).unwrap();
+
+        service.update_table(id, Some("Treasure".to_string()), Some(true), None, None, Some(vec!["gold".to_string()])).unwrap();
+        service.add_entries(id, vec![entry("Rope", Currency::Copper(2)), entry("", Currency::Copper(1))]).unwrap();
+
+        {
+            let data = service.get_data(id).unwrap();
+            assert_eq!("Treasure", data.name());
+            assert!(data.use_cost());
+            assert!(data.is_modified());
+            assert_eq!(&vec!["gold".to_string()], data.tags());
+            assert_eq!(2, data.len());
+        }
+
+        let rope = service.get_data(id).unwrap().entries()[0].id();
+        assert_eq!("Rope", service.remove_entry(id, rope).unwrap().name());
+        assert!(matches!(service.remove_entry(id, rope), Err(ServiceError::EntryNotFound { entry_id, .. }) if entry_id == rope));
+
+        service.remove_table(id).unwrap();
+        assert!(matches!(service.get(id), Err(ServiceError::TableNotFound(_))));
+        assert!(matches!(service.rename_table(id, "Gone"), Err(ServiceError::TableNotFound(_))));
+    }
+
+    #[test]
+    fn entries_are_addressed_by_id() {
+        let dir = temp_dir();
+        let path = dir.join("loot.table");
+        let service = TableService::new();
+        let id = service.add_table(false, false, "Loot", vec![entry("Torch", Currency::Copper(1)), entry("Axe", Currency::Copper(1))]).unwrap();
+        let torch = service.get_data(id).unwrap().entries()[1].id();
+
+        service.add_entries(id, vec![entry("Bow", Currency::Copper(1))]).unwrap();
+        assert_eq!("Torch", service.get_entry(id, torch).unwrap().name());
+        assert_eq!(vec!["Axe", "Bow", "Torch"], service.get_data(id).unwrap().iter().map(|e| e.name()).collect::<Vec<_>>());
+
+        assert_eq!(0, service.move_entry(id, torch, 0).unwrap());
+        assert_eq!(2, service.move_entry(id, torch, 10).unwrap());
+
+        let updated = service.update_entry(id, torch, entry(" Lantern ", Currency::Copper(3))).unwrap();
+        assert_eq!(torch, updated.id());
+        assert_eq!("Lantern", service.get_entry(id, torch).unwrap().name());
+        assert!(matches!(service.update_entry(id, torch, entry("", Currency::Copper(1))), Err(ServiceError::EmptyEntryName(_))));
+        assert!(matches!(service.get_entry(id, Uuid::new_v4()), Err(ServiceError::EntryNotFound { .. })));
+
+        service.save_table(id, &path, 0).unwrap();
+        let opened = service.open_table(&path).unwrap();
+        assert_eq!("Lantern", service.get_entry(opened, torch).unwrap().name());
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn tables_can_be_reordered() {
+        let service = TableService::new();
+        let first = service.add_table(false, false, "Loot", Vec::new()).unwrap();
+        let second = service.add_table(false, false, "Names", Vec::new()).unwrap();
+        let third = service.add_table(false, false, "Weather", Vec::new()).unwrap();
+
+        assert_eq!(vec![second, third, first], service.move_table(first, 10).unwrap());
+        assert_eq!(vec![third, second, first], service.reorder_tables(vec![third]).unwrap());
+        assert!(matches!(service.reorder_tables(vec![Uuid::new_v4()]), Err(ServiceError::TableNotFound(_))));
+
+        service.remove_table(second).unwrap();
+        let fourth = service.add_table(false, false, "Traps", Vec::new()).unwrap();
+        assert_eq!(vec![third, first, fourth], service.order().unwrap());
+
+        let ids = service.summaries().unwrap().iter().map(|s| s.id()).collect::<Vec<_>>();
+        assert_eq!(vec![third, first, fourth], ids);
+    }
+
+    #[test]
+    fn searches_entries_in_table_order() {
+        let service = TableService::new();
+        let first = service.add_table(false, false, "Loot", vec![entry("Driftglobe", Currency::Copper(1))]).unwrap();
+        let second = service.add_table(false, false, "Magic", vec![entry("Driftglobe", Currency::Copper(1)), entry("Rope", Currency::Copper(1))]).unwrap();
+
+        service.move_table(second, 0).unwrap();
+
+        let results = service.search_entries("driftglobe").unwrap();
+        assert_eq!(vec![second, first], results.iter().map(|r| r.table_id()).collect::<Vec<_>>());
+        assert_eq!(service.get_data(second).unwrap().entries()[0].id(), results[0].entry_id());
+    }
+
+    #[test]
+    fn edits_can_be_undone_and_redone() {
+        let service = TableService::new();
+        let id = service.add_table(false, false, "Loot", vec![entry("Torch", Currency::Copper(1)), entry("Rope", Currency::Copper(1))]).unwrap();
+        assert!(!service.history(id).unwrap().can_undo());
+
+        let rope = service.get_data(id).unwrap().iter().find(|e| e.name() == "Rope").unwrap().id();
+        service.remove_entry(id, rope).unwrap();
+        service.rename_table(id, "Treasure").unwrap();
+        assert_eq!(HistoryState::new(2, 0), service.history(id).unwrap());
+
+        assert!(service.undo(id).unwrap());
+        assert_eq!("Loot", service.get_data(id).unwrap().name());
+        assert!(service.undo(id).unwrap());
+        assert_eq!("Rope", service.get_entry(id, rope).unwrap().name());
+        assert!(!service.undo(id).unwrap());
+
+        assert!(service.redo(id).unwrap());
+        assert!(service.get_entry(id, rope).is_err());
+        assert_eq!(HistoryState::new(1, 1), service.history(id).unwrap());
+
+        service.add_entries(id, vec![entry("Lantern", Currency::Copper(1))]).unwrap();
+        assert!(!service.history(id).unwrap().can_redo());
+
+        service.remove_table(id).unwrap();
+        assert!(matches!(service.history(id), Err(ServiceError::TableNotFound(_))));
+    }
+
+    #[test]
+    fn related_edits_are_grouped_and_history_is_bounded() {
+        let service = TableService::new();
+        let id = service.add_table(false, false, "Loot", vec![entry("Torch", Currency::Copper(1))]).unwrap();
+
+        service.rename_table(id, "L").unwrap();
+        service.rename_table(id, "Lo").unwrap();
+        service.rename_table(id, "Lot").unwrap();
+        service.move_entry(id, service.get_data(id).unwrap().entries()[0].id(), 0).unwrap();
+        assert_eq!(1, service.history(id).unwrap().undo_count());
+
+        service.undo(id).unwrap();
+        assert_eq!("Loot", service.get_data(id).unwrap().name());
+
+        for index in 0..MAX_HISTORY_DEPTH + 10 {
+            service.add_entries(id, vec![entry(&format!("Gem {}", index), Currency::Copper(1))]).unwrap();
+        }
+
+        assert_eq!(MAX_HISTORY_DEPTH, service.history(id).unwrap().undo_count());
+    }
+
+    #[test]
+    fn tables_lock_independently() {
+        let service = TableService::new();
+        let first = service.add_table(false, false, "Loot", vec![entry("Torch", Currency::Copper(1))]).unwrap();
+        let second = service.add_table(false, false, "Names", vec![entry("Alys", Currency::Copper(1))]).unwrap();
+
+        let table = service.get(first).unwrap();
+        let _guard = table.write_data().unwrap();
+
+        assert_eq!("Alys", service.roll(second).unwrap().name());
+        service.rename_table(second, "People").unwrap();
+        assert_eq!("People", service.summary(second).unwrap().name());
+        assert!(service.remove_table(second).is_ok());
+    }
+
+    #[test]
+    fn rolls_respect_limits() {
+        let service = TableService::new();
+        let id = service.add_table(true, false, "Loot", vec![entry("Torch", Currency::Copper(1)), entry("Gem", Currency::Gold(5))]).unwrap();
+
+        let results = service.roll_set(id, RollLimit::Count(5), false, false).unwrap();
+        assert_eq!(2, results.iter().map(|r| r.count()).sum::<usize>());
+
+        let results = service.roll_set(id, RollLimit::Cost(Currency::Copper(3)), true, false).unwrap();
+        assert_eq!(vec![("Torch", 3)], results.iter().map(|r| (r.entry().name(), r.count())).collect::<Vec<_>>());
+
+        let empty = service.add_table(false, false, "Empty", Vec::new()).unwrap();
+        assert!(matches!(service.roll(empty), Err(ServiceError::EmptyTable(_))));
+    }
+
+    #[test]
+    fn a_locked_table_does_not_block_the_table_map() {
+        let service = Arc::new(TableService::new());
+        let id = service.add_table(false, false, "Loot", vec![entry("Torch", Currency::Copper(1))]).unwrap();
+        let table = service.get(id).unwrap();
+        let guard = table.write_data().unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let summaries = {
+            let service = service.clone();
+            let sender = sender.clone();
+            thread::spawn(move || sender.send(service.summaries().unwrap().len()).unwrap())
+        };
+
+        thread::sleep(Duration::from_millis(50));
+        let adder = {
+            let service = service.clone();
+            thread::spawn(move || sender.send(service.add_table(false, false, "Names", Vec::new()).map(|_| 0).unwrap()).unwrap())
+        };
+
+        assert_eq!(Ok(0), receiver.recv_timeout(Duration::from_secs(5)));
+        adder.join().unwrap();
+
+        drop(guard);
+        assert!(receiver.recv_timeout(Duration::from_secs(5)).unwrap() >= 1);
+        summaries.join().unwrap();
+    }
+
+    #[test]
+    fn save_and_open_round_trip() {
+        let dir = temp_dir();
+        let path = dir.join("loot.table");
+        let service = TableService::new();
+        let id = service.add_table(true, true, "Loot", vec![entry("Torch", Currency::Copper(1)), entry("Gem", Currency::Gold(5))]).unwrap();
+        service.update_table(id, None, None, None, None, Some(vec!["dungeon".to_string()])).unwrap();
+
+        service.save_table(id, &path, 2).unwrap();
+        {
+            let data = service.get_data(id).unwrap();
+            assert!(!data.is_modified());
+            assert_eq!(Some(path.clone()), data.path());
+        }
+
+        service.rename_table(id, "Changed").unwrap();
+        service.save_table(id, &path, 2).unwrap();
+        assert_eq!(1, list_backups(&path).len());
+
+        let other = TableService::new();
+        let opened = other.open_table(&path).unwrap();
+        assert_ne!(id, opened);
+        assert_eq!(service.get_data(id).unwrap().to_file_data(), other.get_data(opened).unwrap().to_file_data());
+        assert!(!other.has_unsaved_changes().unwrap());
+
+        let (backup_id, backup) = other.open_backup(&path).unwrap();
+        assert_eq!(list_backups(&path)[0], backup);
+        assert_eq!("Loot", other.get_data(backup_id).unwrap().name());
+        assert!(other.has_unsaved_changes().unwrap());
+
+        assert!(matches!(other.open_table(&dir.join("missing.table")), Err(ServiceError::File(BackendError::IoError { .. }))));
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn resolves_conflicts_from_disk() {
+        let dir = temp_dir();
+        let path = dir.join("loot.table");
+        let service = TableService::new();
+        let id = service.add_table(false, false, "Loot", vec![entry("Torch", Currency::Copper(1))]).unwrap();
+
+        assert!(matches!(service.resolve_conflict(id, ConflictResolution::Reload), Err(ServiceError::NotSaved(_))));
+        service.save_table(id, &path, 0).unwrap();
+
+        let mut file_data = service.get_data(id).unwrap().clone();
+        file_data.push(entry("Rope", Currency::Copper(1)));
+        write_json_atomic(&path, &file_data.to_file_data()).unwrap();
+
+        service.add_entries(id, vec![entry("Lantern", Currency::Copper(1))]).unwrap();
+        service.resolve_conflict(id, ConflictResolution::Merge).unwrap();
+        assert_eq!(3, service.get_data(id).unwrap().len());
+
+        service.resolve_conflict(id, ConflictResolution::Reload).unwrap();
+        assert_eq!(2, service.get_data(id).unwrap().len());
+        assert!(!service.get_data(id).unwrap().is_modified());
+
+        fs::remove_dir_all(dir).ok();
+    }
+}
\ No newline at end of file
@@ -0,0 +1,104 @@
+use std::{path::{Path, PathBuf}, fs::File};
+
+use common_data::BackendError;
+use serde::{Serialize, Deserialize, de::DeserializeOwned};
+use uuid::Uuid;
+
+use crate::{logging::get_data_dir, files::write_json_atomic};
+
+pub const MAX_RECENT_FILES: usize = 10;
+
+fn get_recent_files_file() -> PathBuf {
+    get_data_dir().join("recent.json")
+}
+
+fn get_session_file() -> PathBuf {
+    get_data_dir().join("session.json")
+}
+
+fn load_or_default<T: DeserializeOwned + Default>(path: PathBuf) -> T {
+    if !path.exists() {
+        return T::default();
+    }
+
+    let result = File::open(&path)
+        .map_err(BackendError::from)
+        .and_then(|file| serde_json::from_reader(file).map_err(BackendError::from));
+
+    match result {
+        Ok(value) => value,
+        Err(e) => {
+            log::error!("Failed to load {:?}: {}", path, e);
+            T::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecentFiles {
+    paths: Vec<PathBuf>
+}
+
+impl RecentFiles {
+    pub fn load() -> Self {
+        load_or_default(get_recent_files_file())
+    }
+
+    pub fn paths(&self) -> &Vec<PathBuf> {
+        &self.paths
+    }
+
+    pub fn add(&mut self, path: &Path) -> Result<(), BackendError> {
+        self.paths.retain(|p| p != path);
+        self.paths.insert(0, path.to_path_buf());
+        self.paths.truncate(MAX_RECENT_FILES);
+        self.save()
+    }
+
+    pub fn remove(&mut self, path: &Path) -> Result<(), BackendError> {
+        self.paths.retain(|p| p != path);
+        self.save()
+    }
+
+    pub fn clear(&mut self) -> Result<(), BackendError> {
+        self.paths.clear();
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), BackendError> {
+        write_json_atomic(&get_recent_files_file(), self)
+    }
+}
+
+/// A table that was open at shutdown: either a saved file, or the recovery copy of a table with unsaved changes. Sessions written
+/// before recovery copies were included are plain lists of paths, which still load.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SessionTable {
+    File(PathBuf),
+    Recovered { recovery: Uuid }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Session {
+    tables: Vec<SessionTable>
+}
+
+impl Session {
+    pub fn new(tables: Vec<SessionTable>) -> Self {
+        Self { tables }
+    }
+
+    pub fn load() -> Self {
+        load_or_default(get_session_file())
+    }
+
+    /// The open tables in tab order.
+    pub fn tables(&self) -> &Vec<SessionTable> {
+        &self.tables
+    }
+
+    pub fn save(&self) -> Result<(), BackendError> {
+        write_json_atomic(&get_session_file(), self)
+    }
+}
\ No newline at end of file
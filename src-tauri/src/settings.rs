@@ -0,0 +1,32 @@
+use std::{path::PathBuf, fs::File};
+
+use common_data::{settings::Settings, BackendError};
+
+use crate::{logging::get_data_dir, files::write_json_atomic};
+
+fn get_settings_file() -> PathBuf {
+    get_data_dir().join("settings.json")
+}
+
+pub fn load_settings() -> Settings {
+    let path = get_settings_file();
+    if !path.exists() {
+        return Settings::default();
+    }
+
+    let result = File::open(&path)
+        .map_err(BackendError::from)
+        .and_then(|file| serde_json::from_reader::<_, Settings>(file).map_err(BackendError::from));
+
+    match result {
+        Ok(settings) => settings.sanitised(),
+        Err(e) => {
+            log::error!("Failed to load settings from {:?}: {}", path, e);
+            Settings::default()
+        }
+    }
+}
+
+pub fn save_settings(settings: &Settings) -> Result<(), BackendError> {
+    write_json_atomic(&get_settings_file(), settings)
+}
\ No newline at end of file
@@ -0,0 +1,133 @@
+use std::{collections::HashMap, path::{Path, PathBuf}, sync::{mpsc, Arc, Mutex, MutexGuard}, thread};
+
+use common_data::BackendError;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use uuid::Uuid;
+
+/// The tables watching each path. The same file can be open in more than one table.
+type WatchedPaths = HashMap<PathBuf, Vec<Uuid>>;
+
+pub struct TableWatcher {
+    watcher: RecommendedWatcher,
+    paths: Arc<Mutex<WatchedPaths>>,
+    ids: HashMap<Uuid, PathBuf>,
+    directories: HashMap<PathBuf, usize>
+}
+
+/// Resolves symlinks and aliases like macOS's /var -> /private/var, so event paths and watched paths compare equal. A file that
+/// doesn't exist yet is resolved through its directory.
+fn canonical_path(path: &Path) -> PathBuf {
+    path.canonicalize()
+        .or_else(|_| match (path.parent(), path.file_name()) {
+            (Some(directory), Some(name)) => directory.canonicalize().map(|directory| directory.join(name)),
+            _ => Ok(path.to_path_buf())
+        })
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn lock_paths(paths: &Mutex<WatchedPaths>) -> Result<MutexGuard<WatchedPaths>, BackendError> {
+    paths.lock().map_err(|_| BackendError::internal_error("Unable to lock watched paths"))
+}
+
+impl TableWatcher {
+    pub fn new(mut on_change: impl FnMut(Uuid, PathBuf) + Send + 'static) -> Result<Self, BackendError> {
+        let (sender, receiver) = mpsc::channel::<notify::Result<Event>>();
+        let watcher = notify::recommended_watcher(sender)?;
+        let paths = Arc::new(Mutex::new(HashMap::new()));
+
+        let watched = paths.clone();
+        thread::spawn(move || {
+            for result in receiver {
+                let event = match result {
+                    Ok(event) => event,
+                    Err(e) => {
+                        log::error!("File watcher error: {}", e);
+                        continue;
+                    }
+                };
+
+                if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    continue;
+                }
+
+                for path in event.paths {
+                    let path = canonical_path(&path);
+                    let ids = match lock_paths(&watched) {
+                        Ok(paths) => paths.get(&path).cloned().unwrap_or_default(),
+                        Err(e) => {
+                            log::error!("{}", e);
+                            Vec::new()
+                        }
+                    };
+
+                    for id in ids {
+                        on_change(id, path.clone());
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            watcher,
+            paths,
+            ids: HashMap::new(),
+            directories: HashMap::new()
+        })
+    }
+
+    pub fn watch(&mut self, id: Uuid, path: &Path) -> Result<(), BackendError> {
+        self.unwatch(id)?;
+
+        let path = &canonical_path(path);
+
+        let directory = match path.parent() {
+            Some(directory) => directory.to_path_buf(),
+            None => return Ok(())
+        };
+
+        if !self.directories.contains_key(&directory) {
+            self.watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+        }
+
+        *self.directories.entry(directory).or_insert(0) += 1;
+        lock_paths(&self.paths)?.entry(path.to_path_buf()).or_default().push(id);
+        self.ids.insert(id, path.to_path_buf());
+        log::info!("Watching {:?} for changes", path);
+
+        Ok(())
+    }
+
+    pub fn unwatch(&mut self, id: Uuid) -> Result<(), BackendError> {
+        let path = match self.ids.remove(&id) {
+            Some(path) => path,
+            None => return Ok(())
+        };
+
+        {
+            let mut paths = lock_paths(&self.paths)?;
+            if let Some(ids) = paths.get_mut(&path) {
+                ids.retain(|watching| *watching != id);
+
+                if ids.is_empty() {
+                    paths.remove(&path);
+                }
+            }
+        }
+
+        let directory = match path.parent() {
+            Some(directory) => directory.to_path_buf(),
+            None => return Ok(())
+        };
+
+        if let Some(count) = self.directories.get_mut(&directory) {
+            *count -= 1;
+
+            if *count == 0 {
+                self.directories.remove(&directory);
+                self.watcher.unwatch(&directory)?;
+            }
+        }
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -1,47 +1,291 @@
 use std::path::PathBuf;
 
-use common_data::{TableEntry, Currency};
-use web_sys::HtmlInputElement;
+use common_data::{TableEntry, Currency, TableFileChange};
+use uuid::Uuid;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlInputElement};
 use yew::prelude::*;
 
-use crate::{components::{menu::Menu, table_tabs::TableTabs, full_page_modal::FullPageModal, remove_button::RemoveButton, currency_field::CurrencyField, number_field::NumberField, checkbox::Checkbox}, hooks::prelude::*, glue::*};
+use crate::{components::{menu::Menu, table_tabs::TableTabs, table_search::TableSearch, full_page_modal::FullPageModal, remove_button::RemoveButton, currency_field::CurrencyField, number_field::NumberField, checkbox::Checkbox, ledger_modal::LedgerModal, import_modal::ImportModal, paste_modal::PasteModal, bundle_modal::BundleTablesModal, recovery_modal::RecoveryModal, export_modal::ExportModal, settings_modal::SettingsModal, conflict_modal::FileConflictModal, library_modal::LibraryModal, error_banner::{ErrorBanner, ErrorNotice, report_error}}, hooks::prelude::*, glue::*, Error};
 
-fn save_table(is_menu_open: UseStateHandle<bool>, tables: UseTablesHandle) {
-    let is_menu_open = is_menu_open.clone();
-    let tables = tables.clone();
-    
+fn is_text_input(e: &KeyboardEvent) -> bool {
+    e.target()
+        .and_then(|target| target.dyn_into::<Element>().ok())
+        .map(|element| matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT"))
+        .unwrap_or(false)
+}
+
+fn handle_history_shortcut(e: KeyboardEvent, tables: &UseTablesHandle, notice: &UseStateHandle<Option<ErrorNotice>>) {
+    if !(e.ctrl_key() || e.meta_key()) || is_text_input(&e) {
+        return;
+    }
+
+    match (e.key().to_lowercase().as_str(), e.shift_key()) {
+        ("z", false) => {
+            e.prevent_default();
+            tables.undo(notice.clone());
+        },
+        ("z", true) | ("y", _) => {
+            e.prevent_default();
+            tables.redo(notice.clone());
+        },
+        _ => {}
+    }
+}
+
+fn save_table(is_menu_open: UseStateHandle<bool>, tables: UseTablesHandle, notice: UseStateHandle<Option<ErrorNotice>>) {
     if let Some(table) = tables.get_table_data() {
         if let Some(path) = table.path() {
-            save_table_with_callback(table.id(), path.to_path_buf(), move |_| {
-                is_menu_open.set(false);
-                log::info!("Saved table to {:?}", path);
-            });
+            save_table_to(table.id(), path.to_path_buf(), is_menu_open, tables, notice);
         }
-    } 
+    }
 }
 
-fn save_table_as(is_menu_open: UseStateHandle<bool>, tables: UseTablesHandle) {
-    let is_menu_open = is_menu_open.clone();
-    let tables = tables.clone();
-    
+fn save_table_as(is_menu_open: UseStateHandle<bool>, tables: UseTablesHandle, notice: UseStateHandle<Option<ErrorNotice>>) {
     get_save_table_path_with_callback(move |value: Option<PathBuf>| {
+        if let (Some(table), Some(path)) = (tables.get_table_data(), value) {
+            save_table_to(table.id(), path, is_menu_open.clone(), tables.clone(), notice.clone());
+        }
+    })
+}
+
+fn save_table_to(id: Uuid, path: PathBuf, is_menu_open: UseStateHandle<bool>, tables: UseTablesHandle, notice: UseStateHandle<Option<ErrorNotice>>) {
+    let retry = {
+        let path = path.clone();
         let is_menu_open = is_menu_open.clone();
         let tables = tables.clone();
-        if let (Some(table), Some(path)) = (tables.get_table_data(), value) {
-            save_table_with_callback(table.id(), path.clone(), move |_| {
-                is_menu_open.set(false);
-                tables.update_data();
-                log::info!("Saved table to {:?}", path);
+        let notice = notice.clone();
+        Callback::from(move |_: ()| save_table_to(id, path.clone(), is_menu_open.clone(), tables.clone(), notice.clone()))
+    };
+
+    let on_saved = {
+        let path = path.clone();
+        Callback::from(move |_: ()| {
+            is_menu_open.set(false);
+            tables.update_data();
+            log::info!("Saved table to {:?}", path);
+        })
+    };
+
+    save_table_with_error_callback(id, path, on_saved, report_error(notice, retry));
+}
+
+fn save_bundle(is_menu_open: UseStateHandle<bool>, notice: UseStateHandle<Option<ErrorNotice>>) {
+    get_save_bundle_path_with_callback(move |value: Option<PathBuf>| {
+        if let Some(path) = value {
+            save_bundle_to(path, is_menu_open.clone(), notice.clone());
+        }
+    })
+}
+
+fn save_bundle_to(path: PathBuf, is_menu_open: UseStateHandle<bool>, notice: UseStateHandle<Option<ErrorNotice>>) {
+    let retry = {
+        let path = path.clone();
+        let is_menu_open = is_menu_open.clone();
+        let notice = notice.clone();
+        Callback::from(move |_: ()| save_bundle_to(path.clone(), is_menu_open.clone(), notice.clone()))
+    };
+
+    let on_saved = {
+        let path = path.clone();
+        Callback::from(move |_: ()| {
+            is_menu_open.set(false);
+            log::info!("Saved campaign bundle to {:?}", path);
+        })
+    };
+
+    let name = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    save_bundle_with_error_callback(name, path, on_saved, report_error(notice, retry));
+}
+
+fn open_bundle(discard_unsaved: bool, is_menu_open: UseStateHandle<bool>, tables: UseTablesHandle, notice: UseStateHandle<Option<ErrorNotice>>) {
+    get_open_bundle_path_with_callback(move |value: Option<PathBuf>| {
+        if let Some(path) = value {
+            let is_menu_open = is_menu_open.clone();
+            let tables = tables.clone();
+            let notice = notice.clone();
+
+            bundle_has_ledger_with_callback(path.clone(), move |has_ledger: bool| {
+                let path = path.clone();
+                let is_menu_open = is_menu_open.clone();
+                let tables = tables.clone();
+                let notice = notice.clone();
+
+                if has_ledger {
+                    ask_user_with_callback("This bundle includes a party ledger. Replace your current ledger with it?", "Open bundle", move |import_ledger: bool| {
+                        open_bundle_from(path.clone(), discard_unsaved, import_ledger, is_menu_open.clone(), tables.clone(), notice.clone());
+                    });
+                } else {
+                    open_bundle_from(path, discard_unsaved, false, is_menu_open, tables, notice);
+                }
             });
         }
     })
 }
 
+fn open_bundle_from(path: PathBuf, discard_unsaved: bool, import_ledger: bool, is_menu_open: UseStateHandle<bool>, tables: UseTablesHandle, notice: UseStateHandle<Option<ErrorNotice>>) {
+    let retry = {
+        let path = path.clone();
+        let is_menu_open = is_menu_open.clone();
+        let tables = tables.clone();
+        let notice = notice.clone();
+        Callback::from(move |_: ()| open_bundle_from(path.clone(), discard_unsaved, import_ledger, is_menu_open.clone(), tables.clone(), notice.clone()))
+    };
+
+    let on_open = Callback::from(move |_: ()| {
+        tables.update();
+        is_menu_open.set(false);
+    });
+
+    open_bundle_with_error_callback(path, discard_unsaved, import_ledger, on_open, report_error(notice, retry));
+}
+
+fn open_table_from(path: PathBuf, is_menu_open: UseStateHandle<bool>, tables: UseTablesHandle, notice: UseStateHandle<Option<ErrorNotice>>) {
+    let on_open = {
+        let is_menu_open = is_menu_open.clone();
+        let tables = tables.clone();
+        Callback::from(move |_: ()| {
+            tables.update();
+            is_menu_open.set(false);
+        })
+    };
+
+    let retry = {
+        let path = path.clone();
+        let notice = notice.clone();
+        Callback::from(move |_: ()| open_table_from(path.clone(), is_menu_open.clone(), tables.clone(), notice.clone()))
+    };
+
+    let on_error = {
+        let path = path.clone();
+        let on_open = on_open.clone();
+        let report = report_error(notice, retry);
+        Callback::from(move |error: Error| {
+            if error.code() == "parse" {
+                offer_backup(path.clone(), on_open.clone());
+            } else {
+                report.emit(error);
+            }
+        })
+    };
+
+    open_table_with_error_callback(path, on_open, on_error);
+}
+
+fn offer_backup(path: PathBuf, on_open: Callback<()>) {
+    list_table_backups_with_callback(path.clone(), move |backups: Vec<PathBuf>| {
+        if backups.is_empty() {
+            return;
+        }
+
+        let path = path.clone();
+        let on_open = on_open.clone();
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+        ask_user_with_callback(format!("'{}' could not be read. Open its most recent backup instead?", name), "Open backup", move |confirmed: bool| {
+            if confirmed {
+                let on_open = on_open.clone();
+                open_table_backup_with_callback(path.clone(), move |_| {
+                    on_open.emit(());
+                });
+            }
+        });
+    });
+}
+
 #[function_component(App)]
 pub fn app() -> Html {
     let is_menu_open = use_state_eq(|| false);
     let is_new_table_modal_open = use_state_eq(|| false);
+    let is_ledger_modal_open = use_state_eq(|| false);
+    let is_import_modal_open = use_state_eq(|| false);
+    let is_export_modal_open = use_state_eq(|| false);
+    let is_paste_modal_open = use_state_eq(|| false);
+    let is_settings_modal_open = use_state_eq(|| false);
+    let is_library_modal_open = use_state_eq(|| false);
+    let file_conflict = use_state_eq(|| None::<TableFileChange>);
+    let recent_files = use_state_eq(Vec::<PathBuf>::new);
+    let bundle_path = use_state_eq(|| None::<PathBuf>);
+    let error_notice = use_state_eq(|| None::<ErrorNotice>);
     let tables = use_tables();
+    let latest_tables = use_mut_ref(|| tables.clone());
+    *latest_tables.borrow_mut() = tables.clone();
+
+    use_effect_with_deps({
+        let latest_tables = latest_tables.clone();
+        let error_notice = error_notice.clone();
+        move |_| {
+            listen_to_keydown(move |e: KeyboardEvent| {
+                handle_history_shortcut(e, &latest_tables.borrow(), &error_notice);
+            });
+        }
+    }, ());
+
+    use_effect_with_deps(|_| {
+        listen_to_event("close-requested", |_: ()| {
+            ask_user_with_callback("Some tables have unsaved changes. Quit anyway?", "Unsaved changes", |confirmed: bool| {
+                if confirmed {
+                    exit_app_with_callback(|_| {});
+                }
+            });
+        });
+    }, ());
+
+    use_effect_with_deps({
+        let file_conflict = file_conflict.clone();
+        move |_| {
+            listen_to_event("table-file-conflict", move |change: TableFileChange| {
+                file_conflict.set(Some(change));
+            });
+        }
+    }, ());
+
+    use_effect_with_deps({
+        let recent_files = recent_files.clone();
+        move |is_menu_open: &bool| {
+            if *is_menu_open {
+                get_recent_files_with_callback(move |value: Vec<PathBuf>| {
+                    recent_files.set(value);
+                });
+            }
+        }
+    }, *is_menu_open);
+
+    let clear_recent = {
+        let recent_files = recent_files.clone();
+        Callback::from(move |_: MouseEvent| {
+            let recent_files = recent_files.clone();
+            clear_recent_files_with_callback(move |_| {
+                recent_files.set(Vec::new());
+            });
+        })
+    };
+
+    let recent_items = recent_files.iter()
+        .map(|path| {
+            let open_recent = {
+                let is_menu_open = is_menu_open.clone();
+                let tables = tables.clone();
+                let error_notice = error_notice.clone();
+                let path = path.clone();
+
+                Callback::from(move |_: MouseEvent| open_table_from(path.clone(), is_menu_open.clone(), tables.clone(), error_notice.clone()))
+            };
+
+            let name = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+
+            html! {
+                <button title={path.to_string_lossy().to_string()} onclick={open_recent}>{name}</button>
+            }
+        })
+        .collect::<Html>();
+
+    let close_file_conflict = {
+        let file_conflict = file_conflict.clone();
+        Callback::from(move |_: ()| {
+            file_conflict.set(None);
+        })
+    };
 
     let open_new_table_modal = {
         let is_menu_open = is_menu_open.clone();
@@ -52,37 +296,191 @@ pub fn app() -> Html {
         })
     };
 
+    let open_ledger_modal = {
+        let is_menu_open = is_menu_open.clone();
+        let is_ledger_modal_open = is_ledger_modal_open.clone();
+        Callback::from(move |_: MouseEvent| {
+            is_menu_open.set(false);
+            is_ledger_modal_open.set(true);
+        })
+    };
+
+    let close_ledger_modal = {
+        let is_ledger_modal_open = is_ledger_modal_open.clone();
+        Callback::from(move |_: MouseEvent| {
+            is_ledger_modal_open.set(false);
+        })
+    };
+
+    let open_import_modal = {
+        let is_menu_open = is_menu_open.clone();
+        let is_import_modal_open = is_import_modal_open.clone();
+        Callback::from(move |_: MouseEvent| {
+            is_menu_open.set(false);
+            is_import_modal_open.set(true);
+        })
+    };
+
+    let close_import_modal = {
+        let is_import_modal_open = is_import_modal_open.clone();
+        Callback::from(move |_: ()| {
+            is_import_modal_open.set(false);
+        })
+    };
+
+    let open_paste_modal = {
+        let is_menu_open = is_menu_open.clone();
+        let is_paste_modal_open = is_paste_modal_open.clone();
+        Callback::from(move |_: MouseEvent| {
+            is_menu_open.set(false);
+            is_paste_modal_open.set(true);
+        })
+    };
+
+    let close_paste_modal = {
+        let is_paste_modal_open = is_paste_modal_open.clone();
+        Callback::from(move |_: ()| {
+            is_paste_modal_open.set(false);
+        })
+    };
+
+    let open_library_modal = {
+        let is_menu_open = is_menu_open.clone();
+        let is_library_modal_open = is_library_modal_open.clone();
+        Callback::from(move |_: MouseEvent| {
+            is_menu_open.set(false);
+            is_library_modal_open.set(true);
+        })
+    };
+
+    let close_library_modal = {
+        let is_library_modal_open = is_library_modal_open.clone();
+        Callback::from(move |_: ()| {
+            is_library_modal_open.set(false);
+        })
+    };
+
+    let open_settings_modal = {
+        let is_menu_open = is_menu_open.clone();
+        let is_settings_modal_open = is_settings_modal_open.clone();
+        Callback::from(move |_: MouseEvent| {
+            is_menu_open.set(false);
+            is_settings_modal_open.set(true);
+        })
+    };
+
+    let close_settings_modal = {
+        let is_settings_modal_open = is_settings_modal_open.clone();
+        Callback::from(move |_: ()| {
+            is_settings_modal_open.set(false);
+        })
+    };
+
+    let open_export_modal = {
+        let is_menu_open = is_menu_open.clone();
+        let is_export_modal_open = is_export_modal_open.clone();
+        Callback::from(move |_: MouseEvent| {
+            is_menu_open.set(false);
+            is_export_modal_open.set(true);
+        })
+    };
+
+    let close_export_modal = {
+        let is_export_modal_open = is_export_modal_open.clone();
+        Callback::from(move |_: ()| {
+            is_export_modal_open.set(false);
+        })
+    };
+
+    let save_bundle = {
+        let is_menu_open = is_menu_open.clone();
+        let error_notice = error_notice.clone();
+
+        Callback::from(move |_: MouseEvent| save_bundle(is_menu_open.clone(), error_notice.clone()))
+    };
+
+    let open_bundle = {
+        let is_menu_open = is_menu_open.clone();
+        let tables = tables.clone();
+        let error_notice = error_notice.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let is_menu_open = is_menu_open.clone();
+            let tables = tables.clone();
+            let error_notice = error_notice.clone();
+
+            let has_unsaved_changes = tables.has_unsaved_changes();
+            let message = if has_unsaved_changes {
+                "Opening a campaign bundle closes all open tables and discards their unsaved changes. Continue?"
+            } else {
+                "Opening a campaign bundle closes all open tables. Continue?"
+            };
+
+            if tables.len() == 0 {
+                open_bundle(false, is_menu_open, tables, error_notice);
+            } else {
+                ask_user_with_callback(message, "Open bundle", move |confirmed: bool| {
+                    if confirmed {
+                        open_bundle(has_unsaved_changes, is_menu_open.clone(), tables.clone(), error_notice.clone());
+                    }
+                });
+            }
+        })
+    };
+
+    let open_bundle_tables = {
+        let is_menu_open = is_menu_open.clone();
+        let bundle_path = bundle_path.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let is_menu_open = is_menu_open.clone();
+            let bundle_path = bundle_path.clone();
+
+            get_open_bundle_path_with_callback(move |value: Option<PathBuf>| {
+                if value.is_some() {
+                    is_menu_open.set(false);
+                    bundle_path.set(value);
+                }
+            });
+        })
+    };
+
+    let close_bundle_tables = {
+        let bundle_path = bundle_path.clone();
+        Callback::from(move |_: ()| {
+            bundle_path.set(None);
+        })
+    };
+
     let save_table = {
         let is_menu_open = is_menu_open.clone();
         let tables = tables.clone();
+        let error_notice = error_notice.clone();
 
-        Callback::from(move |_: MouseEvent| save_table(is_menu_open.clone(), tables.clone()))
+        Callback::from(move |_: MouseEvent| save_table(is_menu_open.clone(), tables.clone(), error_notice.clone()))
     };
 
     let save_table_as = {
         let is_menu_open = is_menu_open.clone();
         let tables = tables.clone();
+        let error_notice = error_notice.clone();
 
-        Callback::from(move |_: MouseEvent| save_table_as(is_menu_open.clone(), tables.clone()))
+        Callback::from(move |_: MouseEvent| save_table_as(is_menu_open.clone(), tables.clone(), error_notice.clone()))
     };
 
     let open_table = {
         let is_menu_open = is_menu_open.clone();
         let tables = tables.clone();
+        let error_notice = error_notice.clone();
 
         Callback::from(move |_: MouseEvent| {
             let is_menu_open = is_menu_open.clone();
             let tables = tables.clone();
-            
+            let error_notice = error_notice.clone();
+
             get_open_table_path_with_callback(move |value: Option<PathBuf>| {
-                let is_menu_open = is_menu_open.clone();
-                let tables = tables.clone();
-                
                 if let Some(path) = value {
-                    open_table_with_callback(path, move |_| {
-                        tables.update();
-                        is_menu_open.set(false);
-                    });
+                    open_table_from(path, is_menu_open.clone(), tables.clone(), error_notice.clone());
                 }
             })
         })
@@ -95,9 +493,34 @@ pub fn app() -> Html {
 
     html! {
         <>
+            <RecoveryModal tables={tables.clone()} />
+            if let Some(change) = file_conflict.as_ref() {
+                <FileConflictModal tables={tables.clone()} change={change.clone()} on_close={close_file_conflict} />
+            }
             if *is_new_table_modal_open {
                 <NewTableModal tables={tables.clone()} is_open={is_new_table_modal_open.clone()} is_menu_open={is_menu_open.clone()} />
             }
+            if *is_ledger_modal_open {
+                <LedgerModal on_close={close_ledger_modal} />
+            }
+            if *is_import_modal_open {
+                <ImportModal tables={tables.clone()} on_close={close_import_modal} />
+            }
+            if let Some(path) = bundle_path.as_ref() {
+                <BundleTablesModal tables={tables.clone()} path={path.clone()} on_close={close_bundle_tables} />
+            }
+            if *is_paste_modal_open {
+                <PasteModal tables={tables.clone()} on_close={close_paste_modal} />
+            }
+            if *is_library_modal_open {
+                <LibraryModal tables={tables.clone()} on_close={close_library_modal} />
+            }
+            if *is_settings_modal_open {
+                <SettingsModal on_close={close_settings_modal} />
+            }
+            if let (true, Some(table)) = (*is_export_modal_open, tables.get_table_data()) {
+                <ExportModal table={table} on_close={close_export_modal} />
+            }
             <div class="flex-row stretch no-scroll">
                 <Menu is_open={is_menu_open}>
                     <h2>{"Random table tool"}</h2>
@@ -105,9 +528,27 @@ pub fn app() -> Html {
                     <button onclick={save_table} disabled={is_save_disabled}>{"Save"}</button>
                     <button onclick={save_table_as} disabled={tables.get_selected_index().is_none()}>{"Save As"}</button>
                     <button onclick={open_table}>{"Open"}</button>
+                    <button onclick={open_library_modal}>{"Library"}</button>
+                    <button onclick={save_bundle} disabled={tables.len() == 0}>{"Save bundle"}</button>
+                    <button onclick={open_bundle}>{"Open bundle"}</button>
+                    <button onclick={open_bundle_tables}>{"Table from bundle"}</button>
+                    <button onclick={open_import_modal}>{"Import"}</button>
+                    <button onclick={open_paste_modal}>{"Paste table"}</button>
+                    <button onclick={open_export_modal} disabled={tables.get_selected_index().is_none()}>{"Export"}</button>
+                    <button onclick={open_ledger_modal}>{"Party ledger"}</button>
+                    <button onclick={open_settings_modal}>{"Settings"}</button>
+                    if !recent_files.is_empty() {
+                        <h3>{"Recent"}</h3>
+                        {recent_items}
+                        <button onclick={clear_recent}>{"Clear recent"}</button>
+                    }
                 </Menu>
                 <main class="flex-grow-1 stretch-height no-scroll">
-                    <TableTabs tables={tables.clone()} />
+                    <ErrorBanner notice={error_notice.clone()} />
+                    if tables.len() > 0 {
+                        <TableSearch tables={tables.clone()} />
+                    }
+                    <TableTabs tables={tables.clone()} notice={error_notice.clone()} />
                 </main>
             </div>
         </>
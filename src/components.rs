@@ -8,4 +8,15 @@ pub mod select;
 pub mod roll_modals;
 pub mod number_field;
 pub mod edit_table_modal;
-pub mod checkbox;
\ No newline at end of file
+pub mod checkbox;
+pub mod ledger_modal;
+pub mod import_modal;
+pub mod export_modal;
+pub mod paste_modal;
+pub mod bundle_modal;
+pub mod recovery_modal;
+pub mod settings_modal;
+pub mod conflict_modal;
+pub mod library_modal;
+pub mod error_banner;
+pub mod table_search;
\ No newline at end of file
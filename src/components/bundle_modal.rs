@@ -0,0 +1,83 @@
+use std::{path::PathBuf, ops::Deref};
+
+use common_data::IdNamePair;
+use uuid::Uuid;
+use yew::prelude::*;
+
+use crate::{components::full_page_modal::FullPageModal, hooks::prelude::*, glue::*};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct BundleTablesModalProps {
+    pub tables: UseTablesHandle,
+    pub path: PathBuf,
+    #[prop_or_default]
+    pub on_close: Callback<()>
+}
+
+#[function_component(BundleTablesModal)]
+pub fn bundle_tables_modal(props: &BundleTablesModalProps) -> Html {
+    let BundleTablesModalProps { tables, path, on_close } = props.clone();
+    let bundle_tables = use_state_eq(|| None::<Vec<IdNamePair>>);
+    let added = use_vec_state_eq(Vec::<Uuid>::new);
+
+    use_effect_with_deps({
+        let bundle_tables = bundle_tables.clone();
+        move |path: &PathBuf| {
+            list_bundle_tables_with_callback(path.clone(), move |value: Vec<IdNamePair>| {
+                bundle_tables.set(Some(value));
+            });
+        }
+    }, path.clone());
+
+    let content = match bundle_tables.deref() {
+        None => html! { <p>{"Loading..."}</p> },
+        Some(bundle_tables) => bundle_tables.iter()
+            .map(|table| {
+                let id = table.id();
+                let is_added = added.contains(&id);
+
+                let add_table = {
+                    let tables = tables.clone();
+                    let path = path.clone();
+                    let added = added.clone();
+
+                    Callback::from(move |_: MouseEvent| {
+                        let tables = tables.clone();
+                        let added = added.clone();
+
+                        extract_bundle_table_with_callback(path.clone(), id, move |_| {
+                            added.insert(id);
+                            tables.update();
+                        });
+                    })
+                };
+
+                html! {
+                    <tr>
+                        <td><p>{table.name()}</p></td>
+                        <td><button onclick={add_table} disabled={is_added}>{if is_added { "Added" } else { "Add" }}</button></td>
+                    </tr>
+                }
+            })
+            .collect::<Html>()
+    };
+
+    let close = Callback::from(move |_: MouseEvent| {
+        on_close.emit(());
+    });
+
+    html! {
+        <FullPageModal>
+            <h3 class="heading">{"Tables in bundle"}</h3>
+            <p>{"Choose the tables you want to add to the open tables. The bundle itself is not changed."}</p>
+            <table class="stretch-width">
+                <tbody>
+                    {content}
+                </tbody>
+            </table>
+            <div class="flex-row button-row">
+                <button class="flex-grow-1" onclick={close}>{"Close"}</button>
+            </div>
+        </FullPageModal>
+    }
+}
\ No newline at end of file
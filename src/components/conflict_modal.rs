@@ -0,0 +1,47 @@
+use common_data::{TableFileChange, ConflictResolution};
+use yew::prelude::*;
+
+use crate::{components::full_page_modal::FullPageModal, hooks::prelude::*, glue::*};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct FileConflictModalProps {
+    pub tables: UseTablesHandle,
+    pub change: TableFileChange,
+    #[prop_or_default]
+    pub on_close: Callback<()>
+}
+
+#[function_component(FileConflictModal)]
+pub fn file_conflict_modal(props: &FileConflictModalProps) -> Html {
+    let FileConflictModalProps { tables, change, on_close } = props.clone();
+
+    let resolve = |resolution: ConflictResolution| {
+        let tables = tables.clone();
+        let on_close = on_close.clone();
+        let id = change.id();
+
+        Callback::from(move |_: MouseEvent| {
+            let tables = tables.clone();
+            let on_close = on_close.clone();
+
+            resolve_table_conflict_with_callback(id, resolution, move |_| {
+                tables.update();
+                tables.update_data();
+                on_close.emit(());
+            });
+        })
+    };
+
+    html! {
+        <FullPageModal>
+            <h3 class="heading">{"File changed on disk"}</h3>
+            <p>{format!("'{}' was changed by another program, but it also has unsaved changes here.", change.name())}</p>
+            <p>{"Reload the file and lose your changes, merge in the entries that were added to the file, or keep your version and overwrite the file the next time you save."}</p>
+            <div class="flex-row button-row">
+                <button class="flex-grow-1" onclick={resolve(ConflictResolution::Reload)}>{"Reload"}</button>
+                <button class="flex-grow-1" onclick={resolve(ConflictResolution::Merge)}>{"Merge"}</button>
+                <button class="flex-grow-1" onclick={resolve(ConflictResolution::KeepLocal)}>{"Keep mine"}</button>
+            </div>
+        </FullPageModal>
+    }
+}
\ No newline at end of file
@@ -1,10 +1,11 @@
 use std::{sync::Arc, ops::Deref};
 
 use common_data::{TableData, Currency, TableEntry};
+use uuid::Uuid;
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
 
-use crate::{components::{full_page_modal::FullPageModal, number_field::NumberField, currency_field::CurrencyField, remove_button::RemoveButton, checkbox::Checkbox}, hooks::prelude::*, glue::update_table_with_callback};
+use crate::{components::{full_page_modal::FullPageModal, number_field::NumberField, currency_field::CurrencyField, remove_button::RemoveButton, checkbox::Checkbox, error_banner::{ErrorBanner, ErrorNotice, report_error}}, hooks::prelude::*, glue::{update_table_with_error_callback, add_entries_with_error_callback, update_entry_with_error_callback, move_entry_with_error_callback, remove_entry_with_error_callback}};
 
 #[derive(Debug, Clone, PartialEq, Properties)]
 pub struct EditTableModalProps {
@@ -21,7 +22,7 @@ pub fn edit_table_modal(props: &EditTableModalProps) -> Html {
     let is_add_modal_open = use_state_eq(|| false);
     let use_cost = use_state_eq(|| table.use_cost());
     let use_weight = use_state_eq(|| table.use_weight());
-    let entries = use_vec_state_eq(|| table.entries().clone());
+    let notice = use_state_eq(|| None::<ErrorNotice>);
 
     let show_modal = {
         let is_add_modal_open = is_add_modal_open.clone();
@@ -37,30 +38,81 @@ pub fn edit_table_modal(props: &EditTableModalProps) -> Html {
         })
     };
 
-    let update_entries = {
-        let entries = entries.clone();
+    let add_entries = {
+        let id = table.id();
         let is_add_modal_open = is_add_modal_open.clone();
+        let notice = notice.clone();
         Callback::from(move |new_entries: Vec<TableEntry>| {
-            entries.insert_all(new_entries);
-            is_add_modal_open.set(false);
+            add_entries_to(id, new_entries, is_add_modal_open.clone(), notice.clone());
         })
     };
 
     html! {
         if *is_add_modal_open {
-            <AddEntryModal use_cost={*use_cost} use_weight={*use_weight} on_complete={update_entries} on_cancel={hide_modal} />
+            <AddEntryModal use_cost={*use_cost} use_weight={*use_weight} notice={notice} on_complete={add_entries} on_cancel={hide_modal} />
         } else {
-            <EditTableModalContent table={table} use_cost={use_cost} use_weight={use_weight} entries={entries} on_update={on_update} on_cancel={on_cancel} on_open_add_entries={show_modal} />
+            <EditTableModalContent table={table} use_cost={use_cost} use_weight={use_weight} notice={notice} on_update={on_update} on_cancel={on_cancel} on_open_add_entries={show_modal} />
         }
     }
 }
 
+fn add_entries_to(id: Uuid, entries: Vec<TableEntry>, is_add_modal_open: UseStateHandle<bool>, notice: UseStateHandle<Option<ErrorNotice>>) {
+    let retry = {
+        let entries = entries.clone();
+        let is_add_modal_open = is_add_modal_open.clone();
+        let notice = notice.clone();
+        Callback::from(move |_: ()| add_entries_to(id, entries.clone(), is_add_modal_open.clone(), notice.clone()))
+    };
+
+    add_entries_with_error_callback(id, entries, move |_: ()| is_add_modal_open.set(false), report_error(notice, retry));
+}
+
+fn update_table_settings(id: Uuid, name: Option<String>, use_cost: Option<bool>, use_weight: Option<bool>, tags: Option<Vec<String>>, on_update: Callback<()>, notice: UseStateHandle<Option<ErrorNotice>>) {
+    let retry = {
+        let name = name.clone();
+        let tags = tags.clone();
+        let on_update = on_update.clone();
+        let notice = notice.clone();
+        Callback::from(move |_: ()| update_table_settings(id, name.clone(), use_cost, use_weight, tags.clone(), on_update.clone(), notice.clone()))
+    };
+
+    update_table_with_error_callback(id, name, use_cost, use_weight, None, tags, on_update, report_error(notice, retry));
+}
+
+fn update_entry_in(id: Uuid, entry: TableEntry, notice: UseStateHandle<Option<ErrorNotice>>) {
+    let retry = {
+        let entry = entry.clone();
+        let notice = notice.clone();
+        Callback::from(move |_: ()| update_entry_in(id, entry.clone(), notice.clone()))
+    };
+
+    update_entry_with_error_callback(id, entry.id(), entry, |_: TableEntry| {}, report_error(notice, retry));
+}
+
+fn move_entry_in(id: Uuid, entry_id: Uuid, index: usize, notice: UseStateHandle<Option<ErrorNotice>>) {
+    let retry = {
+        let notice = notice.clone();
+        Callback::from(move |_: ()| move_entry_in(id, entry_id, index, notice.clone()))
+    };
+
+    move_entry_with_error_callback(id, entry_id, index, |_: usize| {}, report_error(notice, retry));
+}
+
+fn remove_entry_from(id: Uuid, entry_id: Uuid, notice: UseStateHandle<Option<ErrorNotice>>) {
+    let retry = {
+        let notice = notice.clone();
+        Callback::from(move |_: ()| remove_entry_from(id, entry_id, notice.clone()))
+    };
+
+    remove_entry_with_error_callback(id, entry_id, |_: TableEntry| {}, report_error(notice, retry));
+}
+
 #[derive(Debug, Clone, PartialEq, Properties)]
 struct EditTableModalContentProps {
     pub table: Arc<TableData>,
-    pub entries: UseVecStateHandle<TableEntry>,
     pub use_cost: UseStateHandle<bool>,
     pub use_weight: UseStateHandle<bool>,
+    pub notice: UseStateHandle<Option<ErrorNotice>>,
     #[prop_or_default]
     pub on_update: Callback<()>,
     #[prop_or_default]
@@ -73,16 +125,18 @@ struct EditTableModalContentProps {
 fn edit_table_modal_content(props: &EditTableModalContentProps) -> Html {
     let EditTableModalContentProps {
         table,
-        entries,
         use_cost,
         use_weight,
+        notice,
         on_update,
         on_cancel,
         on_open_add_entries
     } = props.clone();
 
     let name = use_state_eq(|| table.name().to_string());
-    let is_update_disabled = entries.len() == 0;
+    let tags = use_state_eq(|| table.tags().join(", "));
+    let is_update_disabled = table.is_empty();
+    let last_index = table.len().saturating_sub(1);
 
     let update_name = {
         let name = name.clone();
@@ -100,6 +154,14 @@ fn edit_table_modal_content(props: &EditTableModalContentProps) -> Html {
         })
     };
 
+    let update_tags = {
+        let tags = tags.clone();
+        Callback::from(move |e: Event| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            tags.set(target.value());
+        })
+    };
+
     let update_use_cost = {
         let use_cost = use_cost.clone();
         Callback::from(move |checked: bool| {
@@ -118,71 +180,82 @@ fn edit_table_modal_content(props: &EditTableModalContentProps) -> Html {
         let on_update = on_update.clone();
         let table = table.clone();
         let name = name.clone();
+        let tags = tags.clone();
         let use_cost = use_cost.clone();
         let use_weight = use_weight.clone();
-        let entries = entries.clone();
+        let notice = notice.clone();
 
         Callback::from(move |_: MouseEvent| {
-            let on_update = on_update.clone();
             let name = some_if_different(table.name().to_string(), name.deref().clone());
             let use_cost = some_if_different(table.use_cost(), *use_cost);
             let use_weight = some_if_different(table.use_weight(), *use_weight);
-            let entries = some_if_different(table.entries().clone(), entries.deref().clone());
+            let tags = some_if_different(table.tags().clone(), parse_tags(&tags));
 
-            update_table_with_callback(table.id(), name, use_cost, use_weight, entries, move |_: ()| {
-                on_update.emit(());
-            });
+            update_table_settings(table.id(), name, use_cost, use_weight, tags, on_update.clone(), notice.clone());
         })
     };
 
-    let entry_items = entries.iter()
+    let entry_items = table.iter()
         .enumerate()
         .map(|(index, entry)| {
+            let id = table.id();
+            let entry_id = entry.id();
+
             let update_name = {
-                let old_name = entry.name().to_string();
-                let entries = entries.clone();
+                let entry = entry.clone();
+                let notice = notice.clone();
                 Callback::from(move |e: Event| {
                     let target: HtmlInputElement = e.target_unchecked_into();
                     let target_value = target.value();
-        
+
                     if !target_value.trim().is_empty() {
-                        entries.update_single(index, |old| {
-                            let mut new = old.clone();
-                            new.set_name(target_value.trim());
-                            new
-                        });
+                        let mut new = entry.clone();
+                        new.set_name(target_value.trim());
+                        update_entry_in(id, new, notice.clone());
                     } else {
-                        target.set_value(&old_name);
+                        target.set_value(entry.name());
                     }
                 })
             };
 
             let update_weight = {
-                let entries = entries.clone();
+                let entry = entry.clone();
+                let notice = notice.clone();
                 Callback::from(move |weight: usize| {
-                    entries.update_single(index, |old| {
-                        let mut new = old.clone();
-                        new.set_weight(weight);
-                        new
-                    });
+                    let mut new = entry.clone();
+                    new.set_weight(weight);
+                    update_entry_in(id, new, notice.clone());
                 })
             };
 
             let update_cost = {
-                let entries = entries.clone();
+                let entry = entry.clone();
+                let notice = notice.clone();
                 Callback::from(move |cost: Currency| {
-                    entries.update_single(index, |old| {
-                        let mut new = old.clone();
-                        new.set_cost(cost);
-                        new
-                    });
+                    let mut new = entry.clone();
+                    new.set_cost(cost);
+                    update_entry_in(id, new, notice.clone());
+                })
+            };
+
+            let move_up = {
+                let notice = notice.clone();
+                Callback::from(move |_: MouseEvent| {
+                    move_entry_in(id, entry_id, index.saturating_sub(1), notice.clone());
+                })
+            };
+
+            let move_down = {
+                let notice = notice.clone();
+                Callback::from(move |_: MouseEvent| {
+                    move_entry_in(id, entry_id, index + 1, notice.clone());
                 })
             };
 
             let remove_entry = {
-                let entries = entries.clone();
+                let notice = notice.clone();
                 Callback::from(move |_: MouseEvent| {
-                    entries.remove(index);
+                    remove_entry_from(id, entry_id, notice.clone());
                 })
             };
 
@@ -191,7 +264,7 @@ fn edit_table_modal_content(props: &EditTableModalContentProps) -> Html {
             });
 
             html! {
-                <div class="flex-row">
+                <div key={entry_id.to_string()} class="flex-row">
                     <input class="flex-grow-1" value={entry.name().to_string()} onchange={update_name} />
                     if *use_weight {
                         <NumberField<usize> title="Weight" class="number" value={entry.weight()} validate={validate_weight} on_change={update_weight} />
@@ -199,6 +272,8 @@ fn edit_table_modal_content(props: &EditTableModalContentProps) -> Html {
                     if *use_cost {
                         <CurrencyField title="Cost" on_change={update_cost} />
                     }
+                    <button title="Move up" onclick={move_up} disabled={index == 0}>{"▲"}</button>
+                    <button title="Move down" onclick={move_down} disabled={index == last_index}>{"▼"}</button>
                     <RemoveButton on_click={remove_entry} />
                 </div>
             }
@@ -208,11 +283,16 @@ fn edit_table_modal_content(props: &EditTableModalContentProps) -> Html {
     html! {
         <>
             <FullPageModal>
+                <ErrorBanner notice={notice} />
                 <table class="stretch-width settings">
                     <tr>
                         <td><p>{"Table Name:"}</p></td>
                         <td><input class="flex-grow-1" value={(*name).clone()} onchange={update_name} /></td>
                     </tr>
+                    <tr>
+                        <td><p>{"Tags:"}</p></td>
+                        <td><input class="flex-grow-1" placeholder="loot, dungeon" value={(*tags).clone()} onchange={update_tags} /></td>
+                    </tr>
                     <tr>
                         <td><p class="flex-grow-1">{"Use costs:"}</p></td>
                         <td><Checkbox class="stretch-height flex-row center-cross-axis end-main-axis" checked={*use_cost} on_change={update_use_cost} /></td>
@@ -224,6 +304,7 @@ fn edit_table_modal_content(props: &EditTableModalContentProps) -> Html {
                 </table>
                 <div class="flex-column flex-grow-1 table-style">
                     <h2>{"Table entries"}</h2>
+                    <p>{"Changes to entries are applied as you make them and can be undone from the table view."}</p>
                     <div class="flex-column content">
                         {entry_items}
                     </div>
@@ -231,7 +312,7 @@ fn edit_table_modal_content(props: &EditTableModalContentProps) -> Html {
                 <div class="flex-row button-row">
                     <button class="flex-grow-1" onclick={update_table} disabled={is_update_disabled}>{"Update table"}</button>
                     <button class="flex-grow-1" onclick={on_open_add_entries}>{"Add new entries"}</button>
-                    <button class="flex-grow-1" onclick={on_cancel}>{"Cancel"}</button>
+                    <button class="flex-grow-1" onclick={on_cancel}>{"Close"}</button>
                 </div>
             </FullPageModal>
         </>
@@ -242,6 +323,7 @@ fn edit_table_modal_content(props: &EditTableModalContentProps) -> Html {
 struct AddEntryModalProps {
     use_cost: bool,
     use_weight: bool,
+    notice: UseStateHandle<Option<ErrorNotice>>,
     #[prop_or_default]
     on_complete: Callback<Vec<TableEntry>>,
     #[prop_or_default]
@@ -250,7 +332,7 @@ struct AddEntryModalProps {
 
 #[function_component(AddEntryModal)]
 fn add_entry_modal(props: &AddEntryModalProps) -> Html {
-    let AddEntryModalProps { use_cost, use_weight, on_complete, on_cancel } = props.clone();
+    let AddEntryModalProps { use_cost, use_weight, notice, on_complete, on_cancel } = props.clone();
     let entries = use_vec_state(|| Vec::<TableEntry>::new());
     let disable_add = entries.len() == 0 || entries.iter().all(|e| e.name().trim().is_empty());
 
@@ -342,6 +424,7 @@ fn add_entry_modal(props: &AddEntryModalProps) -> Html {
 
     html! {
         <FullPageModal>
+            <ErrorBanner notice={notice} />
             <h3 class="heading">{"Add entries"}</h3>
             <p>{"Here you can add new entries to your table. Use the '+' button to add a new entry."}</p>
             <div class="flex-column flex-grow-1 table-style">
@@ -361,6 +444,18 @@ fn add_entry_modal(props: &AddEntryModalProps) -> Html {
     }
 }
 
+fn parse_tags(tags: &str) -> Vec<String> {
+    let mut parsed = Vec::<String>::new();
+
+    for tag in tags.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        if !parsed.iter().any(|t| t == tag) {
+            parsed.push(tag.to_string());
+        }
+    }
+
+    parsed
+}
+
 fn some_if_different<T: Eq>(original: T, new: T) -> Option<T> {
     if new == original {
         None
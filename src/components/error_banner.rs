@@ -0,0 +1,71 @@
+use yew::prelude::*;
+
+use crate::Error;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorNotice {
+    message: String,
+    retry: Option<Callback<()>>
+}
+
+impl ErrorNotice {
+    pub fn new(error: &Error, retry: Callback<()>) -> Self {
+        Self {
+            message: error.user_message(),
+            retry: error.is_retryable().then_some(retry)
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn retry(&self) -> Option<&Callback<()>> {
+        self.retry.as_ref()
+    }
+}
+
+pub fn report_error(notice: UseStateHandle<Option<ErrorNotice>>, retry: Callback<()>) -> Callback<Error> {
+    Callback::from(move |error: Error| {
+        notice.set(Some(ErrorNotice::new(&error, retry.clone())));
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct ErrorBannerProps {
+    pub notice: UseStateHandle<Option<ErrorNotice>>
+}
+
+#[function_component(ErrorBanner)]
+pub fn error_banner(props: &ErrorBannerProps) -> Html {
+    let ErrorBannerProps { notice } = props.clone();
+
+    let dismiss = {
+        let notice = notice.clone();
+        Callback::from(move |_: MouseEvent| {
+            notice.set(None);
+        })
+    };
+
+    let retry = notice.as_ref().and_then(|n| n.retry()).cloned().map(|retry| {
+        let notice = notice.clone();
+        Callback::from(move |_: MouseEvent| {
+            notice.set(None);
+            retry.emit(());
+        })
+    });
+
+    html! {
+        if let Some(current) = notice.as_ref() {
+            <div class="error-banner flex-row center-cross-axis">
+                <p class="flex-grow-1">{current.message()}</p>
+                <div class="button-row">
+                    if let Some(retry) = retry {
+                        <button onclick={retry}>{"Retry"}</button>
+                    }
+                    <button onclick={dismiss}>{"Dismiss"}</button>
+                </div>
+            </div>
+        }
+    }
+}
\ No newline at end of file
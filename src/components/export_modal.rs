@@ -0,0 +1,112 @@
+use std::{path::PathBuf, sync::Arc, ops::Deref};
+
+use common_data::{TableData, formats::{ExportFormat, share_code::encode_share_code}};
+use yew::prelude::*;
+
+use crate::{components::{full_page_modal::FullPageModal, select::SelectDirect}, glue::*, Error};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct ExportModalProps {
+    pub table: Arc<TableData>,
+    #[prop_or_default]
+    pub on_close: Callback<()>
+}
+
+#[function_component(ExportModal)]
+pub fn export_modal(props: &ExportModalProps) -> Html {
+    let ExportModalProps { table, on_close } = props.clone();
+    let format = use_state_eq(|| ExportFormat::Csv);
+    let error = use_state_eq(|| None::<String>);
+    let status = use_state_eq(String::new);
+
+    let export = {
+        let table = table.clone();
+        let format = format.clone();
+        let error = error.clone();
+        let on_close = on_close.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let format = *format;
+            let table = table.clone();
+            let error = error.clone();
+            let on_close = on_close.clone();
+
+            get_save_export_path_with_callback(format, move |path: Option<PathBuf>| {
+                let on_close = on_close.clone();
+                let error = error.clone();
+
+                if let Some(path) = path {
+                    export_table_with_error_callback(table.id(), format, path.clone(), move |_| {
+                        log::info!("Exported table to {:?}", path);
+                        on_close.emit(());
+                    }, move |e: Error| {
+                        error.set(Some(e.to_string()));
+                    });
+                }
+            });
+        })
+    };
+
+    let copy = {
+        let table = table.clone();
+        let format = format.clone();
+        let error = error.clone();
+        let status = status.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let status = status.clone();
+
+            match format.export_table(&table) {
+                Ok(contents) => write_clipboard_text_with_callback(contents, move |_| {
+                    status.set("Copied to clipboard".to_string());
+                }),
+                Err(e) => error.set(Some(e.to_string()))
+            }
+        })
+    };
+
+    let copy_share_code = {
+        let table = table.clone();
+        let error = error.clone();
+        let status = status.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let status = status.clone();
+
+            match encode_share_code(&table.to_file_data()) {
+                Ok(code) => write_clipboard_text_with_callback(code, move |_| {
+                    status.set("Copied share code to clipboard. Paste it with 'Paste table' to import it.".to_string());
+                }),
+                Err(e) => error.set(Some(e.to_string()))
+            }
+        })
+    };
+
+    let cancel = Callback::from(move |_: MouseEvent| {
+        on_close.emit(());
+    });
+
+    html! {
+        <FullPageModal>
+            <h3 class="heading">{format!("Export '{}'", table.name())}</h3>
+            <table class="stretch-width settings">
+                <tr>
+                    <td><p>{"Format:"}</p></td>
+                    <td><SelectDirect<ExportFormat> items={Arc::new(ExportFormat::get_values())} selected_item={format} /></td>
+                </tr>
+            </table>
+            if let Some(error) = error.deref() {
+                <p>{error}</p>
+            }
+            if !status.is_empty() {
+                <p>{status.to_string()}</p>
+            }
+            <div class="flex-row button-row">
+                <button class="flex-grow-1" onclick={export}>{"Save to file"}</button>
+                <button class="flex-grow-1" onclick={copy}>{"Copy to clipboard"}</button>
+                <button class="flex-grow-1" onclick={copy_share_code}>{"Copy share code"}</button>
+                <button class="flex-grow-1" onclick={cancel}>{"Cancel"}</button>
+            </div>
+        </FullPageModal>
+    }
+}
\ No newline at end of file
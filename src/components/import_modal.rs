@@ -0,0 +1,305 @@
+use std::{path::PathBuf, sync::Arc, ops::Deref};
+
+use common_data::{TableEntry, formats::{ImportFormat, ImportedFile, delimited::{DelimitedData, ColumnMapping}}};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{Error, components::{full_page_modal::FullPageModal, select::SelectDirect, checkbox::Checkbox}, hooks::prelude::*, glue::*};
+
+#[derive(Debug, Clone, PartialEq)]
+enum ColumnOption {
+    None,
+    Column(usize, String)
+}
+
+impl ColumnOption {
+    fn from_mapping(headers: &[String], column: Option<usize>) -> Self {
+        match column.and_then(|c| headers.get(c).map(|h| (c, h))) {
+            Some((column, header)) => Self::Column(column, header.clone()),
+            None => Self::None
+        }
+    }
+
+    fn get_all(headers: &[String]) -> Vec<Self> {
+        let mut options = vec![Self::None];
+        options.extend(headers.iter().enumerate().map(|(column, header)| Self::Column(column, header.clone())));
+        options
+    }
+
+    fn column(&self) -> Option<usize> {
+        match self {
+            Self::None => None,
+            Self::Column(column, _) => Some(*column)
+        }
+    }
+}
+
+impl std::fmt::Display for ColumnOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "(none)"),
+            Self::Column(_, header) => write!(f, "{}", header)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct ImportModalProps {
+    pub tables: UseTablesHandle,
+    #[prop_or_default]
+    pub on_close: Callback<()>
+}
+
+#[function_component(ImportModal)]
+pub fn import_modal(props: &ImportModalProps) -> Html {
+    let ImportModalProps { tables, on_close } = props.clone();
+    let format = use_state_eq(|| ImportFormat::Csv);
+    let data = use_state_eq(|| None::<Arc<DelimitedData>>);
+    let file_name = use_state_eq(String::new);
+    let error = use_state_eq(|| None::<String>);
+
+    let choose_file = {
+        let tables = tables.clone();
+        let on_close = on_close.clone();
+        let format = format.clone();
+        let data = data.clone();
+        let file_name = file_name.clone();
+        let error = error.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let tables = tables.clone();
+            let on_close = on_close.clone();
+            let format = *format;
+            let data = data.clone();
+            let file_name = file_name.clone();
+            let error = error.clone();
+
+            get_open_import_path_with_callback(format, move |path: Option<PathBuf>| {
+                let tables = tables.clone();
+                let on_close = on_close.clone();
+                let data = data.clone();
+                let file_name = file_name.clone();
+                let error = error.clone();
+
+                if let Some(path) = path {
+                    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+                    let on_error = {
+                        let error = error.clone();
+                        Callback::from(move |e: Error| error.set(Some(e.to_string())))
+                    };
+
+                    import_table_file_with_error_callback(path, format, move |imported: ImportedFile| {
+                        match imported {
+                            ImportedFile::Table(table) => {
+                                let tables = tables.clone();
+                                let on_close = on_close.clone();
+                                new_table_with_callback(table.use_cost(), table.use_weight(), table.name(), table.entries().clone(), move |_| {
+                                    tables.update();
+                                    on_close.emit(());
+                                });
+                            },
+                            ImportedFile::Delimited(parsed) => {
+                                file_name.set(stem.clone());
+                                error.set(None);
+                                data.set(Some(Arc::new(parsed)));
+                            }
+                        }
+                    }, on_error);
+                }
+            });
+        })
+    };
+
+    let cancel = {
+        let on_close = on_close.clone();
+        Callback::from(move |_: MouseEvent| {
+            on_close.emit(());
+        })
+    };
+
+    match data.deref() {
+        Some(data) => html! {
+            <ColumnMappingModal tables={tables} data={data.clone()} name={file_name.deref().clone()} on_close={on_close} />
+        },
+        None => html! {
+            <FullPageModal>
+                <h3 class="heading">{"Import table"}</h3>
+                <p>{"Choose the format of the file you want to import, then pick the file. For CSV and TSV files you will be able to choose which columns to use before the table is created."}</p>
+                <table class="stretch-width settings">
+                    <tr>
+                        <td><p>{"Format:"}</p></td>
+                        <td><SelectDirect<ImportFormat> items={Arc::new(ImportFormat::get_values())} selected_item={format} /></td>
+                    </tr>
+                </table>
+                if let Some(error) = error.deref() {
+                    <p>{error}</p>
+                }
+                <div class="flex-row button-row">
+                    <button class="flex-grow-1" onclick={choose_file}>{"Choose file"}</button>
+                    <button class="flex-grow-1" onclick={cancel}>{"Cancel"}</button>
+                </div>
+            </FullPageModal>
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+struct ColumnMappingModalProps {
+    tables: UseTablesHandle,
+    data: Arc<DelimitedData>,
+    name: String,
+    #[prop_or_default]
+    on_close: Callback<()>
+}
+
+#[function_component(ColumnMappingModal)]
+fn column_mapping_modal(props: &ColumnMappingModalProps) -> Html {
+    let ColumnMappingModalProps { tables, data, name, on_close } = props.clone();
+    let mapping = use_state_eq(|| data.guess_mapping());
+    let table_name = use_state_eq(|| name.clone());
+    let name_column = use_state_eq(|| ColumnOption::from_mapping(data.headers(), mapping.name()));
+    let weight_column = use_state_eq(|| ColumnOption::from_mapping(data.headers(), mapping.weight()));
+    let cost_column = use_state_eq(|| ColumnOption::from_mapping(data.headers(), mapping.cost()));
+    let options = Arc::new(ColumnOption::get_all(data.headers()));
+
+    let set_column = |setter: fn(&mut ColumnMapping, Option<usize>)| {
+        let mapping = mapping.clone();
+        Callback::from(move |option: ColumnOption| {
+            let mut updated = mapping.deref().clone();
+            setter(&mut updated, option.column());
+            mapping.set(updated);
+        })
+    };
+
+    let update_name = {
+        let table_name = table_name.clone();
+        Callback::from(move |e: Event| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            table_name.set(target.value().trim().to_string());
+        })
+    };
+
+    let create_table = {
+        let tables = tables.clone();
+        let data = data.clone();
+        let mapping = mapping.clone();
+        let table_name = table_name.clone();
+        let on_close = on_close.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let tables = tables.clone();
+            let on_close = on_close.clone();
+
+            if let Ok(entries) = data.to_entries(&mapping) {
+                new_table_with_callback(mapping.cost().is_some(), mapping.weight().is_some(), table_name.deref().clone(), entries, move |_| {
+                    tables.update();
+                    on_close.emit(());
+                });
+            }
+        })
+    };
+
+    let cancel = {
+        let on_close = on_close.clone();
+        Callback::from(move |_: MouseEvent| {
+            on_close.emit(());
+        })
+    };
+
+    let extra_columns = data.headers()
+        .iter()
+        .enumerate()
+        .filter(|(column, _)| Some(*column) != mapping.name() && Some(*column) != mapping.weight() && Some(*column) != mapping.cost())
+        .map(|(column, header)| {
+            let toggle = {
+                let mapping = mapping.clone();
+                Callback::from(move |checked: bool| {
+                    let mut updated = mapping.deref().clone();
+                    updated.set_extra(column, checked);
+                    mapping.set(updated);
+                })
+            };
+
+            html! {
+                <tr>
+                    <td><p>{format!("Include '{}':", header)}</p></td>
+                    <td><Checkbox class="stretch-height flex-row center-cross-axis end-main-axis" checked={mapping.extra().contains(&column)} on_change={toggle} /></td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+    let preview = data.preview(&mapping, 5);
+    let is_create_disabled = table_name.is_empty() || !matches!(data.to_entries(&mapping), Ok(entries) if !entries.is_empty());
+
+    let preview_html = match preview {
+        Err(e) => html! { <p>{e.to_string()}</p> },
+        Ok(entries) => html! {
+            <table class="stretch-width">
+                <thead>
+                    <tr>
+                        <th>{"Weight"}</th>
+                        <th>{"Entry"}</th>
+                        <th>{"Cost"}</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {entries.iter().map(preview_row).collect::<Html>()}
+                </tbody>
+            </table>
+        }
+    };
+
+    html! {
+        <FullPageModal>
+            <h3 class="heading">{"Import table"}</h3>
+            <p>{format!("Found {} rows. Choose which columns hold the entry names, weights and costs.", data.rows().len())}</p>
+            <table class="stretch-width settings">
+                <tr>
+                    <td><p>{"Table Name:"}</p></td>
+                    <td><input class="flex-grow-1" value={table_name.deref().clone()} onchange={update_name} /></td>
+                </tr>
+                <tr>
+                    <td><p>{"Name column:"}</p></td>
+                    <td><SelectDirect<ColumnOption> items={options.clone()} selected_item={name_column} on_change={set_column(ColumnMapping::set_name)} /></td>
+                </tr>
+                <tr>
+                    <td><p>{"Weight column:"}</p></td>
+                    <td><SelectDirect<ColumnOption> items={options.clone()} selected_item={weight_column} on_change={set_column(ColumnMapping::set_weight)} /></td>
+                </tr>
+                <tr>
+                    <td><p>{"Cost column:"}</p></td>
+                    <td><SelectDirect<ColumnOption> items={options} selected_item={cost_column} on_change={set_column(ColumnMapping::set_cost)} /></td>
+                </tr>
+                {extra_columns}
+            </table>
+            <h3>{"Preview"}</h3>
+            {preview_html}
+            <div class="flex-row button-row">
+                <button class="flex-grow-1" onclick={create_table} disabled={is_create_disabled}>{"Create table"}</button>
+                <button class="flex-grow-1" onclick={cancel}>{"Cancel"}</button>
+            </div>
+        </FullPageModal>
+    }
+}
+
+fn preview_row(entry: &TableEntry) -> Html {
+    let fields = entry.fields()
+        .iter()
+        .map(|(key, value)| format!("{}: {}", key, value))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    html! {
+        <tr>
+            <td>{entry.weight()}</td>
+            <td>
+                <p>{entry.name()}</p>
+                if !fields.is_empty() {
+                    <p>{fields}</p>
+                }
+            </td>
+            <td>{entry.cost().to_string()}</td>
+        </tr>
+    }
+}
\ No newline at end of file
@@ -0,0 +1,319 @@
+use std::ops::Deref;
+
+use common_data::{Balance, BackendError, Currency, ledger::Ledger};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{components::{full_page_modal::FullPageModal, currency_field::CurrencyFieldDirect, number_field::NumberField, checkbox::Checkbox, remove_button::RemoveButton, error_banner::{ErrorBanner, ErrorNotice}}, hooks::prelude::*, glue::*, Error};
+
+fn value_text(value: Result<Currency, BackendError>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_else(|_| "Too large to total".to_string())
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct LedgerModalProps {
+    #[prop_or_default]
+    pub on_close: Callback<MouseEvent>
+}
+
+#[function_component(LedgerModal)]
+pub fn ledger_modal(props: &LedgerModalProps) -> Html {
+    let LedgerModalProps { on_close } = props.clone();
+    let ledger = use_state_eq(|| None::<Ledger>);
+    let problem = use_state_eq(|| None::<String>);
+    let notice = use_state_eq(|| None::<ErrorNotice>);
+    let update_state = use_state_eq(|| false);
+    let description = use_state_eq(String::new);
+    let category = use_state_eq(String::new);
+    let amount = use_currency_state_eq(|| Currency::Gold(1));
+    let is_expense = use_state_eq(|| false);
+    let members = use_state_eq(|| 4_usize);
+
+    use_effect_with_deps({
+        let ledger = ledger.clone();
+        let problem = problem.clone();
+        move |_| {
+            let on_error = {
+                let problem = problem.clone();
+                move |error: Error| problem.set(Some(error.user_message()))
+            };
+
+            get_ledger_with_error_callback(move |updated: Ledger| {
+                problem.set(None);
+                ledger.set(Some(updated));
+            }, on_error);
+        }
+    }, update_state.clone());
+
+    let refresh = {
+        let update_state = update_state.clone();
+        Callback::from(move |_: ()| {
+            update_state.set(!*update_state);
+        })
+    };
+
+    let update_description = {
+        let description = description.clone();
+        Callback::from(move |e: Event| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            description.set(target.value().trim().to_string());
+        })
+    };
+
+    let update_category = {
+        let category = category.clone();
+        Callback::from(move |e: Event| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            category.set(target.value().trim().to_string());
+        })
+    };
+
+    let update_is_expense = {
+        let is_expense = is_expense.clone();
+        Callback::from(move |checked: bool| {
+            is_expense.set(checked);
+        })
+    };
+
+    let update_members = {
+        let members = members.clone();
+        Callback::from(move |value: usize| {
+            members.set(value);
+        })
+    };
+
+    let add_transaction = {
+        let description = description.clone();
+        let category = category.clone();
+        let amount = amount.clone();
+        let is_expense = is_expense.clone();
+        let notice = notice.clone();
+        let refresh = refresh.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let refresh = refresh.clone();
+            let value = Balance::from(amount.currency());
+            let value = match if *is_expense { value.checked_neg() } else { Ok(value) } {
+                Ok(value) => value,
+                Err(e) => {
+                    notice.set(Some(ErrorNotice::new(&Error::BackendError(e), Callback::from(|_: ()| {}))));
+                    return;
+                }
+            };
+
+            add_ledger_transaction_with_callback(description.deref().clone(), category.deref().clone(), value, move |_| {
+                refresh.emit(());
+            });
+        })
+    };
+
+    let start_new = {
+        let refresh = refresh.clone();
+        Callback::from(move |_: MouseEvent| {
+            let refresh = refresh.clone();
+            start_new_ledger_with_callback(move |_| refresh.emit(()));
+        })
+    };
+
+    let validate_members = Callback::from(move |value: usize| value.clamp(1, 20));
+
+    let content = match (problem.deref(), ledger.deref()) {
+        (Some(problem), _) => html! {
+            <>
+                <p>{"Your ledger could not be loaded, so it has been left untouched."}</p>
+                <p>{problem}</p>
+                <p>{"Starting a new ledger keeps the unreadable file in the app's data folder as ledger.json.corrupt-<date>."}</p>
+                <div class="flex-row button-row">
+                    <button class="flex-grow-1" onclick={start_new}>{"Start a new ledger"}</button>
+                </div>
+            </>
+        },
+        (None, None) => html! { <p>{"Loading..."}</p> },
+        (None, Some(ledger)) => {
+            let totals = match ledger.totals_by_category() {
+                Ok(totals) => totals.iter()
+                    .map(|total| html! {
+                        <tr>
+                            <td>{total.item_count()}</td>
+                            <td><p>{total.category()}</p></td>
+                            <td>{total.item_value().to_string()}</td>
+                            <td>{total.balance().to_string()}</td>
+                        </tr>
+                    })
+                    .collect::<Html>(),
+                Err(e) => html! {
+                    <tr>
+                        <td colspan="4"><p>{Error::BackendError(e).user_message()}</p></td>
+                    </tr>
+                }
+            };
+
+            let transactions = ledger.transactions()
+                .iter()
+                .map(|transaction| {
+                    let remove = {
+                        let refresh = refresh.clone();
+                        let id = transaction.id();
+                        Callback::from(move |_: MouseEvent| {
+                            let refresh = refresh.clone();
+                            remove_ledger_transaction_with_callback(id, move |_| refresh.emit(()));
+                        })
+                    };
+
+                    html! {
+                        <tr>
+                            <td>{transaction.date().format("%Y-%m-%d").to_string()}</td>
+                            <td><p>{transaction.description()}</p></td>
+                            <td>{transaction.category()}</td>
+                            <td>{transaction.amount().to_string()}</td>
+                            <td><RemoveButton on_click={remove} /></td>
+                        </tr>
+                    }
+                })
+                .collect::<Html>();
+
+            let items = ledger.items()
+                .iter()
+                .enumerate()
+                .map(|(index, item)| {
+                    let remove = {
+                        let refresh = refresh.clone();
+                        Callback::from(move |_: MouseEvent| {
+                            let refresh = refresh.clone();
+                            remove_ledger_item_with_callback(index, move |_| refresh.emit(()));
+                        })
+                    };
+
+                    html! {
+                        <tr>
+                            <td>{format!("{}x", item.count())}</td>
+                            <td><p>{item.name()}</p></td>
+                            <td>{item.category()}</td>
+                            <td>{value_text(item.total_cost())}</td>
+                            <td><RemoveButton on_click={remove} /></td>
+                        </tr>
+                    }
+                })
+                .collect::<Html>();
+
+            let shares = ledger.split(*members)
+                .into_iter()
+                .enumerate()
+                .map(|(index, share)| html! {
+                    <tr>
+                        <td>{index + 1}</td>
+                        <td><p>{share.to_string()}</p></td>
+                    </tr>
+                })
+                .collect::<Html>();
+
+            html! {
+                <>
+                    <table class="stretch-width blank left-align">
+                        <tr>
+                            <td>{"Balance:"}</td>
+                            <td>{ledger.balance().to_string()}</td>
+                        </tr>
+                        <tr>
+                            <td>{"Inventory value:"}</td>
+                            <td>{value_text(ledger.item_value())}</td>
+                        </tr>
+                    </table>
+                    <h3>{"Totals by category"}</h3>
+                    <table class="stretch-width">
+                        <thead>
+                            <tr>
+                                <th>{"Items"}</th>
+                                <th>{"Category"}</th>
+                                <th>{"Value"}</th>
+                                <th>{"Balance"}</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {totals}
+                        </tbody>
+                    </table>
+                    <h3>{"Transactions"}</h3>
+                    <table class="stretch-width">
+                        <thead>
+                            <tr>
+                                <th>{"Date"}</th>
+                                <th>{"Description"}</th>
+                                <th>{"Category"}</th>
+                                <th>{"Amount"}</th>
+                                <th />
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {transactions}
+                        </tbody>
+                    </table>
+                    <h3>{"Inventory"}</h3>
+                    <table class="stretch-width">
+                        <thead>
+                            <tr>
+                                <th>{"Amount"}</th>
+                                <th>{"Item"}</th>
+                                <th>{"Category"}</th>
+                                <th>{"Value"}</th>
+                                <th />
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {items}
+                        </tbody>
+                    </table>
+                    <h3>{"Split treasury"}</h3>
+                    <table class="stretch-width blank left-align">
+                        <tr>
+                            <td>{"Party members:"}</td>
+                            <td><NumberField<usize> class="number" get_default={|_: ()| 1_usize} value={*members} validate={validate_members} on_change={update_members} /></td>
+                        </tr>
+                    </table>
+                    <table class="stretch-width">
+                        <thead>
+                            <tr>
+                                <th>{"Member"}</th>
+                                <th>{"Share"}</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {shares}
+                        </tbody>
+                    </table>
+                </>
+            }
+        }
+    };
+
+    html! {
+        <FullPageModal>
+            <h2 class="heading">{"Party ledger"}</h2>
+            {content}
+            <h3>{"New transaction"}</h3>
+            <table class="stretch-width settings">
+                <tr>
+                    <td><p>{"Description:"}</p></td>
+                    <td><input class="flex-grow-1" value={description.deref().clone()} onchange={update_description} /></td>
+                </tr>
+                <tr>
+                    <td><p>{"Category:"}</p></td>
+                    <td><input class="flex-grow-1" value={category.deref().clone()} onchange={update_category} /></td>
+                </tr>
+                <tr>
+                    <td><p>{"Amount:"}</p></td>
+                    <td><CurrencyFieldDirect container_class="end-main-axis" amount={amount.amount_handle()} currency_type={amount.currency_type_handle()} /></td>
+                </tr>
+                <tr>
+                    <td><p>{"Expense:"}</p></td>
+                    <td><Checkbox class="stretch-height flex-row center-cross-axis end-main-axis" checked={*is_expense} on_change={update_is_expense} /></td>
+                </tr>
+            </table>
+            <ErrorBanner notice={notice} />
+            <div class="flex-row button-row">
+                <button class="flex-grow-1" onclick={add_transaction} disabled={description.is_empty()}>{"Add transaction"}</button>
+                <button class="flex-grow-1" onclick={on_close}>{"Close"}</button>
+            </div>
+        </FullPageModal>
+    }
+}
\ No newline at end of file
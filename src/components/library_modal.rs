@@ -0,0 +1,199 @@
+use std::{cell::Cell, ops::Deref, rc::Rc, sync::Arc};
+
+use common_data::{library::LibrarySearchResult, settings::Settings};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{components::{full_page_modal::FullPageModal, select::SelectDirect}, hooks::prelude::*, glue::*};
+
+#[derive(Debug, Clone, PartialEq)]
+enum TagOption {
+    All,
+    Tag(String)
+}
+
+impl TagOption {
+    fn get_all(tags: &[String]) -> Vec<Self> {
+        let mut options = vec![Self::All];
+        options.extend(tags.iter().map(|tag| Self::Tag(tag.clone())));
+        options
+    }
+
+    fn tag(&self) -> Option<String> {
+        match self {
+            Self::All => None,
+            Self::Tag(tag) => Some(tag.clone())
+        }
+    }
+}
+
+impl std::fmt::Display for TagOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::All => write!(f, "(all tags)"),
+            Self::Tag(tag) => write!(f, "{}", tag)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct LibraryModalProps {
+    pub tables: UseTablesHandle,
+    #[prop_or_default]
+    pub on_close: Callback<()>
+}
+
+#[function_component(LibraryModal)]
+pub fn library_modal(props: &LibraryModalProps) -> Html {
+    let LibraryModalProps { tables, on_close } = props.clone();
+    let is_loaded = use_state_eq(|| false);
+    let has_library = use_state_eq(|| true);
+    let refresh_state = use_state_eq(|| false);
+    let query = use_state_eq(String::new);
+    let tags = use_state_eq(|| Arc::new(vec![TagOption::All]));
+    let tag = use_state_eq(|| TagOption::All);
+    let results = use_state_eq(Vec::<LibrarySearchResult>::new);
+
+    use_effect_with_deps({
+        let is_loaded = is_loaded.clone();
+        let has_library = has_library.clone();
+        let tags = tags.clone();
+        move |_| {
+            get_settings_with_callback(move |settings: Settings| {
+                if settings.library_dir().is_none() {
+                    has_library.set(false);
+                    return;
+                }
+
+                let is_loaded = is_loaded.clone();
+                let tags = tags.clone();
+                refresh_library_with_callback(move |_| {
+                    let is_loaded = is_loaded.clone();
+                    let tags = tags.clone();
+                    get_library_tags_with_callback(move |value: Vec<String>| {
+                        tags.set(Arc::new(TagOption::get_all(&value)));
+                        is_loaded.set(true);
+                    });
+                });
+            });
+        }
+    }, refresh_state.clone());
+
+    use_effect_with_deps({
+        let results = results.clone();
+        move |(query, tag, is_loaded): &(String, TagOption, bool)| {
+            // Replies can arrive out of order, so only the search for the latest query and tag may set the results
+            let is_current = Rc::new(Cell::new(true));
+
+            if *is_loaded {
+                let is_current = is_current.clone();
+                search_library_with_callback(query.clone(), tag.tag(), move |value: Vec<LibrarySearchResult>| {
+                    if is_current.get() {
+                        results.set(value);
+                    }
+                });
+            }
+
+            move || is_current.set(false)
+        }
+    }, (query.deref().clone(), tag.deref().clone(), *is_loaded));
+
+    let update_query = {
+        let query = query.clone();
+        Callback::from(move |e: InputEvent| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            query.set(target.value());
+        })
+    };
+
+    let refresh = {
+        let is_loaded = is_loaded.clone();
+        let refresh_state = refresh_state.clone();
+        Callback::from(move |_: MouseEvent| {
+            is_loaded.set(false);
+            refresh_state.set(!*refresh_state);
+        })
+    };
+
+    let rows = results.iter()
+        .map(|result| {
+            let open = {
+                let tables = tables.clone();
+                let on_close = on_close.clone();
+                let path = result.path().to_path_buf();
+
+                Callback::from(move |_: MouseEvent| {
+                    let tables = tables.clone();
+                    let on_close = on_close.clone();
+                    open_table_with_callback(path.clone(), move |_| {
+                        tables.update();
+                        on_close.emit(());
+                    });
+                })
+            };
+
+            html! {
+                <tr>
+                    <td>
+                        <p>{result.name()}</p>
+                        if !result.tags().is_empty() {
+                            <p>{result.tags().join(", ")}</p>
+                        }
+                        if !result.matching_entries().is_empty() {
+                            <p>{format!("Matches: {}", result.matching_entries().join(", "))}</p>
+                        }
+                    </td>
+                    <td>{result.entry_count()}</td>
+                    <td>{result.modified().format("%Y-%m-%d %H:%M").to_string()}</td>
+                    <td><button onclick={open}>{"Open"}</button></td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+    let close = Callback::from(move |_: MouseEvent| {
+        on_close.emit(());
+    });
+
+    html! {
+        <FullPageModal>
+            <h3 class="heading">{"Table library"}</h3>
+            <p>{"Search the tables in your library folder by table name or entry name. The library folder can be changed in the settings."}</p>
+            <table class="stretch-width settings">
+                <tr>
+                    <td><p>{"Search:"}</p></td>
+                    <td><input class="flex-grow-1" value={query.deref().clone()} oninput={update_query} /></td>
+                </tr>
+                <tr>
+                    <td><p>{"Tag:"}</p></td>
+                    <td><SelectDirect<TagOption> items={tags.deref().clone()} selected_item={tag} /></td>
+                </tr>
+            </table>
+            if !*has_library {
+                <p>{"Choose a library folder in the settings first."}</p>
+            } else if !*is_loaded {
+                <p>{"Loading..."}</p>
+            } else if results.is_empty() {
+                <p>{"No tables found."}</p>
+            } else {
+                <table class="stretch-width">
+                    <thead>
+                        <tr>
+                            <th>{"Table"}</th>
+                            <th>{"Entries"}</th>
+                            <th>{"Modified"}</th>
+                            <th></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {rows}
+                    </tbody>
+                </table>
+            }
+            <div class="flex-row button-row">
+                <button class="flex-grow-1" onclick={refresh}>{"Refresh"}</button>
+                <button class="flex-grow-1" onclick={close}>{"Close"}</button>
+            </div>
+        </FullPageModal>
+    }
+}
\ No newline at end of file
@@ -0,0 +1,232 @@
+use std::ops::Deref;
+
+use common_data::{TableEntry, Currency, TableData, formats::{FormatError, paste::parse_pasted_table, share_code::{is_share_code, decode_share_code}}};
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+use crate::{components::{full_page_modal::FullPageModal, number_field::NumberField, checkbox::Checkbox, remove_button::RemoveButton}, hooks::prelude::*, glue::*};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct PasteModalProps {
+    pub tables: UseTablesHandle,
+    #[prop_or_default]
+    pub on_close: Callback<()>
+}
+
+#[function_component(PasteModal)]
+pub fn paste_modal(props: &PasteModalProps) -> Html {
+    let PasteModalProps { tables, on_close } = props.clone();
+    let text = use_state_eq(String::new);
+    let error = use_state_eq(|| None::<String>);
+    let is_reviewing = use_state_eq(|| false);
+    let table_name = use_state_eq(String::new);
+    let use_weight = use_state_eq(|| false);
+    let use_cost = use_state_eq(|| false);
+    let tags = use_state_eq(Vec::<String>::new);
+    let entries = use_vec_state(Vec::<TableEntry>::new);
+    let disable_add_button = table_name.trim().is_empty() || entries.is_empty() || entries.iter().any(|e| e.name().trim().is_empty());
+
+    let update_text = {
+        let text = text.clone();
+        Callback::from(move |e: Event| {
+            let target: HtmlTextAreaElement = e.target_unchecked_into();
+            text.set(target.value());
+        })
+    };
+
+    let parse = {
+        let text = text.clone();
+        let error = error.clone();
+        let is_reviewing = is_reviewing.clone();
+        let table_name = table_name.clone();
+        let use_weight = use_weight.clone();
+        let use_cost = use_cost.clone();
+        let tags = tags.clone();
+        let entries = entries.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            match parse_table(&text) {
+                Ok(table) => {
+                    table_name.set(table.name().to_string());
+                    use_weight.set(table.use_weight());
+                    use_cost.set(table.use_cost());
+                    tags.set(table.tags().clone());
+                    entries.set(table.entries().clone());
+                    error.set(None);
+                    is_reviewing.set(true);
+                },
+                Err(e) => error.set(Some(e.to_string()))
+            }
+        })
+    };
+
+    let back = {
+        let is_reviewing = is_reviewing.clone();
+        Callback::from(move |_: MouseEvent| {
+            is_reviewing.set(false);
+        })
+    };
+
+    let update_name = {
+        let table_name = table_name.clone();
+        Callback::from(move |e: Event| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            table_name.set(target.value().trim().to_string());
+        })
+    };
+
+    let update_use_weight = {
+        let use_weight = use_weight.clone();
+        Callback::from(move |checked: bool| {
+            use_weight.set(checked);
+        })
+    };
+
+    let insert_new = {
+        let entries = entries.clone();
+        Callback::from(move |_: MouseEvent| {
+            entries.insert(TableEntry::new(Currency::Copper(1)));
+        })
+    };
+
+    let add_table = {
+        let tables = tables.clone();
+        let table_name = table_name.clone();
+        let use_weight = use_weight.clone();
+        let use_cost = use_cost.clone();
+        let tags = tags.clone();
+        let entries = entries.clone();
+        let on_close = on_close.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let tables = tables.clone();
+            let tags = tags.deref().clone();
+            let on_close = on_close.clone();
+
+            new_table_with_callback(*use_cost, *use_weight, table_name.deref().clone(), entries.deref().clone(), move |id| {
+                let tables = tables.clone();
+                let on_close = on_close.clone();
+
+                if tags.is_empty() {
+                    tables.update();
+                    on_close.emit(());
+                } else {
+                    update_table_with_callback(id, None, None, None, None, Some(tags.clone()), move |_| {
+                        tables.update();
+                        on_close.emit(());
+                    });
+                }
+            });
+        })
+    };
+
+    let cancel = Callback::from(move |_: MouseEvent| {
+        on_close.emit(());
+    });
+
+    if !*is_reviewing {
+        return html! {
+            <FullPageModal>
+                <h3 class="heading">{"Paste table"}</h3>
+                <p>{"Paste a table copied from a book, PDF or wiki. Ranged rows such as '01–05 Goblin', numbered rows, bulleted lists and markdown tables are recognised, as are share codes copied from the export dialog."}</p>
+                <textarea value={text.deref().clone()} onchange={update_text} />
+                if let Some(error) = error.deref() {
+                    <p>{error}</p>
+                }
+                <div class="flex-row button-row">
+                    <button class="flex-grow-1" onclick={parse} disabled={text.trim().is_empty()}>{"Continue"}</button>
+                    <button class="flex-grow-1" onclick={cancel}>{"Cancel"}</button>
+                </div>
+            </FullPageModal>
+        };
+    }
+
+    let entry_items = entries.iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let update_entry = {
+                let entries = entries.clone();
+                Callback::from(move |e: Event| {
+                    let target: HtmlInputElement = e.target_unchecked_into();
+                    let name = target.value();
+                    entries.update_single(index, move |old| {
+                        let mut new = old.clone();
+                        new.set_name(name.trim());
+                        new
+                    });
+                })
+            };
+
+            let set_weight = {
+                let entries = entries.clone();
+                Callback::from(move |weight: usize| {
+                    entries.update_single(index, move |old| {
+                        let mut new = old.clone();
+                        new.set_weight(weight);
+                        new
+                    });
+                })
+            };
+
+            let remove_entry = {
+                let entries = entries.clone();
+                Callback::from(move |_: MouseEvent| {
+                    entries.remove(index);
+                })
+            };
+
+            let validate_weight = Callback::from(move |weight: usize| {
+                weight.clamp(1, 1000)
+            });
+
+            html! {
+                <div class="flex-row">
+                    <input class="flex-grow-1" value={entry.name().to_string()} onchange={update_entry} />
+                    if *use_weight {
+                        <NumberField<usize> title="Weight" class="number" value={entry.weight()} validate={validate_weight} on_change={set_weight} />
+                    }
+                    <RemoveButton on_click={remove_entry} />
+                </div>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <FullPageModal>
+            <h3 class="heading">{"Review pasted table"}</h3>
+            <table class="stretch-width settings">
+                <tr>
+                    <td><p>{"Table Name:"}</p></td>
+                    <td><input class="flex-grow-1" value={table_name.deref().clone()} onchange={update_name} /></td>
+                </tr>
+                <tr>
+                    <td><p class="flex-grow-1">{"Use weights:"}</p></td>
+                    <td><Checkbox class="stretch-height flex-row center-cross-axis end-main-axis" checked={*use_weight} on_change={update_use_weight} /></td>
+                </tr>
+            </table>
+            <p class="vert-margin">{"Check the rows below and fix anything that was not recognised correctly before creating the table."}</p>
+            <div class="flex-column flex-grow-1 table-style vert-margin">
+                <h2>{"Table entries"}</h2>
+                <div class="flex-column content">
+                {entry_items}
+                </div>
+            </div>
+            <div class="flex-row button-row">
+                <button class="flex-grow-1" onclick={insert_new}>{"+"}</button>
+            </div>
+            <div class="flex-row button-row">
+                <button class="flex-grow-1" onclick={add_table} disabled={disable_add_button}>{"Add Table"}</button>
+                <button class="flex-grow-1" onclick={back}>{"Back"}</button>
+                <button class="flex-grow-1" onclick={cancel}>{"Cancel"}</button>
+            </div>
+        </FullPageModal>
+    }
+}
+
+fn parse_table(text: &str) -> Result<TableData, FormatError> {
+    if is_share_code(text) {
+        decode_share_code(text).map(|table| table.into_table_data(0, None))
+    } else {
+        parse_pasted_table(text)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,70 @@
+use std::ops::Deref;
+
+use common_data::IdNamePair;
+use yew::prelude::*;
+
+use crate::{components::full_page_modal::FullPageModal, hooks::prelude::*, glue::*};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct RecoveryModalProps {
+    pub tables: UseTablesHandle
+}
+
+#[function_component(RecoveryModal)]
+pub fn recovery_modal(props: &RecoveryModalProps) -> Html {
+    let RecoveryModalProps { tables } = props.clone();
+    let recovered = use_state_eq(Vec::<IdNamePair>::new);
+
+    use_effect_with_deps({
+        let recovered = recovered.clone();
+        move |_| {
+            get_recovered_tables_with_callback(move |value: Vec<IdNamePair>| {
+                recovered.set(value);
+            });
+        }
+    }, ());
+
+    let restore = {
+        let recovered = recovered.clone();
+        Callback::from(move |_: MouseEvent| {
+            let recovered = recovered.clone();
+            let tables = tables.clone();
+            restore_recovered_tables_with_callback(move |_| {
+                recovered.set(Vec::new());
+                tables.update();
+            });
+        })
+    };
+
+    let discard = {
+        let recovered = recovered.clone();
+        Callback::from(move |_: MouseEvent| {
+            let recovered = recovered.clone();
+            discard_recovered_tables_with_callback(move |_| {
+                recovered.set(Vec::new());
+            });
+        })
+    };
+
+    if recovered.is_empty() {
+        return Html::default();
+    }
+
+    let names = recovered.iter()
+        .map(|table| html! { <li>{table.name()}</li> })
+        .collect::<Html>();
+
+    html! {
+        <FullPageModal>
+            <h3 class="heading">{"Recover tables"}</h3>
+            <p>{format!("{} tables had unsaved changes when the app last closed. Do you want to restore them?", recovered.deref().len())}</p>
+            <ul>
+                {names}
+            </ul>
+            <div class="flex-row button-row">
+                <button class="flex-grow-1" onclick={restore}>{"Restore"}</button>
+                <button class="flex-grow-1" onclick={discard}>{"Discard"}</button>
+            </div>
+        </FullPageModal>
+    }
+}
\ No newline at end of file
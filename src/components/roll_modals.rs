@@ -1,9 +1,9 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
-use common_data::{RollResult, Currency, RollType, RollLimit, TableData};
+use common_data::{RollResult, Currency, RollType, RollLimit, TableData, split::{split_loot, LootShare}, formats::{ExportFormat, markup::Markup}};
 use yew::prelude::*;
 
-use crate::{components::{modal::Modal, currency_field::CurrencyFieldDirect, full_page_modal::FullPageModal, number_field::NumberField, checkbox::Checkbox}, glue::get_random_set_with_callback, hooks::prelude::use_currency_state_eq};
+use crate::{components::{modal::Modal, currency_field::CurrencyFieldDirect, full_page_modal::FullPageModal, number_field::NumberField, checkbox::Checkbox, select::SelectDirect}, glue::{get_random_set_with_callback, add_results_to_ledger_with_callback, write_clipboard_text_with_callback, get_save_export_path_with_callback, export_roll_results_with_callback}, hooks::prelude::use_currency_state_eq};
 
 fn get_roll_type_html(roll_type: RollType, on_select: Callback<RollType>) -> Html {
     let select = {
@@ -224,12 +224,79 @@ pub fn roll_by_cost_modal(props: &RollByCostModalProps) -> Html {
 pub struct RollResultsModalProps {
     pub show_cost: bool,
     pub results: Arc<Vec<RollResult>>,
+    #[prop_or_default]
+    pub title: AttrValue,
+    #[prop_or_default]
+    pub ledger_category: AttrValue,
     pub on_close: Callback<MouseEvent>
 }
 
 #[function_component(RollResultsModal)]
 pub fn roll_results_modal(props: &RollResultsModalProps) -> Html {
-    let RollResultsModalProps { show_cost, results, on_close } = props.clone();
+    let RollResultsModalProps { show_cost, results, title, ledger_category, on_close } = props.clone();
+    let added_to_ledger = use_state_eq(|| false);
+    let members = use_state_eq(|| 1_usize);
+    let markup = use_state_eq(|| Markup::Markdown);
+    let export_status = use_state_eq(String::new);
+
+    let update_members = {
+        let members = members.clone();
+        Callback::from(move |value: usize| {
+            members.set(value);
+        })
+    };
+
+    let validate_members = Callback::from(move |value: usize| value.clamp(1, 20));
+
+    let add_to_ledger = {
+        let results = results.clone();
+        let added_to_ledger = added_to_ledger.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let added_to_ledger = added_to_ledger.clone();
+            add_results_to_ledger_with_callback((*results).clone(), ledger_category.to_string(), move |_| {
+                added_to_ledger.set(true);
+            });
+        })
+    };
+
+    let copy_results = {
+        let results = results.clone();
+        let title = title.clone();
+        let markup = markup.clone();
+        let export_status = export_status.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let export_status = export_status.clone();
+            write_clipboard_text_with_callback(markup.render_results(&title, &results, show_cost), move |_| {
+                export_status.set("Copied to clipboard".to_string());
+            });
+        })
+    };
+
+    let save_results = {
+        let results = results.clone();
+        let title = title.clone();
+        let markup = markup.clone();
+        let export_status = export_status.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let results = results.clone();
+            let title = title.clone();
+            let markup = *markup;
+            let export_status = export_status.clone();
+
+            get_save_export_path_with_callback(ExportFormat::from(markup), move |path: Option<PathBuf>| {
+                let export_status = export_status.clone();
+
+                if let Some(path) = path {
+                    export_roll_results_with_callback(title.to_string(), (*results).clone(), markup, show_cost, path.clone(), move |_| {
+                        export_status.set(format!("Saved to {}", path.display()));
+                    });
+                }
+            });
+        })
+    };
 
     let result_rows = results.iter()
         .map(|result| {
@@ -250,6 +317,16 @@ pub fn roll_results_modal(props: &RollResultsModalProps) -> Html {
         })
         .collect::<Html>();
 
+    let shares = if *members > 1 {
+        split_loot(&results, *members)
+            .iter()
+            .enumerate()
+            .map(|(index, share)| loot_share_html(index, share))
+            .collect::<Html>()
+    } else {
+        Html::default()
+    };
+
     html! {
         <FullPageModal>
             <h2 class="heading">{"Results"}</h2>
@@ -268,13 +345,69 @@ pub fn roll_results_modal(props: &RollResultsModalProps) -> Html {
                     {result_rows}
                 </tbody>
             </table>
+            <h3>{"Split"}</h3>
+            <table class="stretch-width blank left-align">
+                <tr>
+                    <td>{"Party members:"}</td>
+                    <td><NumberField<usize> class="number" get_default={|_: ()| 1_usize} value={*members} validate={validate_members} on_change={update_members} /></td>
+                </tr>
+            </table>
+            {shares}
+            <h3>{"Export"}</h3>
             <div class="flex-row button-row">
+                <SelectDirect<Markup> items={Arc::new(Markup::get_values())} selected_item={markup} />
+                <button class="flex-grow-1" onclick={copy_results}>{"Copy"}</button>
+                <button class="flex-grow-1" onclick={save_results}>{"Save to file"}</button>
+            </div>
+            if !export_status.is_empty() {
+                <p>{export_status.to_string()}</p>
+            }
+            <div class="flex-row button-row">
+                <button class="flex-grow-1" onclick={add_to_ledger} disabled={*added_to_ledger}>{"Add to ledger"}</button>
                 <button class="flex-grow-1" onclick={on_close}>{"Ok"}</button>
             </div>
         </FullPageModal>
     }
 }
 
+fn loot_share_html(index: usize, share: &LootShare) -> Html {
+    let items = share.items()
+        .iter()
+        .map(|result| html! {
+            <tr>
+                <td>{format!("{}x", result.count())}</td>
+                <td>{result.entry().name()}</td>
+            </tr>
+        })
+        .collect::<Html>();
+
+    let change = share.change()
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let settlement = if share.settlement().is_negative() {
+        format!("Pays {} into the pot", change)
+    } else if change.is_empty() {
+        "No change needed".to_string()
+    } else {
+        format!("Receives {} from the pot", change)
+    };
+
+    html! {
+        <>
+            <h4>{format!("Member {} ({})", index + 1, share.total_value())}</h4>
+            <table class="stretch-width">
+                <tbody>
+                    {items}
+                </tbody>
+            </table>
+            <p>{settlement}</p>
+        </>
+    }
+}
+
 fn clamp_count(value: usize, min: usize, max: Option<usize>) -> usize {
     match max {
         Some(max) => value.clamp(min, max),
@@ -0,0 +1,193 @@
+use std::{ops::Deref, path::PathBuf};
+
+use common_data::settings::{Settings, MAX_BACKUP_COUNT, DEFAULT_BACKUP_COUNT, DEFAULT_API_PORT};
+use uuid::Uuid;
+use yew::prelude::*;
+
+use crate::{components::{full_page_modal::FullPageModal, number_field::NumberField, checkbox::Checkbox}, glue::*, Error};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct SettingsModalProps {
+    #[prop_or_default]
+    pub on_close: Callback<()>
+}
+
+#[function_component(SettingsModal)]
+pub fn settings_modal(props: &SettingsModalProps) -> Html {
+    let SettingsModalProps { on_close } = props.clone();
+    let settings = use_state_eq(|| None::<Settings>);
+    let error = use_state_eq(|| None::<String>);
+
+    use_effect_with_deps({
+        let settings = settings.clone();
+        move |_| {
+            get_settings_with_callback(move |value: Settings| {
+                settings.set(Some(value));
+            });
+        }
+    }, ());
+
+    let update_backup_count = {
+        let settings = settings.clone();
+        Callback::from(move |value: usize| {
+            if let Some(current) = settings.deref() {
+                let mut updated = current.clone();
+                updated.set_backup_count(value);
+                settings.set(Some(updated));
+            }
+        })
+    };
+
+    let choose_library_dir = {
+        let settings = settings.clone();
+        Callback::from(move |_: MouseEvent| {
+            let settings = settings.clone();
+            get_library_dir_path_with_callback(move |path: Option<PathBuf>| {
+                if let (Some(path), Some(current)) = (path, settings.deref()) {
+                    let mut updated = current.clone();
+                    updated.set_library_dir(Some(path));
+                    settings.set(Some(updated));
+                }
+            });
+        })
+    };
+
+    let update_restore_session = {
+        let settings = settings.clone();
+        Callback::from(move |checked: bool| {
+            if let Some(current) = settings.deref() {
+                let mut updated = current.clone();
+                updated.set_restore_session(checked);
+                settings.set(Some(updated));
+            }
+        })
+    };
+
+    let update_api_enabled = {
+        let settings = settings.clone();
+        Callback::from(move |checked: bool| {
+            if let Some(current) = settings.deref() {
+                let mut updated = current.clone();
+                updated.set_api_enabled(checked);
+                if checked && updated.api_token().is_empty() {
+                    updated.set_api_token(Uuid::new_v4().simple().to_string());
+                }
+                settings.set(Some(updated));
+            }
+        })
+    };
+
+    let update_api_port = {
+        let settings = settings.clone();
+        Callback::from(move |value: u16| {
+            if let Some(current) = settings.deref() {
+                let mut updated = current.clone();
+                updated.set_api_port(value);
+                settings.set(Some(updated));
+            }
+        })
+    };
+
+    let regenerate_api_token = {
+        let settings = settings.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Some(current) = settings.deref() {
+                let mut updated = current.clone();
+                updated.set_api_token(Uuid::new_v4().simple().to_string());
+                settings.set(Some(updated));
+            }
+        })
+    };
+
+    let copy_api_token = {
+        let settings = settings.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Some(current) = settings.deref() {
+                write_clipboard_text_with_callback(current.api_token(), |_| {});
+            }
+        })
+    };
+
+    let validate_backup_count = Callback::from(move |value: usize| value.min(MAX_BACKUP_COUNT));
+    let validate_api_port = Callback::from(move |value: u16| value.max(1024));
+
+    let save = {
+        let settings = settings.clone();
+        let error = error.clone();
+        let on_close = on_close.clone();
+        Callback::from(move |_: MouseEvent| {
+            let on_close = on_close.clone();
+            let error = error.clone();
+            if let Some(settings) = settings.deref() {
+                update_settings_with_error_callback(settings.clone(), move |_| {
+                    on_close.emit(());
+                }, move |e: Error| {
+                    error.set(Some(e.user_message()));
+                });
+            }
+        })
+    };
+
+    let cancel = Callback::from(move |_: MouseEvent| {
+        on_close.emit(());
+    });
+
+    let content = match settings.deref() {
+        None => html! { <p>{"Loading..."}</p> },
+        Some(settings) => html! {
+            <table class="stretch-width settings">
+                <tr>
+                    <td><p>{"Backups per table:"}</p></td>
+                    <td><NumberField<usize> class="number" get_default={|_: ()| DEFAULT_BACKUP_COUNT} value={settings.backup_count()} validate={validate_backup_count} on_change={update_backup_count} /></td>
+                </tr>
+                <tr>
+                    <td><p>{"Reopen tables from last session:"}</p></td>
+                    <td><Checkbox class="stretch-height flex-row center-cross-axis end-main-axis" checked={settings.restore_session()} on_change={update_restore_session} /></td>
+                </tr>
+                <tr>
+                    <td><p>{"Library folder:"}</p></td>
+                    <td class="flex-row">
+                        <p class="flex-grow-1">{settings.library_dir().map(|p| p.to_string_lossy().to_string()).unwrap_or("(none)".to_string())}</p>
+                        <button onclick={choose_library_dir}>{"Choose"}</button>
+                    </td>
+                </tr>
+                <tr>
+                    <td><p>{"Enable local API:"}</p></td>
+                    <td><Checkbox class="stretch-height flex-row center-cross-axis end-main-axis" checked={settings.api_enabled()} on_change={update_api_enabled} /></td>
+                </tr>
+                if settings.api_enabled() {
+                    <tr>
+                        <td><p>{"API port:"}</p></td>
+                        <td><NumberField<u16> class="number" get_default={|_: ()| DEFAULT_API_PORT} value={settings.api_port()} validate={validate_api_port} on_change={update_api_port} /></td>
+                    </tr>
+                    <tr>
+                        <td><p>{"API token:"}</p></td>
+                        <td class="flex-row">
+                            <p class="flex-grow-1">{settings.api_token().to_string()}</p>
+                            <button onclick={copy_api_token}>{"Copy"}</button>
+                            <button onclick={regenerate_api_token}>{"New token"}</button>
+                        </td>
+                    </tr>
+                    <tr>
+                        <td colspan="2"><p>{format!("Commands are available at http://127.0.0.1:{}/api/<command> and roll events at ws://127.0.0.1:{}/api/events. Send the token in an Authorization: Bearer header. Browsers connecting to the events socket can offer the token as the WebSocket subprotocol instead.", settings.api_port(), settings.api_port())}</p></td>
+                    </tr>
+                }
+            </table>
+        }
+    };
+
+    html! {
+        <FullPageModal>
+            <h3 class="heading">{"Settings"}</h3>
+            <p>{"Each time a table is saved, the previous version is kept next to it as a .bak file. Set the number of backups to 0 to stop keeping them."}</p>
+            {content}
+            if let Some(error) = error.deref() {
+                <p>{error}</p>
+            }
+            <div class="flex-row button-row">
+                <button class="flex-grow-1" onclick={save} disabled={settings.is_none()}>{"Save"}</button>
+                <button class="flex-grow-1" onclick={cancel}>{"Cancel"}</button>
+            </div>
+        </FullPageModal>
+    }
+}
\ No newline at end of file
@@ -0,0 +1,97 @@
+use std::{cell::Cell, ops::Deref, rc::Rc};
+
+use common_data::search::EntrySearchResult;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{hooks::prelude::*, glue::*};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct TableSearchProps {
+    pub tables: UseTablesHandle
+}
+
+#[function_component(TableSearch)]
+pub fn table_search(props: &TableSearchProps) -> Html {
+    let TableSearchProps { tables } = props.clone();
+    let query = use_state_eq(String::new);
+    let results = use_state_eq(Vec::<EntrySearchResult>::new);
+
+    use_effect_with_deps({
+        let results = results.clone();
+        move |query: &String| {
+            // Replies can arrive out of order, so only the search for the latest query may set the results
+            let is_current = Rc::new(Cell::new(true));
+
+            if query.trim().is_empty() {
+                results.set(Vec::new());
+            } else {
+                let is_current = is_current.clone();
+                search_entries_with_callback(query.clone(), move |value: Vec<EntrySearchResult>| {
+                    if is_current.get() {
+                        results.set(value);
+                    }
+                });
+            }
+
+            move || is_current.set(false)
+        }
+    }, query.deref().clone());
+
+    let update_query = {
+        let query = query.clone();
+        Callback::from(move |e: InputEvent| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            query.set(target.value());
+        })
+    };
+
+    let clear_on_escape = {
+        let query = query.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            if e.key() == "Escape" {
+                query.set(String::new());
+            }
+        })
+    };
+
+    let items = results.iter()
+        .map(|result| {
+            let select = {
+                let tables = tables.clone();
+                let query = query.clone();
+                let table_id = result.table_id();
+                let entry_id = result.entry_id();
+
+                Callback::from(move |_: MouseEvent| {
+                    tables.select_entry(table_id, entry_id);
+                    query.set(String::new());
+                })
+            };
+
+            html! {
+                <button class="search-result" onclick={select}>
+                    <p>{format!("{} ({})", result.entry_name(), result.table_name())}</p>
+                    if result.field().is_some() {
+                        <p class="search-snippet">{result.snippet()}</p>
+                    }
+                </button>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="table-search flex-column">
+            <input placeholder="Search all open tables..." value={query.deref().clone()} oninput={update_query} onkeydown={clear_on_escape} />
+            if !query.trim().is_empty() {
+                <div class="search-results flex-column scroll">
+                    if results.is_empty() {
+                        <p>{"No matching entries."}</p>
+                    } else {
+                        {items}
+                    }
+                </div>
+            }
+        </div>
+    }
+}
@@ -1,18 +1,21 @@
 use std::sync::Arc;
 
-use common_data::{TableData, RollResult, TableEntry, RollType};
+use common_data::{TableData, RollResult, TableEntry, RollType, history::HistoryState};
+use web_sys::Element;
 use yew::prelude::*;
 
-use crate::{hooks::prelude::UseTablesHandle, glue::*, components::{remove_button::RemoveButton, roll_modals::{RollTypeSelectionModal, RollByCountModal, RollByCostModal, RollResultsModal}, edit_table_modal::EditTableModal}};
+use crate::{hooks::prelude::UseTablesHandle, glue::*, components::{remove_button::RemoveButton, roll_modals::{RollTypeSelectionModal, RollByCountModal, RollByCostModal, RollResultsModal}, edit_table_modal::EditTableModal, error_banner::ErrorNotice}};
 
 #[derive(Debug, Clone, PartialEq, Properties)]
 pub struct TableTabsProps {
-    pub tables: UseTablesHandle
+    pub tables: UseTablesHandle,
+    pub notice: UseStateHandle<Option<ErrorNotice>>
 }
 
 #[function_component(TableTabs)]
 pub fn table_tabs(props: &TableTabsProps) -> Html {
-    let TableTabsProps { tables } = props.clone();
+    let TableTabsProps { tables, notice } = props.clone();
+    let dragged = use_state_eq(|| None::<usize>);
 
     let items = tables.tables()
         .iter()
@@ -27,13 +30,64 @@ pub fn table_tabs(props: &TableTabsProps) -> Html {
 
             let remove_table = {
                 let tables = tables.clone();
-                let id = tables.tables()[idx].id();
+                let id = table.id();
+                let name = table.name().to_string();
+                let is_modified = table.is_modified();
 
                 Callback::from(move |_: MouseEvent| {
                     let tables = tables.clone();
-                    remove_table_with_callback(id, move |_| {
-                        tables.update()
-                    });
+
+                    if is_modified {
+                        ask_user_with_callback(format!("'{}' has unsaved changes. Close it anyway?", name), "Unsaved changes", move |confirmed: bool| {
+                            if confirmed {
+                                let tables = tables.clone();
+                                remove_table_with_callback(id, move |_| {
+                                    tables.update()
+                                });
+                            }
+                        });
+                    } else {
+                        remove_table_with_callback(id, move |_| {
+                            tables.update()
+                        });
+                    }
+                })
+            };
+
+            let on_drag_start = {
+                let dragged = dragged.clone();
+                Callback::from(move |_: DragEvent| {
+                    dragged.set(Some(idx));
+                })
+            };
+
+            let on_drag_over = Callback::from(|e: DragEvent| {
+                e.prevent_default();
+            });
+
+            let on_drop = {
+                let tables = tables.clone();
+                let dragged = dragged.clone();
+                Callback::from(move |e: DragEvent| {
+                    e.prevent_default();
+
+                    if let Some(from) = (*dragged).filter(|from| *from != idx) {
+                        if let Some(summary) = tables.tables().get(from) {
+                            let tables = tables.clone();
+                            move_table_with_callback(summary.id(), idx, move |_| {
+                                tables.update()
+                            });
+                        }
+                    }
+
+                    dragged.set(None);
+                })
+            };
+
+            let on_drag_end = {
+                let dragged = dragged.clone();
+                Callback::from(move |_: DragEvent| {
+                    dragged.set(None);
                 })
             };
 
@@ -42,9 +96,11 @@ pub fn table_tabs(props: &TableTabsProps) -> Html {
                 _ => classes!("tab-button")
             };
 
+            let drag_class = (*dragged == Some(idx)).then_some("tab-dragging");
+
             html! {
-                <div class={classes!(class, "flex-row")}>
-                    <button onclick={set_index}>{table.name()}</button>
+                <div key={table.id().to_string()} class={classes!(class, drag_class, "flex-row")} draggable="true" ondragstart={on_drag_start} ondragover={on_drag_over} ondrop={on_drop} ondragend={on_drag_end}>
+                    <button onclick={set_index}>{if table.is_modified() { format!("{} *", table.name()) } else { table.name().to_string() }}</button>
                     <RemoveButton on_click={remove_table} />
                 </div>
             }
@@ -57,17 +113,17 @@ pub fn table_tabs(props: &TableTabsProps) -> Html {
                 {items}
             </div>
             <div class="flex-grow-1 flex-column scroll tab-content">
-                {get_table_content(tables)}
+                {get_table_content(tables, notice)}
             </div>
         </div>
     }
 }
 
-fn get_table_content(tables: UseTablesHandle) -> Html {
+fn get_table_content(tables: UseTablesHandle, notice: UseStateHandle<Option<ErrorNotice>>) -> Html {
     match tables.get_table_data() {
         None => render_welcome_content(),
         Some(table) => html! {
-            <TabContent tables={tables} table={table} />
+            <TabContent tables={tables} table={table} notice={notice} />
         }
     }
 }
@@ -82,12 +138,21 @@ fn render_welcome_content() -> Html {
     }
 }
 
-fn entry_row(index: usize, entry: &TableEntry, table: Arc<TableData>) -> Html {
+fn entry_row(index: usize, entry: &TableEntry, table: Arc<TableData>, highlighted: Option<&NodeRef>) -> Html {
     let use_weight = table.use_weight();
     let use_cost = table.use_cost();
+    let fields = table.field_names()
+        .iter()
+        .map(|field| html! { <td><p class="flex-grow-1">{entry.field(field).unwrap_or("")}</p></td> })
+        .collect::<Html>();
     
+    let (class, node_ref) = match highlighted {
+        Some(node_ref) => (classes!("highlighted-row"), node_ref.clone()),
+        None => (classes!(), NodeRef::default())
+    };
+
     html! {
-        <tr>
+        <tr key={entry.id().to_string()} class={class} ref={node_ref}>
             <td>{index + 1}</td>
             <td><p class="flex-grow-1">{entry.name()}</p></td>
             if use_weight {
@@ -96,6 +161,7 @@ fn entry_row(index: usize, entry: &TableEntry, table: Arc<TableData>) -> Html {
             if use_cost {
                 <td><p class="flex-grow-1">{entry.cost().to_string()}</p></td>
             }
+            {fields}
         </tr>
     }
 }
@@ -104,13 +170,35 @@ fn entry_row(index: usize, entry: &TableEntry, table: Arc<TableData>) -> Html {
 struct TabContentProps {
     tables: UseTablesHandle,
     table: Arc<TableData>,
+    notice: UseStateHandle<Option<ErrorNotice>>
 }
 
 #[function_component(TabContent)]
 fn tab_content(props: &TabContentProps) -> Html {
-    let TabContentProps { tables, table } = props.clone();
+    let TabContentProps { tables, table, notice } = props.clone();
     let is_edit_modal_open = use_state_eq(|| false);
     let is_roll_modal_open = use_state_eq(|| false);
+    let highlighted_row = use_node_ref();
+    let highlighted_entry = tables.get_highlighted_entry();
+    let history = use_state_eq(HistoryState::default);
+
+    use_effect_with_deps({
+        let history = history.clone();
+        move |table: &Arc<TableData>| {
+            get_table_history_with_callback(table.id(), move |value: HistoryState| {
+                history.set(value);
+            });
+        }
+    }, table.clone());
+
+    use_effect_with_deps({
+        let highlighted_row = highlighted_row.clone();
+        move |_| {
+            if let Some(row) = highlighted_row.cast::<Element>() {
+                row.scroll_into_view();
+            }
+        }
+    }, (highlighted_entry, table.id()));
 
     let open_roll_modal = {
         let is_roll_modal_open = is_roll_modal_open.clone();
@@ -119,6 +207,21 @@ fn tab_content(props: &TabContentProps) -> Html {
         })
     };
 
+    let undo = {
+        let tables = tables.clone();
+        let notice = notice.clone();
+        Callback::from(move |_: MouseEvent| {
+            tables.undo(notice.clone());
+        })
+    };
+
+    let redo = {
+        let tables = tables.clone();
+        Callback::from(move |_: MouseEvent| {
+            tables.redo(notice.clone());
+        })
+    };
+
     let open_edit_modal = {
         let is_edit_modal_open = is_edit_modal_open.clone();
         Callback::from(move |_: MouseEvent| {
@@ -144,7 +247,7 @@ fn tab_content(props: &TabContentProps) -> Html {
 
     let entries = table.iter()
         .enumerate()
-        .map(|(index, entry)| entry_row(index, entry, table.clone()))
+        .map(|(index, entry)| entry_row(index, entry, table.clone(), (highlighted_entry == Some(entry.id())).then_some(&highlighted_row)))
         .collect::<Html>();
 
     html! {
@@ -168,6 +271,7 @@ fn tab_content(props: &TabContentProps) -> Html {
                             if table.use_cost() {
                                 <th>{"Cost"}</th>
                             }
+                            {table.field_names().iter().map(|field| html! { <th>{field}</th> }).collect::<Html>()}
                         </tr>
                     </thead>
                     <tbody>
@@ -176,6 +280,8 @@ fn tab_content(props: &TabContentProps) -> Html {
                 </table>
             </div>
             <div class="flex-row button-row">
+                <button class="flex-grow-1" onclick={undo} disabled={!history.can_undo()} title="Ctrl+Z">{"Undo"}</button>
+                <button class="flex-grow-1" onclick={redo} disabled={!history.can_redo()} title="Ctrl+Shift+Z">{"Redo"}</button>
                 <button class="flex-grow-1" onclick={open_edit_modal}>{"Edit table"}</button>
                 <button class="flex-grow-1" onclick={open_roll_modal}>{"Roll"}</button>
             </div>
@@ -234,6 +340,6 @@ fn random_roll_modal(props: &RandomRollModalProps) -> Html {
         RollModal::SelectMode => html! { <RollTypeSelectionModal on_select={select_roll_type} on_cancel={close_modal.clone()} /> },
         RollModal::RollByCount => html! { <RollByCountModal table={table.clone()} max_count={table.len()} on_complete={complete} on_cancel={close_modal} /> },
         RollModal::RollByCost => html! { <RollByCostModal table={table.clone()} max_cost={table.total_cost()} on_complete={complete} on_cancel={close_modal} /> },
-        RollModal::Results => html! { <RollResultsModal show_cost={use_cost} results={(*results).clone()} on_close={close_modal} /> },
+        RollModal::Results => html! { <RollResultsModal show_cost={use_cost} results={(*results).clone()} title={format!("{} results", table.name())} ledger_category={table.name().to_string()} on_close={close_modal} /> },
     }
 }
\ No newline at end of file
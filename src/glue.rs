@@ -1,18 +1,18 @@
 use std::path::PathBuf;
 
-use common_data::{IdNamePair, TableData, RollResult, TableEntry, RollLimit};
-use serde::Serialize;
+use common_data::{IdNamePair, TableSummary, ConflictResolution, TableData, RollResult, TableEntry, RollLimit, Balance, history::HistoryState, ledger::{Ledger, LedgerItem}, library::LibrarySearchResult, search::EntrySearchResult, settings::Settings, formats::{ImportFormat, ExportFormat, ImportedFile, markup::Markup}};
+use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 use wasm_bindgen::prelude::*;
-use yew::Callback;
+use yew::{Callback, events::KeyboardEvent};
 
-use crate::{Error, emit_callback_if_ok, MapErrAndLog};
+use crate::{Error, emit_callback_if_ok, emit_callback_or_error, MapErrAndLog};
 
-pub async fn get_tables() -> Result<Vec<IdNamePair>, Error> {
+pub async fn get_tables() -> Result<Vec<TableSummary>, Error> {
     from_result(invoke_no_args("get_tables").await)
 }
 
-pub fn get_tables_with_callback(callback: impl Into<Callback<Vec<IdNamePair>>>) {
+pub fn get_tables_with_callback(callback: impl Into<Callback<Vec<TableSummary>>>) {
     wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_tables(), callback.into()));
 }
 
@@ -61,16 +61,21 @@ struct UpdateTableArgs {
     use_cost: Option<bool>,
     #[serde(rename = "useWeight")]
     use_weight: Option<bool>,
-    entries: Option<Vec<TableEntry>>
+    entries: Option<Vec<TableEntry>>,
+    tags: Option<Vec<String>>
 }
 
-pub async fn update_table(id: Uuid, name: Option<String>, use_cost: Option<bool>, use_weight: Option<bool>, entries: Option<Vec<TableEntry>>) -> Result<(), Error> {
-    let args = serde_wasm_bindgen::to_value(&UpdateTableArgs { id, name, use_cost, use_weight, entries }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+pub async fn update_table(id: Uuid, name: Option<String>, use_cost: Option<bool>, use_weight: Option<bool>, entries: Option<Vec<TableEntry>>, tags: Option<Vec<String>>) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&UpdateTableArgs { id, name, use_cost, use_weight, entries, tags }).map_err_and_log(Error::SerdeWasmBindgenError)?;
     unit_from_result(invoke("update_table", args).await)
 }
 
-pub fn update_table_with_callback(id: Uuid, name: Option<String>, use_cost: Option<bool>, use_weight: Option<bool>, entries: Option<Vec<TableEntry>>, callback: impl Into<Callback<()>>) {
-    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(update_table(id, name, use_cost, use_weight, entries), callback.into()));
+pub fn update_table_with_callback(id: Uuid, name: Option<String>, use_cost: Option<bool>, use_weight: Option<bool>, entries: Option<Vec<TableEntry>>, tags: Option<Vec<String>>, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(update_table(id, name, use_cost, use_weight, entries, tags), callback.into()));
+}
+
+pub fn update_table_with_error_callback(id: Uuid, name: Option<String>, use_cost: Option<bool>, use_weight: Option<bool>, entries: Option<Vec<TableEntry>>, tags: Option<Vec<String>>, callback: impl Into<Callback<()>>, on_error: impl Into<Callback<Error>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_or_error(update_table(id, name, use_cost, use_weight, entries, tags), callback.into(), on_error.into()));
 }
 
 #[derive(Debug, Clone, Copy, Serialize)]
@@ -87,6 +92,35 @@ pub fn remove_table_with_callback(id: Uuid, callback: impl Into<Callback<TableDa
     wasm_bindgen_futures::spawn_local(emit_callback_if_ok(remove_table(id), callback.into()));
 }
 
+#[derive(Debug, Clone, Copy, Serialize)]
+struct MoveTableArgs {
+    id: Uuid,
+    index: usize
+}
+
+pub async fn move_table(id: Uuid, index: usize) -> Result<Vec<Uuid>, Error> {
+    let args = serde_wasm_bindgen::to_value(&MoveTableArgs { id, index }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    from_result(invoke("move_table", args).await)
+}
+
+pub fn move_table_with_callback(id: Uuid, index: usize, callback: impl Into<Callback<Vec<Uuid>>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(move_table(id, index), callback.into()));
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ReorderTablesArgs {
+    ids: Vec<Uuid>
+}
+
+pub async fn reorder_tables(ids: Vec<Uuid>) -> Result<Vec<Uuid>, Error> {
+    let args = serde_wasm_bindgen::to_value(&ReorderTablesArgs { ids }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    from_result(invoke("reorder_tables", args).await)
+}
+
+pub fn reorder_tables_with_callback(ids: Vec<Uuid>, callback: impl Into<Callback<Vec<Uuid>>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(reorder_tables(ids), callback.into()));
+}
+
 #[derive(Debug, Clone, Serialize)]
 struct ChangeTableNameArgs {
     id: Uuid,
@@ -117,19 +151,125 @@ pub fn add_entries_with_callback(id: Uuid, entries: Vec<TableEntry>, callback: i
     wasm_bindgen_futures::spawn_local(emit_callback_if_ok(add_entries(id, entries), callback.into()));
 }
 
+pub fn add_entries_with_error_callback(id: Uuid, entries: Vec<TableEntry>, callback: impl Into<Callback<()>>, on_error: impl Into<Callback<Error>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_or_error(add_entries(id, entries), callback.into(), on_error.into()));
+}
+
 #[derive(Debug, Clone, Serialize)]
-struct RemoveEntryArgs {
+struct EntryArgs {
     id: Uuid,
+    #[serde(rename = "entryId")]
+    entry_id: Uuid
+}
+
+pub async fn get_entry(id: Uuid, entry_id: Uuid) -> Result<TableEntry, Error> {
+    let args = serde_wasm_bindgen::to_value(&EntryArgs { id, entry_id }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    from_result(invoke("get_entry", args).await)
+}
+
+pub fn get_entry_with_callback(id: Uuid, entry_id: Uuid, callback: impl Into<Callback<TableEntry>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_entry(id, entry_id), callback.into()));
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct UpdateEntryArgs {
+    id: Uuid,
+    #[serde(rename = "entryId")]
+    entry_id: Uuid,
+    entry: TableEntry
+}
+
+pub async fn update_entry(id: Uuid, entry_id: Uuid, entry: TableEntry) -> Result<TableEntry, Error> {
+    let args = serde_wasm_bindgen::to_value(&UpdateEntryArgs { id, entry_id, entry }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    from_result(invoke("update_entry", args).await)
+}
+
+pub fn update_entry_with_callback(id: Uuid, entry_id: Uuid, entry: TableEntry, callback: impl Into<Callback<TableEntry>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(update_entry(id, entry_id, entry), callback.into()));
+}
+
+pub fn update_entry_with_error_callback(id: Uuid, entry_id: Uuid, entry: TableEntry, callback: impl Into<Callback<TableEntry>>, on_error: impl Into<Callback<Error>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_or_error(update_entry(id, entry_id, entry), callback.into(), on_error.into()));
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct MoveEntryArgs {
+    id: Uuid,
+    #[serde(rename = "entryId")]
+    entry_id: Uuid,
     index: usize
 }
 
-pub async fn remove_entry(id: Uuid, index: usize) -> Result<(), Error> {
-    let args = serde_wasm_bindgen::to_value(&RemoveEntryArgs { id, index }).map_err_and_log(Error::SerdeWasmBindgenError)?;
-    unit_from_result(invoke("remove_entry", args).await)
+pub async fn move_entry(id: Uuid, entry_id: Uuid, index: usize) -> Result<usize, Error> {
+    let args = serde_wasm_bindgen::to_value(&MoveEntryArgs { id, entry_id, index }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    from_result(invoke("move_entry", args).await)
+}
+
+pub fn move_entry_with_callback(id: Uuid, entry_id: Uuid, index: usize, callback: impl Into<Callback<usize>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(move_entry(id, entry_id, index), callback.into()));
+}
+
+pub fn move_entry_with_error_callback(id: Uuid, entry_id: Uuid, index: usize, callback: impl Into<Callback<usize>>, on_error: impl Into<Callback<Error>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_or_error(move_entry(id, entry_id, index), callback.into(), on_error.into()));
+}
+
+pub async fn remove_entry(id: Uuid, entry_id: Uuid) -> Result<TableEntry, Error> {
+    let args = serde_wasm_bindgen::to_value(&EntryArgs { id, entry_id }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    from_result(invoke("remove_entry", args).await)
+}
+
+pub fn remove_entry_with_callback(id: Uuid, entry_id: Uuid, callback: impl Into<Callback<TableEntry>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(remove_entry(id, entry_id), callback.into()));
+}
+
+pub fn remove_entry_with_error_callback(id: Uuid, entry_id: Uuid, callback: impl Into<Callback<TableEntry>>, on_error: impl Into<Callback<Error>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_or_error(remove_entry(id, entry_id), callback.into(), on_error.into()));
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+struct TableHistoryArgs {
+    id: Uuid
+}
+
+pub async fn undo_table_edit(id: Uuid) -> Result<HistoryState, Error> {
+    let args = serde_wasm_bindgen::to_value(&TableHistoryArgs { id }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    from_result(invoke("undo_table_edit", args).await)
+}
+
+pub fn undo_table_edit_with_error_callback(id: Uuid, callback: impl Into<Callback<HistoryState>>, on_error: impl Into<Callback<Error>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_or_error(undo_table_edit(id), callback.into(), on_error.into()));
+}
+
+pub async fn redo_table_edit(id: Uuid) -> Result<HistoryState, Error> {
+    let args = serde_wasm_bindgen::to_value(&TableHistoryArgs { id }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    from_result(invoke("redo_table_edit", args).await)
+}
+
+pub fn redo_table_edit_with_error_callback(id: Uuid, callback: impl Into<Callback<HistoryState>>, on_error: impl Into<Callback<Error>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_or_error(redo_table_edit(id), callback.into(), on_error.into()));
+}
+
+pub async fn get_table_history(id: Uuid) -> Result<HistoryState, Error> {
+    let args = serde_wasm_bindgen::to_value(&TableHistoryArgs { id }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    from_result(invoke("get_table_history", args).await)
 }
 
-pub fn remove_entry_with_callback(id: Uuid, index: usize, callback: impl Into<Callback<()>>) {
-    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(remove_entry(id, index), callback.into()));
+pub fn get_table_history_with_callback(id: Uuid, callback: impl Into<Callback<HistoryState>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_table_history(id), callback.into()));
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SearchEntriesArgs {
+    query: String
+}
+
+pub async fn search_entries(query: impl Into<String>) -> Result<Vec<EntrySearchResult>, Error> {
+    let args = serde_wasm_bindgen::to_value(&SearchEntriesArgs { query: query.into() }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    from_result(invoke("search_entries", args).await)
+}
+
+pub fn search_entries_with_callback(query: impl Into<String>, callback: impl Into<Callback<Vec<EntrySearchResult>>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(search_entries(query.into()), callback.into()));
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -179,6 +319,43 @@ pub fn open_table_with_callback(path: PathBuf, callback: impl Into<Callback<()>>
     wasm_bindgen_futures::spawn_local(emit_callback_if_ok(open_table(path), callback.into()));
 }
 
+pub fn open_table_with_error_callback(path: PathBuf, callback: impl Into<Callback<()>>, on_error: impl Into<Callback<Error>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_or_error(open_table(path), callback.into(), on_error.into()));
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ResolveTableConflictArgs {
+    id: Uuid,
+    resolution: ConflictResolution
+}
+
+pub async fn resolve_table_conflict(id: Uuid, resolution: ConflictResolution) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&ResolveTableConflictArgs { id, resolution }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    unit_from_result(invoke("resolve_table_conflict", args).await)
+}
+
+pub fn resolve_table_conflict_with_callback(id: Uuid, resolution: ConflictResolution, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(resolve_table_conflict(id, resolution), callback.into()));
+}
+
+pub async fn list_table_backups(path: PathBuf) -> Result<Vec<PathBuf>, Error> {
+    let args = serde_wasm_bindgen::to_value(&OpenTableArgs { path }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    from_result(invoke("list_table_backups", args).await)
+}
+
+pub fn list_table_backups_with_callback(path: PathBuf, callback: impl Into<Callback<Vec<PathBuf>>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(list_table_backups(path), callback.into()));
+}
+
+pub async fn open_table_backup(path: PathBuf) -> Result<PathBuf, Error> {
+    let args = serde_wasm_bindgen::to_value(&OpenTableArgs { path }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    from_result(invoke("open_table_backup", args).await)
+}
+
+pub fn open_table_backup_with_callback(path: PathBuf, callback: impl Into<Callback<PathBuf>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(open_table_backup(path), callback.into()));
+}
+
 #[derive(Debug, Clone, Serialize)]
 struct SaveTableArgs {
     id: Uuid,
@@ -194,6 +371,243 @@ pub fn save_table_with_callback(id: Uuid, path: PathBuf, callback: impl Into<Cal
     wasm_bindgen_futures::spawn_local(emit_callback_if_ok(save_table(id, path), callback.into()));
 }
 
+pub fn save_table_with_error_callback(id: Uuid, path: PathBuf, callback: impl Into<Callback<()>>, on_error: impl Into<Callback<Error>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_or_error(save_table(id, path), callback.into(), on_error.into()));
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SaveBundleArgs {
+    name: String,
+    path: PathBuf
+}
+
+pub async fn save_bundle(name: impl Into<String>, path: PathBuf) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&SaveBundleArgs { name: name.into(), path }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    unit_from_result(invoke("save_bundle", args).await)
+}
+
+pub fn save_bundle_with_callback(name: impl Into<String>, path: PathBuf, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(save_bundle(name.into(), path), callback.into()));
+}
+
+pub fn save_bundle_with_error_callback(name: impl Into<String>, path: PathBuf, callback: impl Into<Callback<()>>, on_error: impl Into<Callback<Error>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_or_error(save_bundle(name.into(), path), callback.into(), on_error.into()));
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct BundlePathArgs {
+    path: PathBuf
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct OpenBundleArgs {
+    path: PathBuf,
+    #[serde(rename = "discardUnsaved")]
+    discard_unsaved: bool,
+    #[serde(rename = "importLedger")]
+    import_ledger: bool
+}
+
+pub async fn open_bundle(path: PathBuf, discard_unsaved: bool, import_ledger: bool) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&OpenBundleArgs { path, discard_unsaved, import_ledger }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    unit_from_result(invoke("open_bundle", args).await)
+}
+
+pub fn open_bundle_with_callback(path: PathBuf, discard_unsaved: bool, import_ledger: bool, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(open_bundle(path, discard_unsaved, import_ledger), callback.into()));
+}
+
+pub fn open_bundle_with_error_callback(path: PathBuf, discard_unsaved: bool, import_ledger: bool, callback: impl Into<Callback<()>>, on_error: impl Into<Callback<Error>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_or_error(open_bundle(path, discard_unsaved, import_ledger), callback.into(), on_error.into()));
+}
+
+pub async fn bundle_has_ledger(path: PathBuf) -> Result<bool, Error> {
+    let args = serde_wasm_bindgen::to_value(&BundlePathArgs { path }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    from_result(invoke("bundle_has_ledger", args).await)
+}
+
+pub fn bundle_has_ledger_with_callback(path: PathBuf, callback: impl Into<Callback<bool>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(bundle_has_ledger(path), callback.into()));
+}
+
+pub async fn list_bundle_tables(path: PathBuf) -> Result<Vec<IdNamePair>, Error> {
+    let args = serde_wasm_bindgen::to_value(&BundlePathArgs { path }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    from_result(invoke("list_bundle_tables", args).await)
+}
+
+pub fn list_bundle_tables_with_callback(path: PathBuf, callback: impl Into<Callback<Vec<IdNamePair>>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(list_bundle_tables(path), callback.into()));
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ExtractBundleTableArgs {
+    path: PathBuf,
+    id: Uuid
+}
+
+pub async fn extract_bundle_table(path: PathBuf, id: Uuid) -> Result<Uuid, Error> {
+    let args = serde_wasm_bindgen::to_value(&ExtractBundleTableArgs { path, id }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    from_result(invoke("extract_bundle_table", args).await)
+}
+
+pub fn extract_bundle_table_with_callback(path: PathBuf, id: Uuid, callback: impl Into<Callback<Uuid>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(extract_bundle_table(path, id), callback.into()));
+}
+
+pub async fn get_recovered_tables() -> Result<Vec<IdNamePair>, Error> {
+    from_result(invoke_no_args("get_recovered_tables").await)
+}
+
+pub fn get_recovered_tables_with_callback(callback: impl Into<Callback<Vec<IdNamePair>>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_recovered_tables(), callback.into()));
+}
+
+pub async fn restore_recovered_tables() -> Result<(), Error> {
+    unit_from_result(invoke_no_args("restore_recovered_tables").await)
+}
+
+pub fn restore_recovered_tables_with_callback(callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(restore_recovered_tables(), callback.into()));
+}
+
+pub async fn discard_recovered_tables() -> Result<(), Error> {
+    unit_from_result(invoke_no_args("discard_recovered_tables").await)
+}
+
+pub fn discard_recovered_tables_with_callback(callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(discard_recovered_tables(), callback.into()));
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ImportTableFileArgs {
+    path: PathBuf,
+    format: ImportFormat
+}
+
+pub async fn import_table_file(path: PathBuf, format: ImportFormat) -> Result<ImportedFile, Error> {
+    let args = serde_wasm_bindgen::to_value(&ImportTableFileArgs { path, format }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    from_result(invoke("import_table_file", args).await)
+}
+
+pub fn import_table_file_with_error_callback(path: PathBuf, format: ImportFormat, callback: impl Into<Callback<ImportedFile>>, on_error: impl Into<Callback<Error>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_or_error(import_table_file(path, format), callback.into(), on_error.into()));
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ExportTableArgs {
+    id: Uuid,
+    format: ExportFormat,
+    path: PathBuf
+}
+
+pub async fn export_table(id: Uuid, format: ExportFormat, path: PathBuf) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&ExportTableArgs { id, format, path }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    unit_from_result(invoke("export_table", args).await)
+}
+
+pub fn export_table_with_error_callback(id: Uuid, format: ExportFormat, path: PathBuf, callback: impl Into<Callback<()>>, on_error: impl Into<Callback<Error>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_or_error(export_table(id, format, path), callback.into(), on_error.into()));
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ExportRollResultsArgs {
+    title: String,
+    results: Vec<RollResult>,
+    markup: Markup,
+    #[serde(rename = "showCost")]
+    show_cost: bool,
+    path: PathBuf
+}
+
+pub async fn export_roll_results(title: impl Into<String>, results: Vec<RollResult>, markup: Markup, show_cost: bool, path: PathBuf) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&ExportRollResultsArgs { title: title.into(), results, markup, show_cost, path }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    unit_from_result(invoke("export_roll_results", args).await)
+}
+
+pub fn export_roll_results_with_callback(title: impl Into<String>, results: Vec<RollResult>, markup: Markup, show_cost: bool, path: PathBuf, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(export_roll_results(title.into(), results, markup, show_cost, path), callback.into()));
+}
+
+pub async fn get_ledger() -> Result<Ledger, Error> {
+    from_result(invoke_no_args("get_ledger").await)
+}
+
+pub fn get_ledger_with_callback(callback: impl Into<Callback<Ledger>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_ledger(), callback.into()));
+}
+
+pub fn get_ledger_with_error_callback(callback: impl Into<Callback<Ledger>>, on_error: impl Into<Callback<Error>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_or_error(get_ledger(), callback.into(), on_error.into()));
+}
+
+pub async fn start_new_ledger() -> Result<(), Error> {
+    unit_from_result(invoke_no_args("start_new_ledger").await)
+}
+
+pub fn start_new_ledger_with_callback(callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(start_new_ledger(), callback.into()));
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct AddLedgerTransactionArgs {
+    description: String,
+    category: String,
+    amount: Balance
+}
+
+pub async fn add_ledger_transaction(description: impl Into<String>, category: impl Into<String>, amount: Balance) -> Result<Uuid, Error> {
+    let args = serde_wasm_bindgen::to_value(&AddLedgerTransactionArgs { description: description.into(), category: category.into(), amount }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    from_result(invoke("add_ledger_transaction", args).await)
+}
+
+pub fn add_ledger_transaction_with_callback(description: impl Into<String>, category: impl Into<String>, amount: Balance, callback: impl Into<Callback<Uuid>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(add_ledger_transaction(description.into(), category.into(), amount), callback.into()));
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+struct RemoveLedgerTransactionArgs {
+    #[serde(rename = "transactionId")]
+    transaction_id: Uuid
+}
+
+pub async fn remove_ledger_transaction(transaction_id: Uuid) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&RemoveLedgerTransactionArgs { transaction_id }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    unit_from_result(invoke("remove_ledger_transaction", args).await)
+}
+
+pub fn remove_ledger_transaction_with_callback(transaction_id: Uuid, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(remove_ledger_transaction(transaction_id), callback.into()));
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct AddResultsToLedgerArgs {
+    results: Vec<RollResult>,
+    category: String
+}
+
+pub async fn add_results_to_ledger(results: Vec<RollResult>, category: impl Into<String>) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&AddResultsToLedgerArgs { results, category: category.into() }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    unit_from_result(invoke("add_results_to_ledger", args).await)
+}
+
+pub fn add_results_to_ledger_with_callback(results: Vec<RollResult>, category: impl Into<String>, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(add_results_to_ledger(results, category.into()), callback.into()));
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+struct RemoveLedgerItemArgs {
+    index: usize
+}
+
+pub async fn remove_ledger_item(index: usize) -> Result<LedgerItem, Error> {
+    let args = serde_wasm_bindgen::to_value(&RemoveLedgerItemArgs { index }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    from_result(invoke("remove_ledger_item", args).await)
+}
+
+pub fn remove_ledger_item_with_callback(index: usize, callback: impl Into<Callback<LedgerItem>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(remove_ledger_item(index), callback.into()));
+}
+
 #[derive(Debug, Clone, Serialize)]
 struct DialogFilter {
     name: String,
@@ -219,49 +633,259 @@ struct OpenDialogArgs {
     recursive: bool,
 }
 
-pub async fn get_save_table_path() -> Result<Option<PathBuf>, Error> {
+fn table_filters() -> Vec<DialogFilter> {
+    vec![
+        DialogFilter {
+            name: "Table".into(),
+            extensions: vec!["table".into()]
+        }
+    ]
+}
+
+fn bundle_filters() -> Vec<DialogFilter> {
+    vec![
+        DialogFilter {
+            name: "Campaign bundle".into(),
+            extensions: vec!["tablebundle".into()]
+        }
+    ]
+}
+
+async fn get_save_path(filters: Vec<DialogFilter>) -> Result<Option<PathBuf>, Error> {
     let args = SaveDialogArgs {
         title: None,
         default_path: None,
-        filters: Some(vec![
-            DialogFilter {
-                name: "Table".into(),
-                extensions: vec!["table".into()]
-            }
-        ])
+        filters: Some(filters)
     };
 
     let args = serde_wasm_bindgen::to_value(&args).map_err(Error::SerdeWasmBindgenError)?;
     serde_wasm_bindgen::from_value(save(args).await).map_err(Error::SerdeWasmBindgenError)
 }
 
-pub fn get_save_table_path_with_callback(callback: impl Into<Callback<Option<PathBuf>>>) {
-    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_save_table_path(), callback.into()));
-}
-
-pub async fn get_open_table_path() -> Result<Option<PathBuf>, Error> {
+async fn get_open_path(filters: Vec<DialogFilter>) -> Result<Option<PathBuf>, Error> {
     let args = OpenDialogArgs {
         title: None,
         default_path: None,
         directory: false,
         multiple: false,
         recursive: false,
-        filters: Some(vec![
-            DialogFilter {
-                name: "Table".into(),
-                extensions: vec!["table".into()]
-            }
-        ])
+        filters: Some(filters)
+    };
+
+    let args = serde_wasm_bindgen::to_value(&args).map_err(Error::SerdeWasmBindgenError)?;
+    serde_wasm_bindgen::from_value(open(args).await).map_err(Error::SerdeWasmBindgenError)
+}
+
+pub async fn get_library_dir_path() -> Result<Option<PathBuf>, Error> {
+    let args = OpenDialogArgs {
+        title: Some("Choose library folder".into()),
+        default_path: None,
+        directory: true,
+        multiple: false,
+        recursive: true,
+        filters: None
     };
 
     let args = serde_wasm_bindgen::to_value(&args).map_err(Error::SerdeWasmBindgenError)?;
     serde_wasm_bindgen::from_value(open(args).await).map_err(Error::SerdeWasmBindgenError)
 }
 
+pub fn get_library_dir_path_with_callback(callback: impl Into<Callback<Option<PathBuf>>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_library_dir_path(), callback.into()));
+}
+
+pub async fn get_save_table_path() -> Result<Option<PathBuf>, Error> {
+    get_save_path(table_filters()).await
+}
+
+pub fn get_save_table_path_with_callback(callback: impl Into<Callback<Option<PathBuf>>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_save_table_path(), callback.into()));
+}
+
+pub async fn get_open_table_path() -> Result<Option<PathBuf>, Error> {
+    get_open_path(table_filters()).await
+}
+
 pub fn get_open_table_path_with_callback(callback: impl Into<Callback<Option<PathBuf>>>) {
     wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_open_table_path(), callback.into()));
 }
 
+pub async fn get_save_bundle_path() -> Result<Option<PathBuf>, Error> {
+    get_save_path(bundle_filters()).await
+}
+
+pub fn get_save_bundle_path_with_callback(callback: impl Into<Callback<Option<PathBuf>>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_save_bundle_path(), callback.into()));
+}
+
+pub async fn get_open_bundle_path() -> Result<Option<PathBuf>, Error> {
+    get_open_path(bundle_filters()).await
+}
+
+pub fn get_open_bundle_path_with_callback(callback: impl Into<Callback<Option<PathBuf>>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_open_bundle_path(), callback.into()));
+}
+
+pub async fn ask_user(message: impl Into<String>, title: impl Into<String>) -> Result<bool, Error> {
+    serde_wasm_bindgen::from_value(ask(&message.into(), &title.into()).await).map_err(Error::SerdeWasmBindgenError)
+}
+
+pub fn ask_user_with_callback(message: impl Into<String>, title: impl Into<String>, callback: impl Into<Callback<bool>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(ask_user(message.into(), title.into()), callback.into()));
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+struct ImportPathArgs {
+    format: ImportFormat
+}
+
+/// Asks the backend to show the open dialog, which lets it accept only the chosen path for the import.
+pub async fn get_open_import_path(format: ImportFormat) -> Result<Option<PathBuf>, Error> {
+    let args = serde_wasm_bindgen::to_value(&ImportPathArgs { format }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    from_result(invoke("get_open_import_path", args).await)
+}
+
+pub fn get_open_import_path_with_callback(format: ImportFormat, callback: impl Into<Callback<Option<PathBuf>>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_open_import_path(format), callback.into()));
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+struct ExportPathArgs {
+    format: ExportFormat
+}
+
+/// Asks the backend to show the save dialog, which lets it accept only the chosen path for the export.
+pub async fn get_save_export_path(format: ExportFormat) -> Result<Option<PathBuf>, Error> {
+    let args = serde_wasm_bindgen::to_value(&ExportPathArgs { format }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    from_result(invoke("get_save_export_path", args).await)
+}
+
+pub fn get_save_export_path_with_callback(format: ExportFormat, callback: impl Into<Callback<Option<PathBuf>>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_save_export_path(format), callback.into()));
+}
+
+pub async fn write_clipboard_text(text: impl Into<String>) -> Result<(), Error> {
+    unit_from_result(write_text(&text.into()).await)
+}
+
+pub fn write_clipboard_text_with_callback(text: impl Into<String>, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(write_clipboard_text(text.into()), callback.into()));
+}
+
+pub async fn get_settings() -> Result<Settings, Error> {
+    from_result(invoke_no_args("get_settings").await)
+}
+
+pub fn get_settings_with_callback(callback: impl Into<Callback<Settings>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_settings(), callback.into()));
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct UpdateSettingsArgs {
+    settings: Settings
+}
+
+pub async fn update_settings(settings: Settings) -> Result<(), Error> {
+    let args = serde_wasm_bindgen::to_value(&UpdateSettingsArgs { settings }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    unit_from_result(invoke("update_settings", args).await)
+}
+
+pub fn update_settings_with_callback(settings: Settings, callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(update_settings(settings), callback.into()));
+}
+
+pub fn update_settings_with_error_callback(settings: Settings, callback: impl Into<Callback<()>>, on_error: impl Into<Callback<Error>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_or_error(update_settings(settings), callback.into(), on_error.into()));
+}
+
+pub async fn refresh_library() -> Result<usize, Error> {
+    from_result(invoke_no_args("refresh_library").await)
+}
+
+pub fn refresh_library_with_callback(callback: impl Into<Callback<usize>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(refresh_library(), callback.into()));
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SearchLibraryArgs {
+    query: String,
+    tag: Option<String>
+}
+
+pub async fn search_library(query: impl Into<String>, tag: Option<String>) -> Result<Vec<LibrarySearchResult>, Error> {
+    let args = serde_wasm_bindgen::to_value(&SearchLibraryArgs { query: query.into(), tag }).map_err_and_log(Error::SerdeWasmBindgenError)?;
+    from_result(invoke("search_library", args).await)
+}
+
+pub fn search_library_with_callback(query: impl Into<String>, tag: Option<String>, callback: impl Into<Callback<Vec<LibrarySearchResult>>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(search_library(query.into(), tag), callback.into()));
+}
+
+pub async fn get_library_tags() -> Result<Vec<String>, Error> {
+    from_result(invoke_no_args("get_library_tags").await)
+}
+
+pub fn get_library_tags_with_callback(callback: impl Into<Callback<Vec<String>>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_library_tags(), callback.into()));
+}
+
+pub async fn get_recent_files() -> Result<Vec<PathBuf>, Error> {
+    from_result(invoke_no_args("get_recent_files").await)
+}
+
+pub fn get_recent_files_with_callback(callback: impl Into<Callback<Vec<PathBuf>>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(get_recent_files(), callback.into()));
+}
+
+pub async fn clear_recent_files() -> Result<(), Error> {
+    unit_from_result(invoke_no_args("clear_recent_files").await)
+}
+
+pub fn clear_recent_files_with_callback(callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(clear_recent_files(), callback.into()));
+}
+
+pub async fn exit_app() -> Result<(), Error> {
+    unit_from_result(invoke_no_args("exit_app").await)
+}
+
+pub fn exit_app_with_callback(callback: impl Into<Callback<()>>) {
+    wasm_bindgen_futures::spawn_local(emit_callback_if_ok(exit_app(), callback.into()));
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct EventArgs<T> {
+    payload: T
+}
+
+pub fn listen_to_event<T: for<'de> Deserialize<'de> + 'static>(event: impl Into<String>, callback: impl Into<Callback<T>>) {
+    let event = event.into();
+    let callback = callback.into();
+    let handler = Closure::<dyn FnMut(JsValue)>::new(move |value: JsValue| {
+        if let Ok(args) = from_value::<EventArgs<T>>(value) {
+            callback.emit(args.payload);
+        }
+    });
+
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(e) = listen(&event, &handler).await {
+            log::error!("Failed to listen to event {}: {:?}", &event, e);
+        }
+
+        handler.forget();
+    });
+}
+
+pub fn listen_to_keydown(callback: impl Into<Callback<KeyboardEvent>>) {
+    let callback = callback.into();
+    let handler = Closure::<dyn FnMut(KeyboardEvent)>::new(move |event: KeyboardEvent| {
+        callback.emit(event);
+    });
+
+    add_window_event_listener("keydown", &handler);
+    handler.forget();
+}
+
 fn unit_from_result(result: Result<JsValue, JsValue>) -> Result<(), Error> {
     match result {
         Ok(_) => Ok(()),
@@ -306,4 +930,16 @@ extern "C" {
 
     #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "dialog"])]
     async fn open(args: JsValue) -> JsValue;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "event"], catch)]
+    async fn listen(event: &str, handler: &Closure<dyn FnMut(JsValue)>) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_namespace = window, js_name = "addEventListener")]
+    fn add_window_event_listener(event: &str, handler: &Closure<dyn FnMut(KeyboardEvent)>);
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "dialog"])]
+    async fn ask(message: &str, title: &str) -> JsValue;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "clipboard"], js_name = "writeText", catch)]
+    async fn write_text(text: &str) -> Result<JsValue, JsValue>;
 }
\ No newline at end of file
@@ -1,26 +1,97 @@
-use std::sync::Arc;
+use std::{sync::Arc, rc::Rc};
 
-use common_data::{IdNamePair, TableData};
+use common_data::{TableSummary, TableData, TableEvent, TABLE_EVENT};
+use uuid::Uuid;
 use yew::prelude::*;
 
-use crate::glue::{get_tables_with_callback, get_table_with_callback};
+use crate::{glue::{get_tables_with_callback, get_table_with_callback, listen_to_event, undo_table_edit_with_error_callback, redo_table_edit_with_error_callback}, components::error_banner::{ErrorNotice, report_error}};
+
+fn undo_table_edit_of(id: Uuid, notice: UseStateHandle<Option<ErrorNotice>>) {
+    let retry = {
+        let notice = notice.clone();
+        Callback::from(move |_: ()| undo_table_edit_of(id, notice.clone()))
+    };
+
+    undo_table_edit_with_error_callback(id, |_| {}, report_error(notice, retry));
+}
+
+fn redo_table_edit_of(id: Uuid, notice: UseStateHandle<Option<ErrorNotice>>) {
+    let retry = {
+        let notice = notice.clone();
+        Callback::from(move |_: ()| redo_table_edit_of(id, notice.clone()))
+    };
+
+    redo_table_edit_with_error_callback(id, |_| {}, report_error(notice, retry));
+}
+
+/// Counts backend events. Several events can arrive before the next render, so the selected table is refetched after any change
+/// rather than only when it was the last one to change.
+#[derive(Debug, Default, PartialEq)]
+struct BackendChanges {
+    tables: usize,
+    table: usize
+}
+
+impl Reducible for BackendChanges {
+    type Action = TableEvent;
+
+    fn reduce(self: Rc<Self>, event: TableEvent) -> Rc<Self> {
+        match event {
+            TableEvent::Changed(_) => Rc::new(Self {
+                tables: self.tables + 1,
+                table: self.table + 1
+            }),
+            _ => Rc::new(Self {
+                tables: self.tables + 1,
+                table: self.table
+            })
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct UseTablesHandle {
     update_state: UseStateHandle<bool>,
     update_table_state: UseStateHandle<bool>,
-    tables: UseStateHandle<Vec<IdNamePair>>,
+    tables: UseStateHandle<Vec<TableSummary>>,
     table_index: UseStateHandle<Option<usize>>,
-    table_data: UseStateHandle<Option<Arc<TableData>>>
+    table_data: UseStateHandle<Option<Arc<TableData>>>,
+    highlighted_entry: UseStateHandle<Option<Uuid>>
 }
 
 impl UseTablesHandle {
     pub fn set_table_index(&self, index: usize) {
         if index <= self.tables.len() {
-            self.table_index.set(Some(index))
+            self.table_index.set(Some(index));
+            self.highlighted_entry.set(None);
+        }
+    }
+
+    pub fn select_entry(&self, table_id: Uuid, entry_id: Uuid) {
+        if let Some(index) = self.tables.iter().position(|t| t.id() == table_id) {
+            self.table_index.set(Some(index));
+            self.highlighted_entry.set(Some(entry_id));
+        }
+    }
+
+    /// Undoes the last edit to the selected table, reporting a failure in `notice`.
+    pub fn undo(&self, notice: UseStateHandle<Option<ErrorNotice>>) {
+        if let Some(table) = self.get_table_data() {
+            undo_table_edit_of(table.id(), notice);
+        }
+    }
+
+    /// Redoes the last undone edit to the selected table, reporting a failure in `notice`.
+    pub fn redo(&self, notice: UseStateHandle<Option<ErrorNotice>>) {
+        if let Some(table) = self.get_table_data() {
+            redo_table_edit_of(table.id(), notice);
         }
     }
 
+    pub fn get_highlighted_entry(&self) -> Option<Uuid> {
+        *self.highlighted_entry
+    }
+
     pub fn len(&self) -> usize {
         self.tables.len()
     }
@@ -31,9 +102,14 @@ impl UseTablesHandle {
 
     pub fn update_data(&self) {
         self.update_table_state.set(!*self.update_table_state);
+        self.update_state.set(!*self.update_state);
     }
 
-    pub fn tables(&self) -> &[IdNamePair] {
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.tables.iter().any(|t| t.is_modified())
+    }
+
+    pub fn tables(&self) -> &[TableSummary] {
         &*self.tables
     }
 
@@ -53,6 +129,39 @@ pub fn use_tables() -> UseTablesHandle {
     let tables = use_state_eq(|| Vec::new());
     let table_index = use_state_eq(|| None);
     let table_data = use_state_eq(|| None);
+    let highlighted_entry = use_state_eq(|| None);
+    let backend_changes = use_reducer_eq(BackendChanges::default);
+
+    use_effect_with_deps({
+        let dispatcher = backend_changes.dispatcher();
+        move |_| {
+            listen_to_event(TABLE_EVENT, move |event: TableEvent| {
+                log::info!("Received table event {:?}", &event);
+                dispatcher.dispatch(event);
+            });
+        }
+    }, ());
+
+    use_effect_with_deps({
+        let update_state = update_state.clone();
+
+        move |changes: &usize| {
+            if *changes > 0 {
+                update_state.set(!*update_state);
+            }
+        }
+    }, backend_changes.tables);
+
+    use_effect_with_deps({
+        let table_index = table_index.clone();
+        let update_table_state = update_table_state.clone();
+
+        move |changes: &usize| {
+            if *changes > 0 && table_index.is_some() {
+                update_table_state.set(!*update_table_state);
+            }
+        }
+    }, backend_changes.table);
 
     use_effect_with_deps({
         let tables = tables.clone();
@@ -61,15 +170,18 @@ pub fn use_tables() -> UseTablesHandle {
         move |_| {
             let tables = tables.clone();
             let table_index = table_index.clone();
-            get_tables_with_callback(move |updated: Vec<IdNamePair>| {
+            let selected_id = (*table_index).and_then(|index| tables.get(index)).map(|summary: &TableSummary| summary.id());
+
+            get_tables_with_callback(move |updated: Vec<TableSummary>| {
                 log::info!("Retrieved tables:\n{:#?}", &updated);
 
                 let new_index = if updated.len() == 0 {
                     None
                 } else {
-                    match *table_index {
-                        Some(index) if index >= updated.len() => Some(updated.len() - 1),
-                        _ => *table_index
+                    match (selected_id.and_then(|id| updated.iter().position(|t| t.id() == id)), *table_index) {
+                        (Some(index), _) => Some(index),
+                        (None, Some(index)) if index >= updated.len() => Some(updated.len() - 1),
+                        (None, index) => index
                     }
                 };
 
@@ -90,8 +202,8 @@ pub fn use_tables() -> UseTablesHandle {
             match *table_index {
                 None => { table_data.set(None); },
                 Some(index) => {
-                    let pair: &IdNamePair = &tables[index];
-                    let id = pair.id();
+                    let summary: &TableSummary = &tables[index];
+                    let id = summary.id();
 
                     get_table_with_callback(id, move |table| {
                         log::info!("Retrieved table:\n{:#?}", &table);
@@ -118,5 +230,5 @@ pub fn use_tables() -> UseTablesHandle {
         }
     }, update_table_state.clone());
 
-    UseTablesHandle { update_state, update_table_state, tables, table_index, table_data }
+    UseTablesHandle { update_state, update_table_state, tables, table_index, table_data, highlighted_entry }
 }
\ No newline at end of file
@@ -6,6 +6,7 @@ pub mod hooks;
 use std::future::Future;
 
 use app::App;
+use common_data::BackendError;
 use regex::Regex;
 use yew::Callback;
 
@@ -16,6 +17,16 @@ pub(crate) async fn emit_callback_if_ok<T, E: std::fmt::Display, F: Future<Outpu
     }
 }
 
+pub(crate) async fn emit_callback_or_error<T, F: Future<Output = Result<T, Error>>>(future: F, callback: Callback<T>, on_error: Callback<Error>) {
+    match future.await {
+        Ok(result) => callback.emit(result),
+        Err(e) => {
+            log::error!("Failed to emit callback: {}", e);
+            on_error.emit(e);
+        }
+    }
+}
+
 pub(crate) fn try_parse<T, E, F: Fn(&str) -> Result<T, E>>(s: &str, parse: F) -> Option<T> {
     let pattern = Regex::new(r"^[ \n\r\t]*(\d+)[ \n\r\t]*$").ok()?;
     let captures = pattern.captures(s)?;
@@ -38,6 +49,46 @@ impl std::fmt::Display for Error {
     }
 }
 
+impl Error {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::BackendError(e) => e.code(),
+            Self::SerdeWasmBindgenError(_) => "serialization"
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::BackendError(e) => e.is_retryable(),
+            Self::SerdeWasmBindgenError(_) => false
+        }
+    }
+
+    pub fn user_message(&self) -> String {
+        let error = match self {
+            Self::BackendError(e) => e,
+            Self::SerdeWasmBindgenError(e) => return format!("The app received data it did not understand: {}", e)
+        };
+
+        match error {
+            BackendError::NotFound { kind, .. } => format!("The {} could not be found. It may have been closed, moved or deleted.", kind),
+            BackendError::IoError { path: Some(path), message } => format!("Could not access '{}': {}. Check that the file exists and is not in use, then try again.", path.display(), message),
+            BackendError::IoError { path: None, message } => format!("A file operation failed: {}. Try again.", message),
+            BackendError::ParseError { path, line, column, .. } => {
+                let name = path.as_ref().and_then(|p| p.file_name()).map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| "The data".to_string());
+                match (line, column) {
+                    (Some(line), Some(column)) => format!("'{}' is not valid (line {}, column {}). It may be damaged or from a newer version of the app.", name, line, column),
+                    _ => format!("'{}' is not valid. It may be damaged or from a newer version of the app.", name)
+                }
+            },
+            BackendError::ValidationError { field, message } => format!("Please check the {}: {}", field, message),
+            BackendError::RollError { message } => format!("The roll could not be completed: {}", message),
+            BackendError::ArgumentError { arg_name, message } => format!("The request was invalid ({}): {}", arg_name, message),
+            BackendError::InternalError { message } => format!("Something went wrong: {}", message)
+        }
+    }
+}
+
 pub trait MapErrAndLog<T, U> {
     fn map_err_and_log<V, F: Fn(U) -> V + 'static>(self, mapper: F) -> Result<T, V>;
 }